@@ -0,0 +1,120 @@
+pub use crate::bytecode::compile::compile;
+
+use crate::{
+    float::Float,
+    int::IntType,
+    ops::{BinOp, UnOp},
+};
+
+mod compile;
+mod display;
+pub mod error;
+mod serialize;
+pub mod vm;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    pub funs: Vec<Function>,
+    pub strings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    pub name: String,
+    pub params: u16,
+    pub regs: u16,
+    pub code: Vec<Op>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    Const {
+        dest: u16,
+        value: i64,
+    },
+
+    FConst {
+        dest: u16,
+        value: Float,
+    },
+
+    StrConst {
+        dest: u16,
+        string: u32,
+    },
+
+    Move {
+        dest: u16,
+        src: u16,
+    },
+
+    Unary {
+        op: UnOp,
+        dest: u16,
+        src: u16,
+    },
+
+    Binary {
+        op: BinOp,
+        dest: u16,
+        lhs: u16,
+        rhs: u16,
+    },
+
+    StrBinary {
+        op: StrOp,
+        dest: u16,
+        lhs: u16,
+        rhs: u16,
+    },
+
+    Convert {
+        conv: Conversion,
+        dest: u16,
+        src: u16,
+    },
+
+    Jump {
+        target: u32,
+    },
+
+    JumpIf {
+        cond: u16,
+        target: u32,
+    },
+
+    Call {
+        dest: u16,
+        fun: u32,
+        args: Vec<u16>,
+    },
+
+    Return {
+        src: Option<u16>,
+    },
+}
+
+/// Conversions between floats and integers, which unlike casts between integers change the bits
+/// of the value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    FromInt,
+    FromUInt,
+    ToNum,
+    ToInt(IntType),
+}
+
+/// Ops on strings, which registers hold as handles into the VM's string heap, so equality compares
+/// their contents rather than the handles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrOp {
+    Concat,
+    Eq,
+    NotEq,
+}
+
+impl Program {
+    pub fn fun(&self, name: &str) -> Option<usize> {
+        self.funs.iter().position(|fun| fun.name == name)
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    bytecode::{Conversion, Function, Op, Program, StrOp},
+    codegen::{self, error::CodegenError},
+    int::{Int, IntType},
+    mir::{BasicBlock, BlockID, InstrKind, MirFun, MirModule, MirType, Operand, Reg, Term},
+    mir_analysis::reg_types::RegTypes,
+    mir_passes,
+    ops::{BinOp, UnOp},
+};
+
+pub fn compile(module: &MirModule) -> Result<Program, CodegenError> {
+    codegen::check_types(module, "bytecode", |_| true)?;
+
+    let mut module = module.clone();
+    mir_passes::out_of_ssa(&mut module);
+
+    let mut strings = module.strings.clone();
+
+    let empty = module
+        .funs
+        .iter()
+        .any(|fun| fun.return_ty == Some(MirType::Str))
+        .then(|| {
+            strings
+                .iter()
+                .position(String::is_empty)
+                .unwrap_or_else(|| {
+                    strings.push(String::new());
+                    strings.len() - 1
+                })
+        })
+        .map(|idx| u32::try_from(idx).unwrap());
+
+    let indices = module
+        .funs
+        .iter()
+        .zip(0..)
+        .map(|(fun, idx)| (fun.name.as_str(), idx))
+        .collect::<BTreeMap<_, _>>();
+
+    let funs = module
+        .funs
+        .iter()
+        .map(|fun| FunCompiler::new(fun, &indices, RegTypes::new(&module, fun), empty).compile())
+        .collect();
+
+    Ok(Program { funs, strings })
+}
+
+struct FunCompiler<'a> {
+    fun: &'a MirFun,
+    indices: &'a BTreeMap<&'a str, u32>,
+    reg_tys: RegTypes,
+    /// The pooled empty string, which functions returning `str` return when they fall off the end.
+    empty: Option<u32>,
+    regs: BTreeMap<Reg, u16>,
+    temps: u16,
+    code: Vec<Op>,
+}
+
+impl<'a> FunCompiler<'a> {
+    fn new(
+        fun: &'a MirFun,
+        indices: &'a BTreeMap<&'a str, u32>,
+        reg_tys: RegTypes,
+        empty: Option<u32>,
+    ) -> Self {
+        let mut regs = fun
+            .params
+            .iter()
+            .map(|(reg, _)| *reg)
+            .zip(0..)
+            .collect::<BTreeMap<_, _>>();
+
+        for instr in fun.blocks.iter().flat_map(|block| &block.instrs) {
+            let next = u16::try_from(regs.len()).unwrap();
+            regs.entry(instr.dest).or_insert(next);
+        }
+
+        Self {
+            fun,
+            indices,
+            reg_tys,
+            empty,
+            regs,
+            temps: 0,
+            code: Vec::new(),
+        }
+    }
+
+    fn compile(mut self) -> Function {
+        let mut offsets = Vec::new();
+
+        for (idx, block) in self.fun.blocks.iter().enumerate() {
+            offsets.push(u32::try_from(self.code.len()).unwrap());
+
+            let next = self.fun.blocks.get(idx + 1).map(|block| block.id);
+            self.block(block, next);
+        }
+
+        for op in &mut self.code {
+            if let Op::Jump { target } | Op::JumpIf { target, .. } = op {
+                *target = offsets[*target as usize];
+            }
+        }
+
+        Function {
+            name: self.fun.name.clone(),
+            params: u16::try_from(self.fun.params.len()).unwrap(),
+            regs: u16::try_from(self.regs.len()).unwrap() + self.temps,
+            code: self.code,
+        }
+    }
+
+    fn block(&mut self, block: &BasicBlock, next: Option<BlockID>) {
+        for instr in &block.instrs {
+            let dest = self.regs[&instr.dest];
+
+            let op = match &instr.kind {
+                InstrKind::Copy { src } => self.copy(dest, *src),
+                InstrKind::Cast { arg, ty } => self.cast(dest, *arg, ty),
+
+                InstrKind::Unary { op, arg } => match self.reg_tys.operand(*arg) {
+                    Some(MirType::Int(ty)) => self.int_unary(*op, dest, *arg, ty),
+
+                    _ => Op::Unary {
+                        op: *op,
+                        dest,
+                        src: self.operand(*arg, 0),
+                    },
+                },
+
+                InstrKind::Binary {
+                    op: op @ (BinOp::Eq | BinOp::NotEq | BinOp::Concat),
+                    lhs,
+                    rhs,
+                } if self.reg_tys.operand(*lhs) == Some(MirType::Str) => Op::StrBinary {
+                    op: match op {
+                        BinOp::Eq => StrOp::Eq,
+                        BinOp::NotEq => StrOp::NotEq,
+                        _ => StrOp::Concat,
+                    },
+                    dest,
+                    lhs: self.operand(*lhs, 0),
+                    rhs: self.operand(*rhs, 1),
+                },
+
+                InstrKind::Binary { op, lhs, rhs } => self.binary(*op, dest, *lhs, *rhs),
+
+                InstrKind::Call { name, args } => Op::Call {
+                    dest,
+                    fun: self.indices[name.as_str()],
+                    args: args
+                        .iter()
+                        .zip(0..)
+                        .map(|(arg, temp)| self.operand(*arg, temp))
+                        .collect(),
+                },
+            };
+
+            self.code.push(op);
+        }
+
+        match &block.term {
+            Some(Term::Jump { target }) => self.jump(*target, next),
+
+            Some(Term::Branch {
+                cond: Operand::Bool(cond),
+                then_block,
+                else_block,
+            }) => self.jump(if *cond { *then_block } else { *else_block }, next),
+
+            Some(Term::Branch {
+                cond,
+                then_block,
+                else_block,
+            }) => {
+                let cond = self.operand(*cond, 0);
+
+                self.code.push(Op::JumpIf {
+                    cond,
+                    target: block_index(*then_block),
+                });
+
+                self.jump(*else_block, next);
+            }
+
+            Some(Term::Return { value: Some(value) })
+                if self.fun.name == "main"
+                    && self.reg_tys.operand(*value) == Some(MirType::Float) =>
+            {
+                let src = self.operand(*value, 0);
+                let dest = self.temp(0);
+
+                self.code.push(Op::Convert {
+                    conv: Conversion::ToNum,
+                    dest,
+                    src,
+                });
+                self.code.push(Op::Return { src: Some(dest) });
+            }
+
+            Some(Term::Return { value: Some(value) }) => {
+                let src = self.operand(*value, 0);
+                self.code.push(Op::Return { src: Some(src) });
+            }
+
+            Some(Term::Return { value: None }) | None => {
+                let src = self
+                    .empty
+                    .filter(|_| self.fun.return_ty == Some(MirType::Str))
+                    .map(|string| {
+                        let dest = self.temp(0);
+                        self.code.push(Op::StrConst { dest, string });
+                        dest
+                    });
+
+                self.code.push(Op::Return { src });
+            }
+        }
+    }
+
+    fn copy(&self, dest: u16, src: Operand) -> Op {
+        match src {
+            Operand::Reg(src) => Op::Move {
+                dest,
+                src: self.regs[&src],
+            },
+            _ => constant(dest, src),
+        }
+    }
+
+    /// Casts between integers wrap through an addition to the target type, which leaves `num`
+    /// and bools as they are. Casts to and from floats convert the value.
+    fn cast(&mut self, dest: u16, arg: Operand, ty: &MirType) -> Op {
+        let conv = match (self.reg_tys.operand(arg), ty) {
+            (Some(MirType::Float), MirType::Float) | (_, MirType::Bool | MirType::Str) => {
+                return self.copy(dest, arg);
+            }
+
+            (Some(MirType::Float), MirType::Num) => Conversion::ToNum,
+            (Some(MirType::Float), MirType::Int(ty)) => Conversion::ToInt(*ty),
+            (Some(MirType::Int(IntType::U64)), MirType::Float) => Conversion::FromUInt,
+            (_, MirType::Float) => Conversion::FromInt,
+
+            (Some(MirType::Int(..)), MirType::Num) => {
+                return self.binary(BinOp::Add, dest, arg, Operand::Num(0));
+            }
+
+            (_, MirType::Num) => return self.copy(dest, arg),
+
+            (_, MirType::Int(ty)) => {
+                return self.binary(BinOp::IAdd(*ty), dest, arg, Operand::Int(Int::new(0, *ty)));
+            }
+        };
+
+        Op::Convert {
+            conv,
+            dest,
+            src: self.operand(arg, 0),
+        }
+    }
+
+    fn binary(&mut self, op: BinOp, dest: u16, lhs: Operand, rhs: Operand) -> Op {
+        Op::Binary {
+            op,
+            dest,
+            lhs: self.operand(lhs, 0),
+            rhs: self.operand(rhs, 1),
+        }
+    }
+
+    /// Sized integers are kept sign- or zero-extended to 64 bits, so negation goes through a
+    /// subtraction that wraps to the width of the type, and complement flips only its bits.
+    fn int_unary(&mut self, op: UnOp, dest: u16, arg: Operand, ty: IntType) -> Op {
+        let (op, lhs, rhs) = match op {
+            UnOp::Negate => (BinOp::ISub(ty), Operand::Int(Int::new(0, ty)), arg),
+            UnOp::BitNot => (BinOp::IXor(ty), arg, Operand::Int(Int::new(-1, ty))),
+            UnOp::Not | UnOp::FNegate => unreachable!(),
+        };
+
+        self.binary(op, dest, lhs, rhs)
+    }
+
+    fn jump(&mut self, target: BlockID, next: Option<BlockID>) {
+        if next != Some(target) {
+            self.code.push(Op::Jump {
+                target: block_index(target),
+            });
+        }
+    }
+
+    fn operand(&mut self, operand: Operand, temp: u16) -> u16 {
+        if let Operand::Reg(reg) = operand {
+            return self.regs[&reg];
+        }
+
+        let dest = self.temp(temp);
+        self.code.push(constant(dest, operand));
+
+        dest
+    }
+
+    fn temp(&mut self, temp: u16) -> u16 {
+        self.temps = self.temps.max(temp + 1);
+        u16::try_from(self.regs.len()).unwrap() + temp
+    }
+}
+
+fn constant(dest: u16, operand: Operand) -> Op {
+    let value = match operand {
+        Operand::Num(value) => value.into(),
+        Operand::Bool(value) => i64::from(value),
+        Operand::Int(value) => codegen::wide_int_bits(value),
+        Operand::Float(value) => return Op::FConst { dest, value },
+
+        Operand::Str(id) => {
+            return Op::StrConst {
+                dest,
+                string: u32::try_from(id.0).unwrap(),
+            };
+        }
+
+        Operand::Reg(..) => unreachable!(),
+    };
+
+    Op::Const { dest, value }
+}
+
+fn block_index(id: BlockID) -> u32 {
+    u32::try_from(id.0).unwrap()
+}
@@ -0,0 +1,82 @@
+use std::fmt::{self, Display, Formatter};
+
+use itertools::Itertools as _;
+
+use crate::bytecode::{Conversion, Op, Program, StrOp};
+
+impl Display for Program {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (idx, fun) in self.funs.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+
+            writeln!(
+                f,
+                "fun {} (params: {}, regs: {}):",
+                fun.name, fun.params, fun.regs
+            )?;
+
+            for (pc, op) in fun.code.iter().enumerate() {
+                write!(f, "{pc:>4}: ")?;
+
+                if let Op::Call { dest, fun, args } = op {
+                    let args = args.iter().map(|arg| format!("r{arg}")).join(", ");
+                    writeln!(f, "call r{dest}, {}({args})", self.funs[*fun as usize].name)?;
+                } else if let Op::StrConst { dest, string } = op {
+                    writeln!(f, "strconst r{dest}, {:?}", self.strings[*string as usize])?;
+                } else {
+                    writeln!(f, "{op}")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Const { dest, value } => write!(f, "const r{dest}, {value}"),
+            Self::FConst { dest, value } => write!(f, "fconst r{dest}, {value}"),
+            Self::StrConst { dest, string } => write!(f, "strconst r{dest}, #{string}"),
+            Self::Move { dest, src } => write!(f, "move r{dest}, r{src}"),
+            Self::Unary { op, dest, src } => write!(f, "{op} r{dest}, r{src}"),
+            Self::Binary { op, dest, lhs, rhs } => write!(f, "{op} r{dest}, r{lhs}, r{rhs}"),
+            Self::StrBinary { op, dest, lhs, rhs } => write!(f, "{op} r{dest}, r{lhs}, r{rhs}"),
+            Self::Convert { conv, dest, src } => write!(f, "{conv} r{dest}, r{src}"),
+            Self::Jump { target } => write!(f, "jump {target}"),
+            Self::JumpIf { cond, target } => write!(f, "jump_if r{cond}, {target}"),
+
+            Self::Call { dest, fun, args } => {
+                let args = args.iter().map(|arg| format!("r{arg}")).join(", ");
+                write!(f, "call r{dest}, #{fun}({args})")
+            }
+
+            Self::Return { src: Some(src) } => write!(f, "ret r{src}"),
+            Self::Return { src: None } => write!(f, "ret"),
+        }
+    }
+}
+
+impl Display for Conversion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FromInt => write!(f, "itof"),
+            Self::FromUInt => write!(f, "utof"),
+            Self::ToNum => write!(f, "ftoi"),
+            Self::ToInt(ty) => write!(f, "ftoi.{ty}"),
+        }
+    }
+}
+
+impl Display for StrOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Concat => write!(f, "concat"),
+            Self::Eq => write!(f, "streq"),
+            Self::NotEq => write!(f, "strneq"),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::string::FromUtf8Error;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BytecodeError {
+    #[error("Invalid magic number, not a .langc file.")]
+    InvalidMagic,
+
+    #[error("Unsupported bytecode version: expected {expected}, found {found}.")]
+    UnsupportedVersion { expected: u16, found: u16 },
+
+    #[error("Unexpected end of bytecode.")]
+    UnexpectedEnd,
+
+    #[error("Invalid opcode: {opcode:#04x}.")]
+    InvalidOpcode { opcode: u8 },
+
+    #[error("Unexpected trailing bytes at offset {offset}.")]
+    TrailingBytes { offset: usize },
+
+    #[error("Invalid function name: {err}.")]
+    InvalidName {
+        #[source]
+        err: FromUtf8Error,
+    },
+
+    #[error("Invalid string constant: {err}.")]
+    InvalidString {
+        #[source]
+        err: FromUtf8Error,
+    },
+
+    #[error("Malformed function: {name}.")]
+    MalformedFunction { name: String },
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum VmError {
+    #[error("Undefined function: {name}.")]
+    UndefinedFunction { name: String },
+
+    #[error("Invalid number of args for function {name}: expected {expected}, found {found}.")]
+    InvalidArgs {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Division by zero in function {name}.")]
+    DivisionByZero { name: String },
+
+    #[error("Stack overflow in function {name}.")]
+    StackOverflow { name: String },
+
+    #[error("Invalid string in function {name}.")]
+    InvalidString { name: String },
+}
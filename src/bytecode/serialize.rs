@@ -0,0 +1,467 @@
+use crate::{
+    bytecode::{Conversion, Function, Op, Program, StrOp, error::BytecodeError},
+    float::Float,
+    int::IntType,
+    ops::{BinOp, UnOp},
+};
+
+const MAGIC: &[u8; 5] = b"LANGC";
+const VERSION: u16 = 5;
+
+const CONST: u8 = 0x01;
+const MOVE: u8 = 0x02;
+const FCONST: u8 = 0x03;
+const STR_CONST: u8 = 0x04;
+const UNARY: u8 = 0x10;
+const BINARY: u8 = 0x20;
+const JUMP: u8 = 0x40;
+const JUMP_IF: u8 = 0x41;
+const CALL: u8 = 0x50;
+const RETURN: u8 = 0x60;
+const RETURN_VOID: u8 = 0x61;
+const FLOAT_BINARY: u8 = 0x70;
+const INT_BINARY: u8 = 0x80;
+const CONVERT: u8 = 0xe0;
+const STR_BINARY: u8 = 0xf0;
+
+const UNARY_OPS: [UnOp; 4] = [UnOp::Negate, UnOp::Not, UnOp::BitNot, UnOp::FNegate];
+
+const BINARY_OPS: [BinOp; 22] = [
+    BinOp::Add,
+    BinOp::Sub,
+    BinOp::Mul,
+    BinOp::Div,
+    BinOp::Eq,
+    BinOp::NotEq,
+    BinOp::Lesser,
+    BinOp::LesserEq,
+    BinOp::Greater,
+    BinOp::GreaterEq,
+    BinOp::And,
+    BinOp::Or,
+    BinOp::Rem,
+    BinOp::BitAnd,
+    BinOp::BitOr,
+    BinOp::BitXor,
+    BinOp::Shl,
+    BinOp::Shr,
+    BinOp::ULesser,
+    BinOp::ULesserEq,
+    BinOp::UGreater,
+    BinOp::UGreaterEq,
+];
+
+const FLOAT_OPS: [BinOp; 10] = [
+    BinOp::FAdd,
+    BinOp::FSub,
+    BinOp::FMul,
+    BinOp::FDiv,
+    BinOp::FEq,
+    BinOp::FNotEq,
+    BinOp::FLesser,
+    BinOp::FLesserEq,
+    BinOp::FGreater,
+    BinOp::FGreaterEq,
+];
+
+const STR_OPS: [StrOp; 3] = [StrOp::Concat, StrOp::Eq, StrOp::NotEq];
+
+const INT_TYPES: [IntType; 7] = [
+    IntType::I8,
+    IntType::I16,
+    IntType::U8,
+    IntType::U16,
+    IntType::U32,
+    IntType::I64,
+    IntType::U64,
+];
+
+const INT_OPS: [fn(IntType) -> BinOp; 13] = [
+    BinOp::IAdd,
+    BinOp::ISub,
+    BinOp::IMul,
+    BinOp::IDiv,
+    BinOp::UDiv,
+    BinOp::IRem,
+    BinOp::URem,
+    BinOp::IAnd,
+    BinOp::IOr,
+    BinOp::IXor,
+    BinOp::IShl,
+    BinOp::IShr,
+    BinOp::UShr,
+];
+
+type Result<T> = std::result::Result<T, BytecodeError>;
+
+impl Program {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.extend(VERSION.to_le_bytes());
+        out.extend(len_u32(self.strings.len()).to_le_bytes());
+
+        for string in &self.strings {
+            out.extend(len_u32(string.len()).to_le_bytes());
+            out.extend(string.as_bytes());
+        }
+
+        out.extend(len_u32(self.funs.len()).to_le_bytes());
+
+        for fun in &self.funs {
+            out.extend(u16::try_from(fun.name.len()).unwrap().to_le_bytes());
+            out.extend(fun.name.as_bytes());
+            out.extend(fun.params.to_le_bytes());
+            out.extend(fun.regs.to_le_bytes());
+            out.extend(len_u32(fun.code.len()).to_le_bytes());
+
+            for op in &fun.code {
+                write_op(&mut out, op);
+            }
+        }
+
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader { bytes, pos: 0 };
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(BytecodeError::InvalidMagic);
+        }
+
+        let version = reader.u16()?;
+
+        if version != VERSION {
+            return Err(BytecodeError::UnsupportedVersion {
+                expected: VERSION,
+                found: version,
+            });
+        }
+
+        let count = reader.u32()?;
+        let mut strings = Vec::new();
+
+        for _ in 0..count {
+            let len = reader.u32()? as usize;
+            let string = String::from_utf8(reader.take(len)?.to_vec())
+                .map_err(|err| BytecodeError::InvalidString { err })?;
+
+            strings.push(string);
+        }
+
+        let count = reader.u32()?;
+        let mut funs = Vec::new();
+
+        for _ in 0..count {
+            let len = usize::from(reader.u16()?);
+            let name = String::from_utf8(reader.take(len)?.to_vec())
+                .map_err(|err| BytecodeError::InvalidName { err })?;
+
+            let params = reader.u16()?;
+            let regs = reader.u16()?;
+            let len = reader.u32()?;
+
+            let code = (0..len).map(|_| reader.op()).collect::<Result<Vec<_>>>()?;
+
+            funs.push(Function {
+                name,
+                params,
+                regs,
+                code,
+            });
+        }
+
+        if reader.pos != bytes.len() {
+            return Err(BytecodeError::TrailingBytes { offset: reader.pos });
+        }
+
+        let program = Self { funs, strings };
+
+        for fun in &program.funs {
+            if !program.is_well_formed(fun) {
+                return Err(BytecodeError::MalformedFunction {
+                    name: fun.name.clone(),
+                });
+            }
+        }
+
+        Ok(program)
+    }
+
+    fn is_well_formed(&self, fun: &Function) -> bool {
+        let reg = |reg: &u16| *reg < fun.regs;
+        let target = |target: &u32| (*target as usize) < fun.code.len();
+
+        let ops = fun.code.iter().all(|op| match op {
+            Op::Const { dest, .. } | Op::FConst { dest, .. } => reg(dest),
+            Op::Move { dest, src }
+            | Op::Unary { dest, src, .. }
+            | Op::Convert { dest, src, .. } => reg(dest) && reg(src),
+            Op::StrConst { dest, string } => reg(dest) && (*string as usize) < self.strings.len(),
+            Op::Binary { dest, lhs, rhs, .. } | Op::StrBinary { dest, lhs, rhs, .. } => {
+                reg(dest) && reg(lhs) && reg(rhs)
+            }
+            Op::Jump { target: to } => target(to),
+            Op::JumpIf { cond, target: to } => reg(cond) && target(to),
+            Op::Return { src } => src.as_ref().is_none_or(reg),
+
+            Op::Call { dest, fun, args } => {
+                reg(dest)
+                    && args.iter().all(reg)
+                    && self
+                        .funs
+                        .get(*fun as usize)
+                        .is_some_and(|callee| usize::from(callee.params) == args.len())
+            }
+        });
+
+        fun.params <= fun.regs
+            && matches!(fun.code.last(), Some(Op::Return { .. } | Op::Jump { .. }))
+            && ops
+    }
+}
+
+fn write_op(out: &mut Vec<u8>, op: &Op) {
+    match op {
+        Op::Const { dest, value } => {
+            out.push(CONST);
+            out.extend(dest.to_le_bytes());
+            out.extend(value.to_le_bytes());
+        }
+
+        Op::FConst { dest, value } => {
+            out.push(FCONST);
+            out.extend(dest.to_le_bytes());
+            out.extend(value.value().to_le_bytes());
+        }
+
+        Op::StrConst { dest, string } => {
+            out.push(STR_CONST);
+            out.extend(dest.to_le_bytes());
+            out.extend(string.to_le_bytes());
+        }
+
+        Op::Move { dest, src } => {
+            out.push(MOVE);
+            out.extend(dest.to_le_bytes());
+            out.extend(src.to_le_bytes());
+        }
+
+        Op::Unary { op, dest, src } => {
+            out.push(UNARY + opcode_offset(&UNARY_OPS, op));
+            out.extend(dest.to_le_bytes());
+            out.extend(src.to_le_bytes());
+        }
+
+        Op::Binary { op, dest, lhs, rhs } => {
+            if BINARY_OPS.contains(op) {
+                out.push(BINARY + opcode_offset(&BINARY_OPS, op));
+            } else if FLOAT_OPS.contains(op) {
+                out.push(FLOAT_BINARY + opcode_offset(&FLOAT_OPS, op));
+            } else {
+                out.push(INT_BINARY + opcode_offset(&int_binary_ops(), op));
+            }
+
+            out.extend(dest.to_le_bytes());
+            out.extend(lhs.to_le_bytes());
+            out.extend(rhs.to_le_bytes());
+        }
+
+        Op::StrBinary { op, dest, lhs, rhs } => {
+            out.push(STR_BINARY + opcode_offset(&STR_OPS, op));
+            out.extend(dest.to_le_bytes());
+            out.extend(lhs.to_le_bytes());
+            out.extend(rhs.to_le_bytes());
+        }
+
+        Op::Convert { conv, dest, src } => {
+            out.push(CONVERT + opcode_offset(&conversions(), conv));
+            out.extend(dest.to_le_bytes());
+            out.extend(src.to_le_bytes());
+        }
+
+        Op::Jump { target } => {
+            out.push(JUMP);
+            out.extend(target.to_le_bytes());
+        }
+
+        Op::JumpIf { cond, target } => {
+            out.push(JUMP_IF);
+            out.extend(cond.to_le_bytes());
+            out.extend(target.to_le_bytes());
+        }
+
+        Op::Call { dest, fun, args } => {
+            out.push(CALL);
+            out.extend(dest.to_le_bytes());
+            out.extend(fun.to_le_bytes());
+            out.push(u8::try_from(args.len()).unwrap());
+
+            for arg in args {
+                out.extend(arg.to_le_bytes());
+            }
+        }
+
+        Op::Return { src: Some(src) } => {
+            out.push(RETURN);
+            out.extend(src.to_le_bytes());
+        }
+
+        Op::Return { src: None } => out.push(RETURN_VOID),
+    }
+}
+
+/// Every sized integer op, once per type, in opcode order.
+fn int_binary_ops() -> Vec<BinOp> {
+    INT_TYPES
+        .iter()
+        .flat_map(|ty| INT_OPS.map(|op| op(*ty)))
+        .collect()
+}
+
+/// The float conversions, with one conversion to each sized integer type, in opcode order.
+fn conversions() -> Vec<Conversion> {
+    [Conversion::FromInt, Conversion::FromUInt, Conversion::ToNum]
+        .into_iter()
+        .chain(INT_TYPES.map(Conversion::ToInt))
+        .collect()
+}
+
+fn opcode_offset<T: PartialEq>(ops: &[T], op: &T) -> u8 {
+    u8::try_from(ops.iter().position(|other| other == op).unwrap()).unwrap()
+}
+
+fn len_u32(len: usize) -> u32 {
+    u32::try_from(len).unwrap()
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or(BytecodeError::UnexpectedEnd)?;
+
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn f64(&mut self) -> Result<f64> {
+        Ok(f64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn op(&mut self) -> Result<Op> {
+        let opcode = self.u8()?;
+
+        let op = match opcode {
+            CONST => Op::Const {
+                dest: self.u16()?,
+                value: self.i64()?,
+            },
+
+            FCONST => Op::FConst {
+                dest: self.u16()?,
+                value: Float::new(self.f64()?),
+            },
+
+            STR_CONST => Op::StrConst {
+                dest: self.u16()?,
+                string: self.u32()?,
+            },
+
+            MOVE => Op::Move {
+                dest: self.u16()?,
+                src: self.u16()?,
+            },
+
+            JUMP => Op::Jump {
+                target: self.u32()?,
+            },
+
+            JUMP_IF => Op::JumpIf {
+                cond: self.u16()?,
+                target: self.u32()?,
+            },
+
+            CALL => {
+                let dest = self.u16()?;
+                let fun = self.u32()?;
+                let count = self.u8()?;
+                let args = (0..count).map(|_| self.u16()).collect::<Result<_>>()?;
+
+                Op::Call { dest, fun, args }
+            }
+
+            RETURN => Op::Return {
+                src: Some(self.u16()?),
+            },
+
+            RETURN_VOID => Op::Return { src: None },
+
+            _ => {
+                if let Some(op) = lookup(&UNARY_OPS, opcode, UNARY) {
+                    Op::Unary {
+                        op,
+                        dest: self.u16()?,
+                        src: self.u16()?,
+                    }
+                } else if let Some(op) = lookup(&BINARY_OPS, opcode, BINARY)
+                    .or_else(|| lookup(&FLOAT_OPS, opcode, FLOAT_BINARY))
+                    .or_else(|| lookup(&int_binary_ops(), opcode, INT_BINARY))
+                {
+                    Op::Binary {
+                        op,
+                        dest: self.u16()?,
+                        lhs: self.u16()?,
+                        rhs: self.u16()?,
+                    }
+                } else if let Some(op) = lookup(&STR_OPS, opcode, STR_BINARY) {
+                    Op::StrBinary {
+                        op,
+                        dest: self.u16()?,
+                        lhs: self.u16()?,
+                        rhs: self.u16()?,
+                    }
+                } else if let Some(conv) = lookup(&conversions(), opcode, CONVERT) {
+                    Op::Convert {
+                        conv,
+                        dest: self.u16()?,
+                        src: self.u16()?,
+                    }
+                } else {
+                    return Err(BytecodeError::InvalidOpcode { opcode });
+                }
+            }
+        };
+
+        Ok(op)
+    }
+}
+
+fn lookup<T: Copy>(ops: &[T], opcode: u8, base: u8) -> Option<T> {
+    opcode
+        .checked_sub(base)
+        .and_then(|offset| ops.get(usize::from(offset)))
+        .copied()
+}
@@ -0,0 +1,326 @@
+use std::cmp::Ordering;
+
+use crate::{
+    bytecode::{Conversion, Op, Program, StrOp, error::VmError},
+    codegen,
+    int::{Int, IntType},
+    ops::{BinOp, UnOp},
+};
+
+const MAX_FRAMES: usize = 1 << 16;
+
+type Result<T> = std::result::Result<T, VmError>;
+
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    fun: usize,
+    pc: usize,
+    base: usize,
+    dest: u16,
+}
+
+pub struct Vm<'a> {
+    program: &'a Program,
+    regs: Vec<i64>,
+    frames: Vec<Frame>,
+    /// Starts out as the program's string pool. Concatenation appends, and nothing is freed until
+    /// the next call.
+    strings: Vec<String>,
+}
+
+impl<'a> Vm<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            regs: Vec::new(),
+            frames: Vec::new(),
+            strings: Vec::new(),
+        }
+    }
+
+    /// Args and the result are held the way registers hold them: `num` and sized integers sign- or
+    /// zero-extended to 64 bits, bools as 0 or 1, floats as the bits of an `f64`, and strings as
+    /// handles that [`Vm::str`] resolves.
+    pub fn call(&mut self, name: &str, args: &[i64]) -> Result<Option<i64>> {
+        let fun = self
+            .program
+            .fun(name)
+            .ok_or_else(|| VmError::UndefinedFunction { name: name.into() })?;
+
+        let params = usize::from(self.program.funs[fun].params);
+
+        if params != args.len() {
+            return Err(VmError::InvalidArgs {
+                name: name.into(),
+                expected: params,
+                found: args.len(),
+            });
+        }
+
+        self.regs.clear();
+        self.frames.clear();
+        self.strings.clone_from(&self.program.strings);
+        self.push_frame(fun, 0)?;
+        self.regs[..args.len()].copy_from_slice(args);
+
+        self.run()
+    }
+
+    /// The string a handle from the last call refers to.
+    pub fn str(&self, value: i64) -> Option<&str> {
+        let idx = usize::try_from(value).ok()?;
+        self.strings.get(idx).map(String::as_str)
+    }
+
+    fn run(&mut self) -> Result<Option<i64>> {
+        let program = self.program;
+
+        loop {
+            let frame = self.frames.last_mut().unwrap();
+            let fun = &program.funs[frame.fun];
+            let op = &fun.code[frame.pc];
+            let base = frame.base;
+
+            frame.pc += 1;
+
+            match op {
+                Op::Const { dest, value } => self.set(base, *dest, *value),
+                Op::FConst { dest, value } => self.set(base, *dest, from_float(value.value())),
+                Op::StrConst { dest, string } => self.set(base, *dest, (*string).into()),
+                Op::Move { dest, src } => self.set(base, *dest, self.get(base, *src)),
+
+                Op::Unary { op, dest, src } => {
+                    let value = self.get(base, *src);
+
+                    let result = match op {
+                        UnOp::Negate => num(value).wrapping_neg().into(),
+                        UnOp::Not => i64::from(value == 0),
+                        UnOp::BitNot => !value,
+                        UnOp::FNegate => from_float(-float(value)),
+                    };
+
+                    self.set(base, *dest, result);
+                }
+
+                Op::Binary { op, dest, lhs, rhs } => {
+                    let (lhs, rhs) = (self.get(base, *lhs), self.get(base, *rhs));
+
+                    let result = binary(*op, lhs, rhs).ok_or_else(|| VmError::DivisionByZero {
+                        name: fun.name.clone(),
+                    })?;
+
+                    self.set(base, *dest, result);
+                }
+
+                Op::StrBinary { op, dest, lhs, rhs } => {
+                    let (lhs, rhs) = (self.get(base, *lhs), self.get(base, *rhs));
+
+                    let result =
+                        self.str_binary(*op, lhs, rhs)
+                            .ok_or_else(|| VmError::InvalidString {
+                                name: fun.name.clone(),
+                            })?;
+
+                    self.set(base, *dest, result);
+                }
+
+                Op::Convert { conv, dest, src } => {
+                    let value = convert(*conv, self.get(base, *src));
+                    self.set(base, *dest, value);
+                }
+
+                Op::Jump { target } => self.jump(*target),
+
+                Op::JumpIf { cond, target } => {
+                    if self.get(base, *cond) != 0 {
+                        self.jump(*target);
+                    }
+                }
+
+                Op::Call { dest, fun, args } => {
+                    let args = args
+                        .iter()
+                        .map(|arg| self.get(base, *arg))
+                        .collect::<Vec<_>>();
+
+                    let callee = self.push_frame(*fun as usize, *dest)?;
+                    self.regs[callee..callee + args.len()].copy_from_slice(&args);
+                }
+
+                Op::Return { src } => {
+                    let value = src.map(|src| self.get(base, src));
+                    let frame = self.frames.pop().unwrap();
+
+                    self.regs.truncate(frame.base);
+
+                    let Some(&caller) = self.frames.last() else {
+                        return Ok(value);
+                    };
+
+                    if let Some(value) = value {
+                        self.set(caller.base, frame.dest, value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns `None` if either operand isn't a string handle, which only malformed bytecode
+    /// produces.
+    fn str_binary(&mut self, op: StrOp, lhs: i64, rhs: i64) -> Option<i64> {
+        let (a, b) = (self.str(lhs)?, self.str(rhs)?);
+
+        Some(match op {
+            StrOp::Eq => i64::from(a == b),
+            StrOp::NotEq => i64::from(a != b),
+
+            StrOp::Concat => {
+                let string = [a, b].concat();
+                self.strings.push(string);
+                i64::try_from(self.strings.len() - 1).unwrap()
+            }
+        })
+    }
+
+    fn push_frame(&mut self, fun: usize, dest: u16) -> Result<usize> {
+        if self.frames.len() >= MAX_FRAMES {
+            return Err(VmError::StackOverflow {
+                name: self.program.funs[fun].name.clone(),
+            });
+        }
+
+        let base = self.regs.len();
+
+        self.regs
+            .resize(base + usize::from(self.program.funs[fun].regs), 0);
+
+        self.frames.push(Frame {
+            fun,
+            pc: 0,
+            base,
+            dest,
+        });
+
+        Ok(base)
+    }
+
+    fn jump(&mut self, target: u32) {
+        self.frames.last_mut().unwrap().pc = target as usize;
+    }
+
+    fn get(&self, base: usize, reg: u16) -> i64 {
+        self.regs[base + usize::from(reg)]
+    }
+
+    fn set(&mut self, base: usize, reg: u16, value: i64) {
+        self.regs[base + usize::from(reg)] = value;
+    }
+}
+
+/// Evaluates a binary op, or returns `None` when dividing by zero. `num` ops wrap to 32 bits, and
+/// sized integers are kept sign- or zero-extended to 64 bits and wrapped back to their width after
+/// each op, and floats are kept as the bits of an `f64`.
+fn binary(op: BinOp, lhs: i64, rhs: i64) -> Option<i64> {
+    let (a, b) = (num(lhs), num(rhs));
+
+    Some(match op {
+        BinOp::Add => a.wrapping_add(b).into(),
+        BinOp::Sub => a.wrapping_sub(b).into(),
+        BinOp::Mul => a.wrapping_mul(b).into(),
+        BinOp::Div
+        | BinOp::Rem
+        | BinOp::IDiv(..)
+        | BinOp::UDiv(..)
+        | BinOp::IRem(..)
+        | BinOp::URem(..)
+            if rhs == 0 =>
+        {
+            return None;
+        }
+        BinOp::Div => a.wrapping_div(b).into(),
+        BinOp::Rem => a.wrapping_rem(b).into(),
+        BinOp::And | BinOp::BitAnd | BinOp::IAnd(..) => lhs & rhs,
+        BinOp::Or | BinOp::BitOr | BinOp::IOr(..) => lhs | rhs,
+        BinOp::BitXor | BinOp::IXor(..) => lhs ^ rhs,
+        BinOp::Shl => a.wrapping_shl(b.cast_unsigned()).into(),
+        BinOp::Shr => a.wrapping_shr(b.cast_unsigned()).into(),
+        BinOp::IAdd(ty) => wrap(widen(lhs, ty) + widen(rhs, ty), ty),
+        BinOp::ISub(ty) => wrap(widen(lhs, ty) - widen(rhs, ty), ty),
+        BinOp::IMul(ty) => wrap(widen(lhs, ty) * widen(rhs, ty), ty),
+        BinOp::IDiv(ty) | BinOp::UDiv(ty) => wrap(widen(lhs, ty) / widen(rhs, ty), ty),
+        BinOp::IRem(ty) | BinOp::URem(ty) => wrap(widen(lhs, ty) % widen(rhs, ty), ty),
+        BinOp::IShl(ty) => wrap(widen(lhs, ty) << shift_amount(rhs, ty), ty),
+        BinOp::IShr(ty) | BinOp::UShr(ty) => wrap(widen(lhs, ty) >> shift_amount(rhs, ty), ty),
+        BinOp::Eq => i64::from(lhs == rhs),
+        BinOp::NotEq => i64::from(lhs != rhs),
+        BinOp::Lesser => i64::from(lhs < rhs),
+        BinOp::LesserEq => i64::from(lhs <= rhs),
+        BinOp::Greater => i64::from(lhs > rhs),
+        BinOp::GreaterEq => i64::from(lhs >= rhs),
+        BinOp::ULesser => i64::from(lhs.cast_unsigned() < rhs.cast_unsigned()),
+        BinOp::ULesserEq => i64::from(lhs.cast_unsigned() <= rhs.cast_unsigned()),
+        BinOp::UGreater => i64::from(lhs.cast_unsigned() > rhs.cast_unsigned()),
+        BinOp::UGreaterEq => i64::from(lhs.cast_unsigned() >= rhs.cast_unsigned()),
+        BinOp::FAdd => from_float(float(lhs) + float(rhs)),
+        BinOp::FSub => from_float(float(lhs) - float(rhs)),
+        BinOp::FMul => from_float(float(lhs) * float(rhs)),
+        BinOp::FDiv => from_float(float(lhs) / float(rhs)),
+        BinOp::FEq => i64::from(float(lhs).partial_cmp(&float(rhs)) == Some(Ordering::Equal)),
+        BinOp::FNotEq => i64::from(float(lhs).partial_cmp(&float(rhs)) != Some(Ordering::Equal)),
+        BinOp::FLesser => i64::from(float(lhs) < float(rhs)),
+        BinOp::FLesserEq => i64::from(float(lhs) <= float(rhs)),
+        BinOp::FGreater => i64::from(float(lhs) > float(rhs)),
+        BinOp::FGreaterEq => i64::from(float(lhs) >= float(rhs)),
+        BinOp::Concat => unreachable!(),
+    })
+}
+
+/// Float to integer conversions saturate at the bounds of the target type and map NaN to 0, like
+/// `as` in Rust.
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    reason = "conversions follow the semantics of `as`"
+)]
+fn convert(conv: Conversion, value: i64) -> i64 {
+    match conv {
+        Conversion::FromInt => from_float(value as f64),
+        Conversion::FromUInt => from_float(value.cast_unsigned() as f64),
+        Conversion::ToNum => (float(value) as i32).into(),
+        Conversion::ToInt(ty) => wrap(
+            (float(value) as i128).clamp(ty.min_value(), ty.max_value()),
+            ty,
+        ),
+    }
+}
+
+fn float(value: i64) -> f64 {
+    f64::from_bits(value.cast_unsigned())
+}
+
+fn from_float(value: f64) -> i64 {
+    value.to_bits().cast_signed()
+}
+
+/// The `num` a register holds. Casts to `num` go through an addition, so wide integers are
+/// truncated here.
+#[expect(clippy::cast_possible_truncation, reason = "`num` wraps to 32 bits")]
+fn num(value: i64) -> i32 {
+    value as i32
+}
+
+fn widen(value: i64, ty: IntType) -> i128 {
+    if ty.is_signed() {
+        value.into()
+    } else {
+        value.cast_unsigned().into()
+    }
+}
+
+fn wrap(value: i128, ty: IntType) -> i64 {
+    codegen::wide_int_bits(Int::new(value, ty))
+}
+
+fn shift_amount(rhs: i64, ty: IntType) -> u32 {
+    num(rhs).cast_unsigned() & (ty.bits() - 1)
+}
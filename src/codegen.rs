@@ -0,0 +1,122 @@
+use crate::{
+    codegen::error::CodegenError,
+    int::Int,
+    mir::{MirFun, MirModule, MirType, Operand, Term},
+    mir_analysis::reg_types::RegTypes,
+};
+
+pub mod c;
+pub mod elf;
+pub mod error;
+pub mod llvm;
+pub mod wasm;
+pub mod x86_64;
+
+/// Rejects modules with values of a type the backend can't represent, so lowering never meets one.
+/// `main` must also return something that converts to an exit code.
+pub(crate) fn check_types(
+    module: &MirModule,
+    backend: &'static str,
+    supports: impl Fn(&MirType) -> bool,
+) -> Result<(), CodegenError> {
+    for fun in &module.funs {
+        if fun.name == "main"
+            && let Some(ty @ MirType::Str) = &fun.return_ty
+        {
+            return Err(CodegenError::UnsupportedMainType {
+                backend,
+                ty: ty.clone(),
+            });
+        }
+
+        check_fun_types(module, fun, backend, &supports)?;
+    }
+
+    Ok(())
+}
+
+/// Rejects a function with a param, register or constant of a type the backend can't represent.
+pub(crate) fn check_fun_types(
+    module: &MirModule,
+    fun: &MirFun,
+    backend: &'static str,
+    supports: impl Fn(&MirType) -> bool,
+) -> Result<(), CodegenError> {
+    let reg_tys = RegTypes::new(module, fun);
+
+    let consts = fun.blocks.iter().flat_map(|block| {
+        let phis = block
+            .phis
+            .iter()
+            .flat_map(|phi| &phi.srcs)
+            .map(|(_, src)| *src);
+        let instrs = block.instrs.iter().flat_map(|instr| instr.kind.operands());
+        let term = block.term.iter().flat_map(Term::operands);
+
+        phis.chain(instrs)
+            .chain(term)
+            .filter(Operand::is_const)
+            .filter_map(|operand| reg_tys.operand(operand))
+            .collect::<Vec<_>>()
+    });
+
+    let tys = fun
+        .params
+        .iter()
+        .map(|(_, ty)| ty.clone())
+        .chain(fun.return_ty.clone())
+        .chain(reg_tys.values().cloned())
+        .chain(consts);
+
+    for ty in tys {
+        if !supports(&ty) {
+            return Err(CodegenError::UnsupportedType { backend, ty });
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the module needs the string runtime. Every string starts out as a constant or arrives
+/// through a parameter or return value, so there's no need to look at registers.
+pub(crate) fn uses_strings(module: &MirModule) -> bool {
+    !module.strings.is_empty()
+        || module.funs.iter().any(|fun| {
+            fun.return_ty == Some(MirType::Str)
+                || fun.params.iter().any(|(_, ty)| *ty == MirType::Str)
+        })
+}
+
+/// Whether `ty` is held as an integer: `num`, bools and sized integers of any width.
+pub(crate) fn is_integral(ty: &MirType) -> bool {
+    !matches!(ty, MirType::Float | MirType::Str)
+}
+
+/// Whether a backend that keeps every value in 32 bits can represent `ty`.
+pub(crate) fn fits_32_bits(ty: &MirType) -> bool {
+    match ty {
+        MirType::Num | MirType::Bool => true,
+        MirType::Int(ty) => ty.bits() <= 32,
+        MirType::Float | MirType::Str => false,
+    }
+}
+
+/// The bits of a sized integer that fits in 32 bits, the way 32-bit backends keep it: sign-extended
+/// if the type is signed and zero-extended if not.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "the value fits in 32 bits, and `u32` values keep their bit pattern"
+)]
+pub(crate) fn int_bits(value: Int) -> i32 {
+    value.value() as i32
+}
+
+/// The bits of a sized integer the way 64-bit backends keep it: extended like [`int_bits`], with
+/// `u64` values keeping their bit pattern.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "the value fits in 64 bits, and `u64` values keep their bit pattern"
+)]
+pub(crate) fn wide_int_bits(value: Int) -> i64 {
+    value.value() as i64
+}
@@ -0,0 +1,532 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use itertools::Itertools as _;
+
+use crate::{
+    codegen::{self, error::CodegenError},
+    float::Float,
+    int::{Int, IntType},
+    mir::{BasicBlock, Instr, InstrKind, MirFun, MirModule, MirType, Operand, Reg, StrID, Term},
+    mir_analysis::reg_types::RegTypes,
+    mir_passes,
+    ops::{BinOp, UnOp},
+};
+
+const PRELUDE: &str = "\
+#include <math.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+static inline int32_t lang_add(int32_t a, int32_t b) { return (int32_t)((uint32_t)a + (uint32_t)b); }
+static inline int32_t lang_sub(int32_t a, int32_t b) { return (int32_t)((uint32_t)a - (uint32_t)b); }
+static inline int32_t lang_mul(int32_t a, int32_t b) { return (int32_t)((uint32_t)a * (uint32_t)b); }
+static inline int32_t lang_neg(int32_t a) { return (int32_t)(0u - (uint32_t)a); }
+
+static inline int32_t lang_div(int32_t a, int32_t b) {
+    if (b == 0) abort();
+    if (b == -1) return lang_neg(a);
+    return a / b;
+}
+
+static inline int32_t lang_rem(int32_t a, int32_t b) {
+    if (b == 0) abort();
+    if (b == -1) return 0;
+    return a % b;
+}
+
+static inline int32_t lang_shl(int32_t a, int32_t b) { return (int32_t)((uint32_t)a << (b & 31)); }
+static inline int32_t lang_shr(int32_t a, int32_t b) { return a >> (b & 31); }
+
+static inline int64_t lang_div_i64(int64_t a, int64_t b) {
+    if (b == 0) abort();
+    if (b == -1) return (int64_t)(0u - (uint64_t)a);
+    return a / b;
+}
+
+static inline int64_t lang_rem_i64(int64_t a, int64_t b) {
+    if (b == 0) abort();
+    if (b == -1) return 0;
+    return a % b;
+}
+
+static inline uint64_t lang_div_u64(uint64_t a, uint64_t b) {
+    if (b == 0) abort();
+    return a / b;
+}
+
+static inline uint64_t lang_rem_u64(uint64_t a, uint64_t b) {
+    if (b == 0) abort();
+    return a % b;
+}
+
+static inline int64_t lang_ftoi(double a, int64_t min, int64_t max) {
+    if (a != a) return 0;
+    if (a <= (double)min) return min;
+    if (a >= (double)max) return max;
+    return (int64_t)a;
+}
+
+static inline uint64_t lang_ftou(double a, uint64_t max) {
+    if (!(a > 0)) return 0;
+    if (a >= (double)max) return max;
+    return (uint64_t)a;
+}
+";
+
+/// Strings are immutable byte slices. Concatenation allocates, and strings are never freed.
+const STR_PRELUDE: &str = "\
+#include <string.h>
+
+typedef struct { const char *data; size_t len; } lang_str;
+
+static inline lang_str lang_concat(lang_str a, lang_str b) {
+    char *data = malloc(a.len + b.len + 1);
+    if (data == NULL) abort();
+    memcpy(data, a.data, a.len);
+    memcpy(data + a.len, b.data, b.len);
+    return (lang_str){data, a.len + b.len};
+}
+
+static inline bool lang_str_eq(lang_str a, lang_str b) {
+    return a.len == b.len && memcmp(a.data, b.data, a.len) == 0;
+}
+";
+
+pub fn emit_module(module: &MirModule) -> Result<String, CodegenError> {
+    codegen::check_types(module, "C", |_| true)?;
+
+    let mut module = module.clone();
+    mir_passes::out_of_ssa(&mut module);
+
+    let return_tys = module
+        .funs
+        .iter()
+        .map(|fun| (fun.name.as_str(), fun.return_ty.clone()))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut out = String::from(PRELUDE);
+    out.push('\n');
+
+    if codegen::uses_strings(&module) {
+        out.push_str(STR_PRELUDE);
+        out.push('\n');
+
+        out.extend(module.strings.iter().enumerate().map(|(idx, value)| {
+            format!(
+                "static const lang_str {} = {{\"{}\", {}}};\n",
+                str_name(StrID(idx)),
+                escape(value),
+                value.len()
+            )
+        }));
+
+        out.push('\n');
+    }
+
+    for fun in &module.funs {
+        out.push_str(&signature(fun));
+        out.push_str(";\n");
+    }
+
+    for fun in &module.funs {
+        FunEmitter {
+            fun,
+            return_tys: &return_tys,
+            reg_tys: RegTypes::new(&module, fun),
+            out: &mut out,
+        }
+        .emit();
+    }
+
+    Ok(out)
+}
+
+struct FunEmitter<'a> {
+    fun: &'a MirFun,
+    return_tys: &'a BTreeMap<&'a str, Option<MirType>>,
+    reg_tys: RegTypes,
+    out: &'a mut String,
+}
+
+impl FunEmitter<'_> {
+    fn emit(mut self) {
+        self.out.push('\n');
+        self.out.push_str(&signature(self.fun));
+        self.out.push_str(" {\n");
+
+        let params = self
+            .fun
+            .params
+            .iter()
+            .map(|(reg, _)| *reg)
+            .collect::<BTreeSet<_>>();
+
+        let locals = self
+            .fun
+            .blocks
+            .iter()
+            .flat_map(|block| &block.instrs)
+            .map(|instr| instr.dest)
+            .filter(|reg| !params.contains(reg))
+            .unique()
+            .sorted()
+            .map(|reg| {
+                let ty = self.reg_tys.get(reg);
+                let init = if ty == Some(&MirType::Str) {
+                    "{0}"
+                } else {
+                    "0"
+                };
+
+                (local_ty(ty), format!("{} = {init}", name(reg)))
+            })
+            .into_group_map();
+
+        for (ty, locals) in locals.into_iter().sorted() {
+            self.line(&format!("    {ty} {};", locals.join(", ")));
+        }
+
+        let fun = self.fun;
+
+        for block in &fun.blocks {
+            self.block(block);
+        }
+
+        self.out.push_str("}\n");
+    }
+
+    fn block(&mut self, block: &BasicBlock) {
+        self.line(&format!("{}:", block.id));
+
+        for instr in &block.instrs {
+            self.instr(instr);
+        }
+
+        let line = match &block.term {
+            Some(Term::Jump { target }) => format!("goto {target};"),
+
+            Some(Term::Branch {
+                cond,
+                then_block,
+                else_block,
+            }) => format!(
+                "if ({}) goto {then_block}; else goto {else_block};",
+                operand(*cond)
+            ),
+
+            Some(Term::Return { value: Some(value) })
+                if self.fun.name == "main"
+                    && self.reg_tys.operand(*value) == Some(MirType::Float) =>
+            {
+                format!("return {};", float_cast(&operand(*value), &MirType::Num))
+            }
+
+            Some(Term::Return { value: Some(value) }) => format!("return {};", operand(*value)),
+
+            Some(Term::Return { value: None }) | None => {
+                if self.fun.name == "main" {
+                    "return 0;".into()
+                } else if self.fun.return_ty == Some(MirType::Str) {
+                    "return (lang_str){\"\", 0};".into()
+                } else if let Some(ty) = &self.fun.return_ty {
+                    format!("return {};", operand(ty.default_value()))
+                } else {
+                    "return;".into()
+                }
+            }
+        };
+
+        self.line(&format!("    {line}"));
+    }
+
+    fn instr(&mut self, instr: &Instr) {
+        let expr = match &instr.kind {
+            InstrKind::Copy { src }
+            | InstrKind::Cast {
+                arg: src,
+                ty: MirType::Str,
+            } => operand(*src),
+
+            InstrKind::Cast { arg, ty } => match self.reg_tys.operand(*arg) {
+                Some(MirType::Float) if *ty != MirType::Float => float_cast(&operand(*arg), ty),
+                _ => format!("({}){}", ty_name(Some(ty)), operand(*arg)),
+            },
+
+            InstrKind::Unary { op, arg } => {
+                let ty = self.reg_tys.operand(*arg);
+                let arg = operand(*arg);
+
+                match (op, ty) {
+                    (UnOp::Negate, Some(MirType::Int(ty))) => {
+                        let (c, u) = (int_name(ty), unsigned_name(ty));
+                        format!("({c})(({u})0 - ({u}){arg})")
+                    }
+                    (UnOp::BitNot, Some(MirType::Int(ty))) => {
+                        format!("({})~{arg}", int_name(ty))
+                    }
+                    (UnOp::Negate, _) => format!("lang_neg({arg})"),
+                    (UnOp::Not, _) => format!("!{arg}"),
+                    (UnOp::BitNot, _) => format!("~{arg}"),
+                    (UnOp::FNegate, _) => format!("-({arg})"),
+                }
+            }
+
+            InstrKind::Binary {
+                op:
+                    op @ (BinOp::IAdd(ty)
+                    | BinOp::ISub(ty)
+                    | BinOp::IMul(ty)
+                    | BinOp::IDiv(ty)
+                    | BinOp::UDiv(ty)
+                    | BinOp::IRem(ty)
+                    | BinOp::URem(ty)
+                    | BinOp::IShl(ty)
+                    | BinOp::IShr(ty)
+                    | BinOp::UShr(ty)),
+                lhs,
+                rhs,
+            } => int_binary(*op, *ty, &operand(*lhs), &operand(*rhs)),
+
+            InstrKind::Binary {
+                op: op @ (BinOp::Eq | BinOp::NotEq | BinOp::Concat),
+                lhs,
+                rhs,
+            } if self.reg_tys.operand(*lhs) == Some(MirType::Str) => {
+                let (lhs, rhs) = (operand(*lhs), operand(*rhs));
+
+                match op {
+                    BinOp::Eq => format!("lang_str_eq({lhs}, {rhs})"),
+                    BinOp::NotEq => format!("!lang_str_eq({lhs}, {rhs})"),
+                    _ => format!("lang_concat({lhs}, {rhs})"),
+                }
+            }
+
+            InstrKind::Binary { op, lhs, rhs } => binary(*op, &operand(*lhs), &operand(*rhs)),
+
+            InstrKind::Call { name, args } => {
+                let call = format!(
+                    "{}({})",
+                    fun_name(name),
+                    args.iter().map(|arg| operand(*arg)).join(", ")
+                );
+
+                if self.return_tys.get(name.as_str()) == Some(&None) {
+                    return self.line(&format!("    {call};"));
+                }
+
+                call
+            }
+        };
+
+        self.line(&format!("    {} = {expr};", name(instr.dest)));
+    }
+
+    fn line(&mut self, line: &str) {
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+}
+
+fn binary(op: BinOp, lhs: &str, rhs: &str) -> String {
+    match op {
+        BinOp::Add => format!("lang_add({lhs}, {rhs})"),
+        BinOp::Sub => format!("lang_sub({lhs}, {rhs})"),
+        BinOp::Mul => format!("lang_mul({lhs}, {rhs})"),
+        BinOp::Div => format!("lang_div({lhs}, {rhs})"),
+        BinOp::Rem => format!("lang_rem({lhs}, {rhs})"),
+        BinOp::BitAnd | BinOp::IAnd(..) => format!("{lhs} & {rhs}"),
+        BinOp::BitOr | BinOp::IOr(..) => format!("{lhs} | {rhs}"),
+        BinOp::BitXor | BinOp::IXor(..) => format!("{lhs} ^ {rhs}"),
+        BinOp::Shl => format!("lang_shl({lhs}, {rhs})"),
+        BinOp::Shr => format!("lang_shr({lhs}, {rhs})"),
+        BinOp::And => format!("{lhs} && {rhs}"),
+        BinOp::Or => format!("{lhs} || {rhs}"),
+        BinOp::FAdd => format!("{lhs} + {rhs}"),
+        BinOp::FSub => format!("{lhs} - {rhs}"),
+        BinOp::FMul => format!("{lhs} * {rhs}"),
+        BinOp::FDiv => format!("{lhs} / {rhs}"),
+        BinOp::Eq | BinOp::FEq => format!("{lhs} == {rhs}"),
+        BinOp::NotEq | BinOp::FNotEq => format!("{lhs} != {rhs}"),
+        BinOp::Lesser | BinOp::ULesser | BinOp::FLesser => format!("{lhs} < {rhs}"),
+        BinOp::LesserEq | BinOp::ULesserEq | BinOp::FLesserEq => {
+            format!("{lhs} <= {rhs}")
+        }
+        BinOp::Greater | BinOp::UGreater | BinOp::FGreater => format!("{lhs} > {rhs}"),
+        BinOp::GreaterEq | BinOp::UGreaterEq | BinOp::FGreaterEq => {
+            format!("{lhs} >= {rhs}")
+        }
+        BinOp::IAdd(..)
+        | BinOp::ISub(..)
+        | BinOp::IMul(..)
+        | BinOp::IDiv(..)
+        | BinOp::UDiv(..)
+        | BinOp::IRem(..)
+        | BinOp::URem(..)
+        | BinOp::IShl(..)
+        | BinOp::IShr(..)
+        | BinOp::UShr(..)
+        | BinOp::Concat => unreachable!(),
+    }
+}
+
+/// Sized integer arithmetic goes through unsigned types, which wrap instead of overflowing, and
+/// division through the 64-bit helpers, which trap on zero and can't overflow for narrower types.
+fn int_binary(op: BinOp, ty: IntType, lhs: &str, rhs: &str) -> String {
+    let (c, u) = (int_name(ty), unsigned_name(ty));
+    let wide = if ty.is_signed() { "i64" } else { "u64" };
+    let mask = ty.bits() - 1;
+
+    match op {
+        BinOp::IAdd(..) => format!("({c})(({u}){lhs} + ({u}){rhs})"),
+        BinOp::ISub(..) => format!("({c})(({u}){lhs} - ({u}){rhs})"),
+        BinOp::IMul(..) => format!("({c})(({u}){lhs} * ({u}){rhs})"),
+        BinOp::IDiv(..) | BinOp::UDiv(..) => format!("({c})lang_div_{wide}({lhs}, {rhs})"),
+        BinOp::IRem(..) | BinOp::URem(..) => format!("({c})lang_rem_{wide}({lhs}, {rhs})"),
+        BinOp::IShl(..) => format!("({c})(({u}){lhs} << ({rhs} & {mask}))"),
+        BinOp::IShr(..) | BinOp::UShr(..) => format!("{lhs} >> ({rhs} & {mask})"),
+        _ => unreachable!(),
+    }
+}
+
+/// Float to integer casts saturate at the bounds of the target type and map NaN to 0, like `as`
+/// in Rust, where a plain C conversion would be undefined.
+fn float_cast(arg: &str, ty: &MirType) -> String {
+    match ty {
+        MirType::Num => format!("(int32_t)lang_ftoi({arg}, INT32_MIN, INT32_MAX)"),
+
+        MirType::Int(ty) if ty.is_signed() => format!(
+            "({})lang_ftoi({arg}, {}, {})",
+            int_name(*ty),
+            int_literal(Int::new(ty.min_value(), *ty)),
+            int_literal(Int::new(ty.max_value(), *ty))
+        ),
+
+        MirType::Int(ty) => format!(
+            "({})lang_ftou({arg}, {})",
+            int_name(*ty),
+            int_literal(Int::new(ty.max_value(), *ty))
+        ),
+
+        MirType::Bool | MirType::Float | MirType::Str => unreachable!(),
+    }
+}
+
+fn signature(fun: &MirFun) -> String {
+    if fun.name == "main" {
+        return "int main(void)".into();
+    }
+
+    let params = if fun.params.is_empty() {
+        "void".into()
+    } else {
+        fun.params
+            .iter()
+            .map(|(reg, ty)| format!("{} {}", ty_name(Some(ty)), name(*reg)))
+            .join(", ")
+    };
+
+    format!(
+        "{} {}({params})",
+        ty_name(fun.return_ty.as_ref()),
+        fun_name(&fun.name)
+    )
+}
+
+fn ty_name(ty: Option<&MirType>) -> &'static str {
+    match ty {
+        Some(MirType::Num) => "int32_t",
+        Some(MirType::Bool) => "bool",
+        Some(MirType::Int(ty)) => int_name(*ty),
+        Some(MirType::Float) => "double",
+        Some(MirType::Str) => "lang_str",
+        None => "void",
+    }
+}
+
+/// Registers without a type only hold the result of calls to functions returning nothing.
+fn local_ty(ty: Option<&MirType>) -> &'static str {
+    ty.map_or("int32_t", |ty| ty_name(Some(ty)))
+}
+
+fn int_name(ty: IntType) -> &'static str {
+    match ty {
+        IntType::I8 => "int8_t",
+        IntType::I16 => "int16_t",
+        IntType::I64 => "int64_t",
+        IntType::U8 => "uint8_t",
+        IntType::U16 => "uint16_t",
+        IntType::U32 => "uint32_t",
+        IntType::U64 => "uint64_t",
+    }
+}
+
+fn unsigned_name(ty: IntType) -> &'static str {
+    if ty.bits() <= 32 {
+        "uint32_t"
+    } else {
+        "uint64_t"
+    }
+}
+
+fn fun_name(name: &str) -> String {
+    if name == "main" {
+        name.into()
+    } else {
+        format!("lang_fn_{name}")
+    }
+}
+
+fn str_name(id: StrID) -> String {
+    format!("lang_str_{}", id.0)
+}
+
+fn name(reg: Reg) -> String {
+    format!("r{}", reg.0)
+}
+
+fn operand(operand: Operand) -> String {
+    match operand {
+        Operand::Num(i32::MIN) => "INT32_MIN".into(),
+        Operand::Num(value) => value.to_string(),
+        Operand::Bool(value) => value.to_string(),
+        Operand::Int(value) => int_literal(value),
+        Operand::Float(value) => float_literal(value),
+        Operand::Str(id) => str_name(id),
+        Operand::Reg(reg) => name(reg),
+    }
+}
+
+fn int_literal(value: Int) -> String {
+    match value.ty() {
+        IntType::I64 if value.value() == i128::from(i64::MIN) => "INT64_MIN".into(),
+        IntType::I64 => format!("INT64_C({})", value.value()),
+        IntType::U64 => format!("UINT64_C({})", value.value()),
+        _ => value.value().to_string(),
+    }
+}
+
+fn float_literal(value: Float) -> String {
+    let value = value.value();
+
+    if value.is_nan() {
+        "NAN".into()
+    } else if value.is_infinite() {
+        if value > 0.0 { "INFINITY" } else { "-INFINITY" }.into()
+    } else {
+        format!("{value:?}")
+    }
+}
+
+/// Escapes a string for a C literal. Bytes other than printable ASCII are written as octal escapes,
+/// which take at most three digits and so can't run into the next character.
+fn escape(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'"' | b'\\' | b'?' => format!("\\{}", char::from(byte)),
+            b' '..=b'~' => char::from(byte).to_string(),
+            _ => format!("\\{byte:03o}"),
+        })
+        .collect()
+}
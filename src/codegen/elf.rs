@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    codegen::{
+        self,
+        error::CodegenError,
+        x86_64::encode::{self, Checks, MachineCode},
+    },
+    mir::MirModule,
+    mir_passes,
+};
+
+const EHDR_SIZE: u16 = 64;
+const SHDR_SIZE: u16 = 64;
+const SYM_SIZE: u64 = 24;
+const RELA_SIZE: u64 = 24;
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_RELA: u32 = 4;
+
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+const SHF_INFO_LINK: u64 = 0x40;
+
+const STB_GLOBAL: u8 = 1;
+const STT_FUNC: u8 = 2;
+const STT_SECTION: u8 = 3;
+
+const R_X86_64_PLT32: u64 = 4;
+
+const TEXT: u32 = 1;
+const TEXT_INDEX: u16 = 1;
+const SYMTAB: u32 = 3;
+const STRTAB: u32 = 4;
+const SHSTRTAB: u32 = 6;
+
+/// Symbols before the function symbols: null and the `.text` section.
+const LOCALS: u32 = 2;
+
+/// The encoder keeps every value in 32 bits like the JIT's native code, so unlike the assembly
+/// backend it rejects `i64` and `u64` along with floats and strings.
+pub fn emit_object(module: &MirModule) -> Result<Vec<u8>, CodegenError> {
+    codegen::check_types(module, "ELF", codegen::fits_32_bits)?;
+
+    let mut module = module.clone();
+    mir_passes::out_of_ssa(&mut module);
+
+    let funs = module.funs.iter().collect::<Vec<_>>();
+    let machine = encode::encode(&module, &funs, Checks::Native);
+
+    Ok(ObjectWriter::default().write(&machine))
+}
+
+#[derive(Debug, Clone)]
+struct Section {
+    name: u32,
+    kind: u32,
+    flags: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    align: u64,
+    entsize: u64,
+}
+
+#[derive(Default)]
+struct ObjectWriter {
+    out: Vec<u8>,
+    sections: Vec<Section>,
+}
+
+impl ObjectWriter {
+    fn write(mut self, machine: &MachineCode) -> Vec<u8> {
+        let mut shstrtab = StringTable::default();
+        let mut strtab = StringTable::default();
+
+        let indices = machine
+            .symbols
+            .iter()
+            .zip(LOCALS..)
+            .map(|(symbol, idx)| (symbol.name.as_str(), idx))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut symtab = Vec::new();
+        symbol(&mut symtab, 0, 0, 0, 0, 0);
+        symbol(&mut symtab, 0, STT_SECTION, TEXT_INDEX, 0, 0);
+
+        for sym in &machine.symbols {
+            let info = (STB_GLOBAL << 4) | STT_FUNC;
+            let (value, size) = (sym.offset as u64, sym.size as u64);
+            symbol(
+                &mut symtab,
+                strtab.add(&sym.name),
+                info,
+                TEXT_INDEX,
+                value,
+                size,
+            );
+        }
+
+        let mut rela = Vec::new();
+
+        for reloc in &machine.relocs {
+            let sym = u64::from(indices[reloc.symbol.as_str()]);
+
+            rela.extend_from_slice(&(reloc.offset as u64).to_le_bytes());
+            rela.extend_from_slice(&((sym << 32) | R_X86_64_PLT32).to_le_bytes());
+            rela.extend_from_slice(&(-4_i64).to_le_bytes());
+        }
+
+        self.out.resize(usize::from(EHDR_SIZE), 0);
+        self.sections.push(Section::null());
+
+        let text = Section {
+            name: shstrtab.add(".text"),
+            kind: SHT_PROGBITS,
+            flags: SHF_ALLOC | SHF_EXECINSTR,
+            align: 16,
+            ..Section::null()
+        };
+        self.section(text, &machine.code);
+
+        let rela_text = Section {
+            name: shstrtab.add(".rela.text"),
+            kind: SHT_RELA,
+            flags: SHF_INFO_LINK,
+            link: SYMTAB,
+            info: TEXT,
+            align: 8,
+            entsize: RELA_SIZE,
+            ..Section::null()
+        };
+        self.section(rela_text, &rela);
+
+        let symtab_section = Section {
+            name: shstrtab.add(".symtab"),
+            kind: SHT_SYMTAB,
+            link: STRTAB,
+            info: LOCALS,
+            align: 8,
+            entsize: SYM_SIZE,
+            ..Section::null()
+        };
+        self.section(symtab_section, &symtab);
+
+        let strtab_section = Section {
+            name: shstrtab.add(".strtab"),
+            kind: SHT_STRTAB,
+            align: 1,
+            ..Section::null()
+        };
+        self.section(strtab_section, &strtab.bytes);
+
+        let note = Section {
+            name: shstrtab.add(".note.GNU-stack"),
+            kind: SHT_PROGBITS,
+            align: 1,
+            ..Section::null()
+        };
+        self.section(note, &[]);
+
+        let shstrtab_section = Section {
+            name: shstrtab.add(".shstrtab"),
+            kind: SHT_STRTAB,
+            align: 1,
+            ..Section::null()
+        };
+        self.section(shstrtab_section, &shstrtab.bytes.clone());
+
+        self.align(8);
+        let shoff = self.out.len() as u64;
+
+        for section in std::mem::take(&mut self.sections) {
+            self.u32(section.name);
+            self.u32(section.kind);
+            self.u64(section.flags);
+            self.u64(0);
+            self.u64(section.offset);
+            self.u64(section.size);
+            self.u32(section.link);
+            self.u32(section.info);
+            self.u64(section.align);
+            self.u64(section.entsize);
+        }
+
+        self.header(shoff);
+        self.out
+    }
+
+    fn header(&mut self, shoff: u64) {
+        let mut header = Vec::new();
+
+        header.extend_from_slice(b"\x7fELF");
+        header.extend_from_slice(&[2, 1, 1, 0]);
+        header.extend_from_slice(&[0; 8]);
+        header.extend_from_slice(&1_u16.to_le_bytes());
+        header.extend_from_slice(&62_u16.to_le_bytes());
+        header.extend_from_slice(&1_u32.to_le_bytes());
+        header.extend_from_slice(&0_u64.to_le_bytes());
+        header.extend_from_slice(&0_u64.to_le_bytes());
+        header.extend_from_slice(&shoff.to_le_bytes());
+        header.extend_from_slice(&0_u32.to_le_bytes());
+        header.extend_from_slice(&EHDR_SIZE.to_le_bytes());
+        header.extend_from_slice(&0_u16.to_le_bytes());
+        header.extend_from_slice(&0_u16.to_le_bytes());
+        header.extend_from_slice(&SHDR_SIZE.to_le_bytes());
+        header.extend_from_slice(&u16::try_from(SHSTRTAB + 1).unwrap().to_le_bytes());
+        header.extend_from_slice(&u16::try_from(SHSTRTAB).unwrap().to_le_bytes());
+
+        self.out[..header.len()].copy_from_slice(&header);
+    }
+
+    fn section(&mut self, mut section: Section, data: &[u8]) {
+        self.align(usize::try_from(section.align).unwrap());
+
+        section.offset = self.out.len() as u64;
+        section.size = data.len() as u64;
+
+        self.out.extend_from_slice(data);
+        self.sections.push(section);
+    }
+
+    fn align(&mut self, align: usize) {
+        let len = self.out.len().next_multiple_of(align);
+        self.out.resize(len, 0);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.out.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+impl Section {
+    const fn null() -> Self {
+        Self {
+            name: 0,
+            kind: 0,
+            flags: 0,
+            offset: 0,
+            size: 0,
+            link: 0,
+            info: 0,
+            align: 0,
+            entsize: 0,
+        }
+    }
+}
+
+struct StringTable {
+    bytes: Vec<u8>,
+}
+
+impl Default for StringTable {
+    fn default() -> Self {
+        Self { bytes: vec![0] }
+    }
+}
+
+impl StringTable {
+    fn add(&mut self, name: &str) -> u32 {
+        let offset = u32::try_from(self.bytes.len()).unwrap();
+
+        self.bytes.extend_from_slice(name.as_bytes());
+        self.bytes.push(0);
+
+        offset
+    }
+}
+
+fn symbol(out: &mut Vec<u8>, name: u32, info: u8, shndx: u16, value: u64, size: u64) {
+    out.extend_from_slice(&name.to_le_bytes());
+    out.extend_from_slice(&[info, 0]);
+    out.extend_from_slice(&shndx.to_le_bytes());
+    out.extend_from_slice(&value.to_le_bytes());
+    out.extend_from_slice(&size.to_le_bytes());
+}
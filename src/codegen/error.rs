@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+use crate::mir::MirType;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CodegenError {
+    #[error("The {backend} backend does not support {ty} values.")]
+    UnsupportedType { backend: &'static str, ty: MirType },
+
+    #[error("The {backend} backend cannot return {ty} from main.")]
+    UnsupportedMainType { backend: &'static str, ty: MirType },
+}
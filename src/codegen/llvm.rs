@@ -0,0 +1,695 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use itertools::Itertools as _;
+
+use crate::{
+    codegen::{self, error::CodegenError},
+    int::{Int, IntType},
+    mir::{BasicBlock, Instr, InstrKind, MirFun, MirModule, MirType, Operand, Reg, StrID, Term},
+    mir_analysis::reg_types::RegTypes,
+    ops::{BinOp, UnOp},
+};
+
+const HELPERS: &str = "\
+define internal i32 @lang.div(i32 %a, i32 %b) {
+entry:
+  switch i32 %b, label %div [ i32 0, label %trap
+                              i32 -1, label %neg ]
+trap:
+  call void @llvm.trap()
+  unreachable
+neg:
+  %n = sub i32 0, %a
+  ret i32 %n
+div:
+  %q = sdiv i32 %a, %b
+  ret i32 %q
+}
+
+define internal i32 @lang.rem(i32 %a, i32 %b) {
+entry:
+  switch i32 %b, label %rem [ i32 0, label %trap
+                              i32 -1, label %zero ]
+trap:
+  call void @llvm.trap()
+  unreachable
+zero:
+  ret i32 0
+rem:
+  %r = srem i32 %a, %b
+  ret i32 %r
+}
+
+define internal i32 @lang.shl(i32 %a, i32 %b) {
+entry:
+  %n = and i32 %b, 31
+  %r = shl i32 %a, %n
+  ret i32 %r
+}
+
+define internal i32 @lang.shr(i32 %a, i32 %b) {
+entry:
+  %n = and i32 %b, 31
+  %r = ashr i32 %a, %n
+  ret i32 %r
+}
+
+declare void @llvm.trap()
+";
+
+/// Strings are pointers to an immutable slice header. Concatenation allocates the header and the
+/// bytes together, and strings are never freed.
+const STR_HELPERS: &str = "\
+%lang.str = type { i8*, i64 }
+
+@lang.str.empty = private constant %lang.str zeroinitializer
+
+define internal %lang.str* @lang.concat(%lang.str* %a, %lang.str* %b) {
+entry:
+  %a.data.ptr = getelementptr %lang.str, %lang.str* %a, i32 0, i32 0
+  %a.data = load i8*, i8** %a.data.ptr
+  %a.len.ptr = getelementptr %lang.str, %lang.str* %a, i32 0, i32 1
+  %a.len = load i64, i64* %a.len.ptr
+  %b.data.ptr = getelementptr %lang.str, %lang.str* %b, i32 0, i32 0
+  %b.data = load i8*, i8** %b.data.ptr
+  %b.len.ptr = getelementptr %lang.str, %lang.str* %b, i32 0, i32 1
+  %b.len = load i64, i64* %b.len.ptr
+  %len = add i64 %a.len, %b.len
+  %size = add i64 %len, 16
+  %mem = call i8* @malloc(i64 %size)
+  %null = icmp eq i8* %mem, null
+  br i1 %null, label %trap, label %copy
+trap:
+  call void @llvm.trap()
+  unreachable
+copy:
+  %data = getelementptr i8, i8* %mem, i64 16
+  call void @llvm.memcpy.p0i8.p0i8.i64(i8* %data, i8* %a.data, i64 %a.len, i1 false)
+  %rest = getelementptr i8, i8* %data, i64 %a.len
+  call void @llvm.memcpy.p0i8.p0i8.i64(i8* %rest, i8* %b.data, i64 %b.len, i1 false)
+  %str = bitcast i8* %mem to %lang.str*
+  %data.ptr = getelementptr %lang.str, %lang.str* %str, i32 0, i32 0
+  store i8* %data, i8** %data.ptr
+  %len.ptr = getelementptr %lang.str, %lang.str* %str, i32 0, i32 1
+  store i64 %len, i64* %len.ptr
+  ret %lang.str* %str
+}
+
+define internal i1 @lang.str.eq(%lang.str* %a, %lang.str* %b) {
+entry:
+  %a.len.ptr = getelementptr %lang.str, %lang.str* %a, i32 0, i32 1
+  %a.len = load i64, i64* %a.len.ptr
+  %b.len.ptr = getelementptr %lang.str, %lang.str* %b, i32 0, i32 1
+  %b.len = load i64, i64* %b.len.ptr
+  %same.len = icmp eq i64 %a.len, %b.len
+  br i1 %same.len, label %compare, label %differ
+differ:
+  ret i1 false
+compare:
+  %a.data.ptr = getelementptr %lang.str, %lang.str* %a, i32 0, i32 0
+  %a.data = load i8*, i8** %a.data.ptr
+  %b.data.ptr = getelementptr %lang.str, %lang.str* %b, i32 0, i32 0
+  %b.data = load i8*, i8** %b.data.ptr
+  %cmp = call i32 @memcmp(i8* %a.data, i8* %b.data, i64 %a.len)
+  %eq = icmp eq i32 %cmp, 0
+  ret i1 %eq
+}
+
+declare i8* @malloc(i64)
+declare i32 @memcmp(i8*, i8*, i64)
+declare void @llvm.memcpy.p0i8.p0i8.i64(i8*, i8*, i64, i1)
+";
+
+pub fn emit_module(module: &MirModule) -> Result<String, CodegenError> {
+    codegen::check_types(module, "LLVM", |_| true)?;
+
+    let return_tys = module
+        .funs
+        .iter()
+        .map(|fun| (fun.name.as_str(), fun.return_ty.clone()))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut out = String::new();
+
+    if codegen::uses_strings(module) {
+        out.push_str(STR_HELPERS);
+        out.push('\n');
+
+        out.extend(module.strings.iter().enumerate().map(|(idx, value)| {
+            let (name, len) = (str_name(StrID(idx)), value.len());
+
+            format!(
+                "{name}.data = private unnamed_addr constant [{len} x i8] c\"{}\"\n\
+                 {name} = private constant %lang.str {{ i8* getelementptr inbounds \
+                 ([{len} x i8], [{len} x i8]* {name}.data, i64 0, i64 0), i64 {len} }}\n",
+                escape(value)
+            )
+        }));
+
+        out.push('\n');
+    }
+
+    let mut div_tys = BTreeSet::new();
+    let mut float_casts = BTreeSet::new();
+
+    for fun in &module.funs {
+        FunEmitter {
+            fun,
+            return_tys: &return_tys,
+            reg_tys: RegTypes::new(module, fun),
+            div_tys: &mut div_tys,
+            float_casts: &mut float_casts,
+            out: &mut out,
+        }
+        .emit();
+
+        out.push('\n');
+    }
+
+    out.push_str(HELPERS);
+
+    out.extend(
+        float_casts
+            .into_iter()
+            .map(|(cast, ty)| format!("declare {ty} @llvm.{cast}.sat.{ty}.f64(double)\n")),
+    );
+
+    for ty in div_tys {
+        out.push('\n');
+        out.push_str(&int_div_helpers(ty));
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+/// Division helpers for a sized integer type, which trap on zero like `@lang.div`. Signed division
+/// by -1 can overflow, so it negates or yields 0 instead.
+fn int_div_helpers(ty: IntType) -> String {
+    let name = int_name(ty);
+    let sign = if ty.is_signed() { 's' } else { 'u' };
+    let mut helpers = Vec::new();
+
+    for (helper, minus_one) in [("div", "%n"), ("rem", "0")] {
+        let mut lines = vec![
+            format!("define internal {name} @lang.{helper}.{ty}({name} %a, {name} %b) {{"),
+            "entry:".into(),
+        ];
+
+        if ty.is_signed() {
+            let switch = format!("  switch {name} %b, label %op [ ");
+
+            lines.extend([
+                format!("{switch}{name} 0, label %trap"),
+                format!("{}{name} -1, label %minus_one ]", " ".repeat(switch.len())),
+            ]);
+        } else {
+            lines.extend([
+                format!("  %zero = icmp eq {name} %b, 0"),
+                "  br i1 %zero, label %trap, label %op".into(),
+            ]);
+        }
+
+        lines.extend([
+            "trap:".into(),
+            "  call void @llvm.trap()".into(),
+            "  unreachable".into(),
+        ]);
+
+        if ty.is_signed() {
+            lines.extend([
+                "minus_one:".into(),
+                format!("  %n = sub {name} 0, %a"),
+                format!("  ret {name} {minus_one}"),
+            ]);
+        }
+
+        lines.extend([
+            "op:".into(),
+            format!("  %r = {sign}{helper} {name} %a, %b"),
+            format!("  ret {name} %r"),
+            "}".into(),
+        ]);
+
+        helpers.push(lines.join("\n"));
+    }
+
+    helpers.join("\n\n")
+}
+
+struct FunEmitter<'a> {
+    fun: &'a MirFun,
+    return_tys: &'a BTreeMap<&'a str, Option<MirType>>,
+    reg_tys: RegTypes,
+    div_tys: &'a mut BTreeSet<IntType>,
+    float_casts: &'a mut BTreeSet<(&'static str, &'static str)>,
+    out: &'a mut String,
+}
+
+impl FunEmitter<'_> {
+    fn emit(mut self) {
+        let params = self
+            .fun
+            .params
+            .iter()
+            .map(|(reg, ty)| format!("{} {}", ty_name(Some(ty)), name(*reg)))
+            .join(", ");
+
+        let return_ty = if self.fun.name == "main" {
+            "i32"
+        } else {
+            ty_name(self.fun.return_ty.as_ref())
+        };
+
+        self.line(&format!(
+            "define {return_ty} @{}({params}) {{",
+            self.fun.name
+        ));
+
+        if let Some(entry) = self.fun.blocks.first()
+            && !self.fun.cfg.predecessors(entry.id).is_empty()
+        {
+            self.line("entry:");
+            self.line(&format!("  br label %{}", entry.id));
+        }
+
+        let fun = self.fun;
+
+        for block in &fun.blocks {
+            self.block(block);
+        }
+
+        self.line("}");
+    }
+
+    fn block(&mut self, block: &BasicBlock) {
+        self.line(&format!("{}:", block.id));
+
+        for phi in &block.phis {
+            let srcs = phi
+                .srcs
+                .iter()
+                .map(|(pred, src)| format!("[ {}, %{pred} ]", operand(*src)))
+                .join(", ");
+
+            self.line(&format!(
+                "  {} = phi {} {srcs}",
+                name(phi.dest),
+                self.ty(Operand::Reg(phi.dest))
+            ));
+        }
+
+        for instr in &block.instrs {
+            self.instr(instr);
+        }
+
+        let line = match &block.term {
+            Some(Term::Jump { target }) => format!("br label %{target}"),
+
+            Some(Term::Branch {
+                cond,
+                then_block,
+                else_block,
+            }) => format!(
+                "br i1 {}, label %{then_block}, label %{else_block}",
+                operand(*cond)
+            ),
+
+            Some(Term::Return { value: Some(value) }) if self.fun.name == "main" => {
+                self.exit_code(block, *value)
+            }
+
+            Some(Term::Return { value: Some(value) }) => {
+                format!("ret {} {}", self.ty(*value), operand(*value))
+            }
+
+            Some(Term::Return { value: None }) | None => {
+                if self.fun.name == "main" {
+                    "ret i32 0".into()
+                } else if self.fun.return_ty == Some(MirType::Str) {
+                    "ret %lang.str* @lang.str.empty".into()
+                } else if let Some(ty) = &self.fun.return_ty {
+                    format!("ret {} {}", ty_name(Some(ty)), operand(ty.default_value()))
+                } else {
+                    "ret void".into()
+                }
+            }
+        };
+
+        self.line(&format!("  {line}"));
+    }
+
+    fn instr(&mut self, instr: &Instr) {
+        let dest = instr.dest;
+
+        let expr = match &instr.kind {
+            InstrKind::Copy { src }
+            | InstrKind::Cast {
+                arg: src,
+                ty: MirType::Str,
+            } => {
+                let ty = self.ty(*src);
+                format!("bitcast {ty} {} to {ty}", operand(*src))
+            }
+
+            InstrKind::Cast { arg, ty }
+                if *ty == MirType::Float || self.reg_tys.operand(*arg) == Some(MirType::Float) =>
+            {
+                self.float_cast(*arg, ty)
+            }
+
+            InstrKind::Cast { arg, ty } => {
+                let cast = int_cast(self.reg_tys.operand(*arg).as_ref(), ty);
+                let (from, to) = (self.ty(*arg), ty_name(Some(ty)));
+
+                format!("{cast} {from} {} to {to}", operand(*arg))
+            }
+
+            InstrKind::Unary { op, arg } => match op {
+                UnOp::Negate => format!("sub {} 0, {}", self.ty(*arg), operand(*arg)),
+                UnOp::Not => format!("xor i1 {}, true", operand(*arg)),
+                UnOp::BitNot => format!("xor {} {}, -1", self.ty(*arg), operand(*arg)),
+                UnOp::FNegate => format!("fneg double {}", operand(*arg)),
+            },
+
+            InstrKind::Binary {
+                op:
+                    op @ (BinOp::IAdd(ty)
+                    | BinOp::ISub(ty)
+                    | BinOp::IMul(ty)
+                    | BinOp::IDiv(ty)
+                    | BinOp::UDiv(ty)
+                    | BinOp::IRem(ty)
+                    | BinOp::URem(ty)
+                    | BinOp::IShl(ty)
+                    | BinOp::IShr(ty)
+                    | BinOp::UShr(ty)),
+                lhs,
+                rhs,
+            } => self.int_binary(dest, *op, *ty, *lhs, *rhs),
+
+            InstrKind::Binary {
+                op: op @ (BinOp::Eq | BinOp::NotEq | BinOp::Concat),
+                lhs,
+                rhs,
+            } if self.reg_tys.operand(*lhs) == Some(MirType::Str) => {
+                self.str_binary(dest, *op, *lhs, *rhs)
+            }
+
+            InstrKind::Binary { op, lhs, rhs } => self.binary(*op, *lhs, *rhs),
+
+            InstrKind::Call { name, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| format!("{} {}", self.ty(*arg), operand(*arg)))
+                    .join(", ");
+
+                let return_ty = self.return_tys.get(name.as_str()).cloned().flatten();
+                let call = format!("call {} @{name}({args})", ty_name(return_ty.as_ref()));
+
+                if return_ty.is_none() {
+                    return self.line(&format!("  {call}"));
+                }
+
+                call
+            }
+        };
+
+        self.line(&format!("  {} = {expr}", name(dest)));
+    }
+
+    fn binary(&self, op: BinOp, lhs: Operand, rhs: Operand) -> String {
+        match (op, rhs) {
+            (BinOp::Div | BinOp::Rem, Operand::Num(value)) if value != 0 && value != -1 => {
+                let op = if op == BinOp::Div { "sdiv" } else { "srem" };
+                format!("{op} i32 {}, {}", operand(lhs), operand(rhs))
+            }
+
+            (BinOp::Shl | BinOp::Shr, Operand::Num(value)) => {
+                let op = if op == BinOp::Shl { "shl" } else { "ashr" };
+                format!("{op} i32 {}, {}", operand(lhs), value & 31)
+            }
+
+            _ => {
+                let ty = self.ty(lhs);
+                let (lhs, rhs) = (operand(lhs), operand(rhs));
+
+                match op {
+                    BinOp::Add => format!("add i32 {lhs}, {rhs}"),
+                    BinOp::Sub => format!("sub i32 {lhs}, {rhs}"),
+                    BinOp::Mul => format!("mul i32 {lhs}, {rhs}"),
+                    BinOp::Div => format!("call i32 @lang.div(i32 {lhs}, i32 {rhs})"),
+                    BinOp::Rem => format!("call i32 @lang.rem(i32 {lhs}, i32 {rhs})"),
+                    BinOp::Shl => format!("call i32 @lang.shl(i32 {lhs}, i32 {rhs})"),
+                    BinOp::Shr => format!("call i32 @lang.shr(i32 {lhs}, i32 {rhs})"),
+                    BinOp::And | BinOp::BitAnd | BinOp::IAnd(..) => {
+                        format!("and {ty} {lhs}, {rhs}")
+                    }
+                    BinOp::Or | BinOp::BitOr | BinOp::IOr(..) => format!("or {ty} {lhs}, {rhs}"),
+                    BinOp::BitXor | BinOp::IXor(..) => format!("xor {ty} {lhs}, {rhs}"),
+                    BinOp::Eq => format!("icmp eq {ty} {lhs}, {rhs}"),
+                    BinOp::NotEq => format!("icmp ne {ty} {lhs}, {rhs}"),
+                    BinOp::Lesser => format!("icmp slt {ty} {lhs}, {rhs}"),
+                    BinOp::LesserEq => format!("icmp sle {ty} {lhs}, {rhs}"),
+                    BinOp::Greater => format!("icmp sgt {ty} {lhs}, {rhs}"),
+                    BinOp::GreaterEq => format!("icmp sge {ty} {lhs}, {rhs}"),
+                    BinOp::ULesser => format!("icmp ult {ty} {lhs}, {rhs}"),
+                    BinOp::ULesserEq => format!("icmp ule {ty} {lhs}, {rhs}"),
+                    BinOp::UGreater => format!("icmp ugt {ty} {lhs}, {rhs}"),
+                    BinOp::UGreaterEq => format!("icmp uge {ty} {lhs}, {rhs}"),
+                    BinOp::FAdd => format!("fadd double {lhs}, {rhs}"),
+                    BinOp::FSub => format!("fsub double {lhs}, {rhs}"),
+                    BinOp::FMul => format!("fmul double {lhs}, {rhs}"),
+                    BinOp::FDiv => format!("fdiv double {lhs}, {rhs}"),
+                    BinOp::FEq => format!("fcmp oeq double {lhs}, {rhs}"),
+                    BinOp::FNotEq => format!("fcmp une double {lhs}, {rhs}"),
+                    BinOp::FLesser => format!("fcmp olt double {lhs}, {rhs}"),
+                    BinOp::FLesserEq => format!("fcmp ole double {lhs}, {rhs}"),
+                    BinOp::FGreater => format!("fcmp ogt double {lhs}, {rhs}"),
+                    BinOp::FGreaterEq => format!("fcmp oge double {lhs}, {rhs}"),
+                    BinOp::IAdd(..)
+                    | BinOp::ISub(..)
+                    | BinOp::IMul(..)
+                    | BinOp::IDiv(..)
+                    | BinOp::UDiv(..)
+                    | BinOp::IRem(..)
+                    | BinOp::URem(..)
+                    | BinOp::IShl(..)
+                    | BinOp::IShr(..)
+                    | BinOp::UShr(..)
+                    | BinOp::Concat => unreachable!(),
+                }
+            }
+        }
+    }
+
+    /// Strings compare by contents, so equality goes through a helper rather than `icmp`.
+    fn str_binary(&mut self, dest: Reg, op: BinOp, lhs: Operand, rhs: Operand) -> String {
+        let args = format!("%lang.str* {}, %lang.str* {}", operand(lhs), operand(rhs));
+
+        match op {
+            BinOp::Concat => format!("call %lang.str* @lang.concat({args})"),
+            BinOp::Eq => format!("call i1 @lang.str.eq({args})"),
+
+            _ => {
+                let eq = format!("{}.eq", name(dest));
+                self.line(&format!("  {eq} = call i1 @lang.str.eq({args})"));
+
+                format!("xor i1 {eq}, true")
+            }
+        }
+    }
+
+    /// Sized integers wrap natively. Shift amounts are masked to the width of the type, and
+    /// division goes through helpers that trap on zero.
+    fn int_binary(
+        &mut self,
+        dest: Reg,
+        op: BinOp,
+        ty: IntType,
+        lhs: Operand,
+        rhs: Operand,
+    ) -> String {
+        let name = int_name(ty);
+        let (lhs, rhs) = (operand(lhs), operand(rhs));
+
+        let ins = match op {
+            BinOp::IAdd(..) => "add",
+            BinOp::ISub(..) => "sub",
+            BinOp::IMul(..) => "mul",
+            BinOp::IShl(..) => "shl",
+            BinOp::IShr(..) => "ashr",
+            BinOp::UShr(..) => "lshr",
+
+            BinOp::IDiv(..) | BinOp::UDiv(..) | BinOp::IRem(..) | BinOp::URem(..) => {
+                let helper = if matches!(op, BinOp::IDiv(..) | BinOp::UDiv(..)) {
+                    "div"
+                } else {
+                    "rem"
+                };
+
+                self.div_tys.insert(ty);
+                return format!("call {name} @lang.{helper}.{ty}({name} {lhs}, {name} {rhs})");
+            }
+
+            _ => unreachable!(),
+        };
+
+        if !matches!(op, BinOp::IShl(..) | BinOp::IShr(..) | BinOp::UShr(..)) {
+            return format!("{ins} {name} {lhs}, {rhs}");
+        }
+
+        let amount = format!("{}.amount", self::name(dest));
+        self.line(&format!("  {amount} = and {name} {rhs}, {}", ty.bits() - 1));
+
+        format!("{ins} {name} {lhs}, {amount}")
+    }
+
+    /// Conversions to and from floats. Float to integer casts saturate and map NaN to 0, which the
+    /// `fpto*.sat` intrinsics do.
+    fn float_cast(&mut self, arg: Operand, ty: &MirType) -> String {
+        let (from, to) = (self.ty(arg), ty_name(Some(ty)));
+
+        let cast = match (self.reg_tys.operand(arg), ty) {
+            (Some(MirType::Float), MirType::Float) => "bitcast",
+            (Some(MirType::Bool), _) => "uitofp",
+            (Some(MirType::Int(ty)), _) if !ty.is_signed() => "uitofp",
+            (_, MirType::Float) => "sitofp",
+
+            (_, ty) => {
+                let cast = match ty {
+                    MirType::Int(ty) if !ty.is_signed() => "fptoui",
+                    _ => "fptosi",
+                };
+
+                self.float_casts.insert((cast, to));
+                return format!(
+                    "call {to} @llvm.{cast}.sat.{to}.f64(double {})",
+                    operand(arg)
+                );
+            }
+        };
+
+        format!("{cast} {from} {} to {to}", operand(arg))
+    }
+
+    /// `main` returns an `i32` exit code, so other values are converted as if cast to `num`.
+    fn exit_code(&mut self, block: &BasicBlock, value: Operand) -> String {
+        if self.reg_tys.operand(value) == Some(MirType::Float) {
+            let code = format!("%exit.{}", block.id);
+            let cast = self.float_cast(value, &MirType::Num);
+            self.line(&format!("  {code} = {cast}"));
+
+            return format!("ret i32 {code}");
+        }
+
+        let cast = int_cast(self.reg_tys.operand(value).as_ref(), &MirType::Num);
+
+        if cast == "bitcast" {
+            return format!("ret i32 {}", operand(value));
+        }
+
+        let code = format!("%exit.{}", block.id);
+        self.line(&format!(
+            "  {code} = {cast} {} {} to i32",
+            self.ty(value),
+            operand(value)
+        ));
+
+        format!("ret i32 {code}")
+    }
+
+    fn ty(&self, operand: Operand) -> &'static str {
+        match operand {
+            Operand::Num(..) => "i32",
+            Operand::Int(value) => ty_name(Some(&MirType::Int(value.ty()))),
+            Operand::Bool(..) => "i1",
+            Operand::Str(..) => ty_name(Some(&MirType::Str)),
+            Operand::Float(..) => ty_name(Some(&MirType::Float)),
+            Operand::Reg(reg) => ty_name(self.reg_tys.get(reg)),
+        }
+    }
+
+    fn line(&mut self, line: &str) {
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+}
+
+fn ty_name(ty: Option<&MirType>) -> &'static str {
+    match ty {
+        Some(MirType::Num) => "i32",
+        Some(MirType::Bool) => "i1",
+        Some(MirType::Int(ty)) => int_name(*ty),
+        Some(MirType::Float) => "double",
+        Some(MirType::Str) => "%lang.str*",
+        None => "void",
+    }
+}
+
+/// The conversion between integer types that matches constant folding: narrowing truncates, and
+/// widening extends according to the signedness of the source.
+fn int_cast(from: Option<&MirType>, to: &MirType) -> &'static str {
+    let bits = |ty: Option<&MirType>| match ty {
+        Some(MirType::Bool) => (1, false),
+        Some(MirType::Int(ty)) => (ty.bits(), ty.is_signed()),
+        _ => (32, true),
+    };
+
+    let ((from_bits, signed), (to_bits, _)) = (bits(from), bits(Some(to)));
+
+    match from_bits.cmp(&to_bits) {
+        Ordering::Greater => "trunc",
+        Ordering::Less if signed => "sext",
+        Ordering::Less => "zext",
+        Ordering::Equal => "bitcast",
+    }
+}
+
+fn int_name(ty: IntType) -> &'static str {
+    match ty.bits() {
+        8 => "i8",
+        16 => "i16",
+        32 => "i32",
+        _ => "i64",
+    }
+}
+
+fn str_name(id: StrID) -> String {
+    format!("@lang.str.{}", id.0)
+}
+
+fn name(reg: Reg) -> String {
+    format!("%r{}", reg.0)
+}
+
+fn operand(operand: Operand) -> String {
+    match operand {
+        Operand::Num(value) => value.to_string(),
+        Operand::Bool(value) => value.to_string(),
+        Operand::Int(value) => int_literal(value).to_string(),
+        Operand::Float(value) => format!("0x{:016X}", value.value().to_bits()),
+        Operand::Str(id) => str_name(id),
+        Operand::Reg(reg) => name(reg),
+    }
+}
+
+/// LLVM integers have no sign, so unsigned constants are written as their two's complement bits.
+fn int_literal(value: Int) -> i128 {
+    let shift = 128 - value.ty().bits();
+    (value.value() << shift) >> shift
+}
+
+/// Escapes a string for an LLVM `c"..."` constant, which takes two hex digits after a backslash.
+fn escape(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b' '..=b'~' if !matches!(byte, b'"' | b'\\') => char::from(byte).to_string(),
+            _ => format!("\\{byte:02X}"),
+        })
+        .collect()
+}
@@ -0,0 +1,140 @@
+pub use crate::codegen::wasm::lower::lower_module;
+
+use crate::float::Float;
+
+mod encode;
+mod lower;
+mod wat;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmModule {
+    pub types: Vec<FuncType>,
+    pub funcs: Vec<Func>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuncType {
+    pub params: Vec<ValType>,
+    pub result: Option<ValType>,
+}
+
+/// `num`, bools and narrow sized integers are `i32`s, `i64` and `u64` are `i64`s, and floats are
+/// `f64`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValType {
+    I32,
+    I64,
+    F64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Func {
+    pub name: String,
+    pub ty: u32,
+    pub locals: Vec<ValType>,
+    pub body: Vec<Instr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instr {
+    Unreachable,
+    Block,
+    Loop,
+    If,
+    Else,
+    End,
+    Br(u32),
+    BrIf(u32),
+    Return,
+    Call(u32),
+    Select,
+
+    LocalGet(u32),
+    LocalSet(u32),
+    I32Const(i32),
+    I64Const(i64),
+    F64Const(Float),
+
+    I32Eqz,
+    I32Eq,
+    I32Ne,
+    I32LtS,
+    I32LtU,
+    I32GtS,
+    I32GtU,
+    I32LeS,
+    I32LeU,
+    I32GeS,
+    I32GeU,
+
+    I32Add,
+    I32Sub,
+    I32Mul,
+    I32DivS,
+    I32DivU,
+    I32RemS,
+    I32RemU,
+    I32And,
+    I32Or,
+    I32Xor,
+    I32Shl,
+    I32ShrS,
+    I32ShrU,
+
+    I32Extend8S,
+    I32Extend16S,
+
+    I64Eqz,
+    I64Eq,
+    I64Ne,
+    I64LtS,
+    I64LtU,
+    I64GtS,
+    I64GtU,
+    I64LeS,
+    I64LeU,
+    I64GeS,
+    I64GeU,
+
+    I64Add,
+    I64Sub,
+    I64Mul,
+    I64DivS,
+    I64DivU,
+    I64RemS,
+    I64RemU,
+    I64And,
+    I64Or,
+    I64Xor,
+    I64Shl,
+    I64ShrS,
+    I64ShrU,
+
+    F64Eq,
+    F64Ne,
+    F64Lt,
+    F64Gt,
+    F64Le,
+    F64Ge,
+
+    F64Neg,
+    F64Add,
+    F64Sub,
+    F64Mul,
+    F64Div,
+    F64Min,
+    F64Max,
+
+    I32WrapI64,
+    I64ExtendI32S,
+    I64ExtendI32U,
+
+    I32TruncSatF64S,
+    I32TruncSatF64U,
+    I64TruncSatF64S,
+    I64TruncSatF64U,
+    F64ConvertI32S,
+    F64ConvertI32U,
+    F64ConvertI64S,
+    F64ConvertI64U,
+}
@@ -0,0 +1,243 @@
+use itertools::Itertools as _;
+
+use crate::codegen::wasm::{Instr, ValType, WasmModule};
+
+const EMPTY: u8 = 0x40;
+
+impl WasmModule {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = b"\0asm".to_vec();
+        out.extend(1u32.to_le_bytes());
+
+        let mut types = Vec::new();
+        uleb(&mut types, self.types.len());
+
+        for ty in &self.types {
+            types.push(0x60);
+            uleb(&mut types, ty.params.len());
+            types.extend(ty.params.iter().map(|ty| val_type(*ty)));
+            uleb(&mut types, usize::from(ty.result.is_some()));
+            types.extend(ty.result.map(val_type));
+        }
+
+        section(&mut out, 1, &types);
+
+        let mut funcs = Vec::new();
+        uleb(&mut funcs, self.funcs.len());
+
+        for func in &self.funcs {
+            uleb(&mut funcs, func.ty as usize);
+        }
+
+        section(&mut out, 3, &funcs);
+
+        let mut exports = Vec::new();
+        uleb(&mut exports, self.funcs.len());
+
+        for (idx, func) in self.funcs.iter().enumerate() {
+            uleb(&mut exports, func.name.len());
+            exports.extend(func.name.as_bytes());
+            exports.push(0x00);
+            uleb(&mut exports, idx);
+        }
+
+        section(&mut out, 7, &exports);
+
+        let mut code = Vec::new();
+        uleb(&mut code, self.funcs.len());
+
+        for func in &self.funcs {
+            let mut body = Vec::new();
+            let locals = func.locals.iter().dedup_with_count().collect::<Vec<_>>();
+
+            uleb(&mut body, locals.len());
+
+            for (count, ty) in locals {
+                uleb(&mut body, count);
+                body.push(val_type(*ty));
+            }
+
+            for instr in &func.body {
+                encode_instr(&mut body, *instr);
+            }
+
+            body.push(0x0b);
+
+            uleb(&mut code, body.len());
+            code.extend(body);
+        }
+
+        section(&mut out, 10, &code);
+        out
+    }
+}
+
+fn encode_instr(out: &mut Vec<u8>, instr: Instr) {
+    match instr {
+        Instr::Unreachable => out.push(0x00),
+        Instr::Block => out.extend([0x02, EMPTY]),
+        Instr::Loop => out.extend([0x03, EMPTY]),
+        Instr::If => out.extend([0x04, EMPTY]),
+        Instr::Else => out.push(0x05),
+        Instr::End => out.push(0x0b),
+        Instr::Return => out.push(0x0f),
+        Instr::Select => out.push(0x1b),
+
+        Instr::Br(label) => indexed(out, 0x0c, label),
+        Instr::BrIf(label) => indexed(out, 0x0d, label),
+        Instr::Call(func) => indexed(out, 0x10, func),
+        Instr::LocalGet(local) => indexed(out, 0x20, local),
+        Instr::LocalSet(local) => indexed(out, 0x21, local),
+
+        Instr::I32Const(value) => {
+            out.push(0x41);
+            sleb(out, value.into());
+        }
+
+        Instr::I64Const(value) => {
+            out.push(0x42);
+            sleb(out, value);
+        }
+
+        Instr::I32Eqz => out.push(0x45),
+        Instr::I32Eq => out.push(0x46),
+        Instr::I32Ne => out.push(0x47),
+        Instr::I32LtS => out.push(0x48),
+        Instr::I32LtU => out.push(0x49),
+        Instr::I32GtS => out.push(0x4a),
+        Instr::I32GtU => out.push(0x4b),
+        Instr::I32LeS => out.push(0x4c),
+        Instr::I32LeU => out.push(0x4d),
+        Instr::I32GeS => out.push(0x4e),
+        Instr::I32GeU => out.push(0x4f),
+
+        Instr::I32Add => out.push(0x6a),
+        Instr::I32Sub => out.push(0x6b),
+        Instr::I32Mul => out.push(0x6c),
+        Instr::I32DivS => out.push(0x6d),
+        Instr::I32DivU => out.push(0x6e),
+        Instr::I32RemS => out.push(0x6f),
+        Instr::I32RemU => out.push(0x70),
+        Instr::I32And => out.push(0x71),
+        Instr::I32Or => out.push(0x72),
+        Instr::I32Xor => out.push(0x73),
+        Instr::I32Shl => out.push(0x74),
+        Instr::I32ShrS => out.push(0x75),
+        Instr::I32ShrU => out.push(0x76),
+
+        Instr::I32Extend8S => out.push(0xc0),
+        Instr::I32Extend16S => out.push(0xc1),
+
+        Instr::I64Eqz => out.push(0x50),
+        Instr::I64Eq => out.push(0x51),
+        Instr::I64Ne => out.push(0x52),
+        Instr::I64LtS => out.push(0x53),
+        Instr::I64LtU => out.push(0x54),
+        Instr::I64GtS => out.push(0x55),
+        Instr::I64GtU => out.push(0x56),
+        Instr::I64LeS => out.push(0x57),
+        Instr::I64LeU => out.push(0x58),
+        Instr::I64GeS => out.push(0x59),
+        Instr::I64GeU => out.push(0x5a),
+
+        Instr::I64Add => out.push(0x7c),
+        Instr::I64Sub => out.push(0x7d),
+        Instr::I64Mul => out.push(0x7e),
+        Instr::I64DivS => out.push(0x7f),
+        Instr::I64DivU => out.push(0x80),
+        Instr::I64RemS => out.push(0x81),
+        Instr::I64RemU => out.push(0x82),
+        Instr::I64And => out.push(0x83),
+        Instr::I64Or => out.push(0x84),
+        Instr::I64Xor => out.push(0x85),
+        Instr::I64Shl => out.push(0x86),
+        Instr::I64ShrS => out.push(0x87),
+        Instr::I64ShrU => out.push(0x88),
+
+        Instr::I32WrapI64 => out.push(0xa7),
+        Instr::I64ExtendI32S => out.push(0xac),
+        Instr::I64ExtendI32U => out.push(0xad),
+
+        _ => encode_float_instr(out, instr),
+    }
+}
+
+fn encode_float_instr(out: &mut Vec<u8>, instr: Instr) {
+    match instr {
+        Instr::F64Const(value) => {
+            out.push(0x44);
+            out.extend(value.value().to_le_bytes());
+        }
+
+        Instr::F64Eq => out.push(0x61),
+        Instr::F64Ne => out.push(0x62),
+        Instr::F64Lt => out.push(0x63),
+        Instr::F64Gt => out.push(0x64),
+        Instr::F64Le => out.push(0x65),
+        Instr::F64Ge => out.push(0x66),
+
+        Instr::F64Neg => out.push(0x9a),
+        Instr::F64Add => out.push(0xa0),
+        Instr::F64Sub => out.push(0xa1),
+        Instr::F64Mul => out.push(0xa2),
+        Instr::F64Div => out.push(0xa3),
+        Instr::F64Min => out.push(0xa4),
+        Instr::F64Max => out.push(0xa5),
+
+        Instr::I32TruncSatF64S => out.extend([0xfc, 2]),
+        Instr::I32TruncSatF64U => out.extend([0xfc, 3]),
+        Instr::I64TruncSatF64S => out.extend([0xfc, 6]),
+        Instr::I64TruncSatF64U => out.extend([0xfc, 7]),
+        Instr::F64ConvertI32S => out.push(0xb7),
+        Instr::F64ConvertI32U => out.push(0xb8),
+        Instr::F64ConvertI64S => out.push(0xb9),
+        Instr::F64ConvertI64U => out.push(0xba),
+
+        _ => unreachable!(),
+    }
+}
+
+fn val_type(ty: ValType) -> u8 {
+    match ty {
+        ValType::I32 => 0x7f,
+        ValType::I64 => 0x7e,
+        ValType::F64 => 0x7c,
+    }
+}
+
+fn indexed(out: &mut Vec<u8>, opcode: u8, idx: u32) {
+    out.push(opcode);
+    uleb(out, idx as usize);
+}
+
+fn section(out: &mut Vec<u8>, id: u8, contents: &[u8]) {
+    out.push(id);
+    uleb(out, contents.len());
+    out.extend(contents);
+}
+
+fn uleb(out: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = u8::try_from(value & 0x7f).unwrap();
+        value >>= 7;
+
+        if value == 0 {
+            return out.push(byte);
+        }
+
+        out.push(byte | 0x80);
+    }
+}
+
+fn sleb(out: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = u8::try_from(value & 0x7f).unwrap();
+        value >>= 7;
+
+        if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+            return out.push(byte);
+        }
+
+        out.push(byte | 0x80);
+    }
+}
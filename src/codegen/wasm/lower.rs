@@ -0,0 +1,661 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    codegen::{
+        self,
+        error::CodegenError,
+        wasm::{Func, FuncType, Instr, ValType, WasmModule},
+    },
+    int::IntType,
+    mir::{BasicBlock, BlockID, InstrKind, MirFun, MirModule, MirType, Operand, Reg, Term},
+    mir_analysis::{dominators::Dominators, reg_types::RegTypes},
+    mir_passes,
+    ops::{BinOp, UnOp},
+};
+
+#[derive(Debug, Clone, Copy)]
+enum Frame {
+    IfThenElse,
+    LoopHeadedBy(BlockID),
+    BlockFollowedBy(BlockID),
+}
+
+pub fn lower_module(module: &MirModule) -> Result<WasmModule, CodegenError> {
+    codegen::check_types(module, "wasm", |ty| *ty != MirType::Str)?;
+
+    let mut module = module.clone();
+    mir_passes::out_of_ssa(&mut module);
+
+    let indices = module
+        .funs
+        .iter()
+        .zip(0..)
+        .map(|(fun, idx)| (fun.name.as_str(), (idx, fun.return_ty.is_some())))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut types = Vec::new();
+    let mut funcs = Vec::new();
+
+    for fun in &module.funs {
+        let ty = FuncType {
+            params: fun.params.iter().map(|(_, ty)| val_type(ty)).collect(),
+            result: fun.return_ty.as_ref().map(|ty| result_type(fun, ty)),
+        };
+
+        let ty = types
+            .iter()
+            .position(|other| *other == ty)
+            .unwrap_or_else(|| {
+                types.push(ty);
+                types.len() - 1
+            });
+
+        let reg_tys = RegTypes::new(&module, fun);
+        funcs.push(FunLowering::new(fun, &indices, reg_tys).lower(u32::try_from(ty).unwrap()));
+    }
+
+    Ok(WasmModule { types, funcs })
+}
+
+struct FunLowering<'a> {
+    fun: &'a MirFun,
+    funs: &'a BTreeMap<&'a str, (u32, bool)>,
+    reg_tys: RegTypes,
+    doms: Dominators,
+    rpo: BTreeMap<BlockID, usize>,
+    locals: BTreeMap<Reg, u32>,
+    context: Vec<Frame>,
+    body: Vec<Instr>,
+}
+
+impl<'a> FunLowering<'a> {
+    fn new(fun: &'a MirFun, funs: &'a BTreeMap<&'a str, (u32, bool)>, reg_tys: RegTypes) -> Self {
+        let doms = Dominators::new(fun);
+        let rpo = doms.reverse_postorder().zip(0..).collect();
+
+        let mut locals = fun
+            .params
+            .iter()
+            .map(|(reg, _)| *reg)
+            .zip(0..)
+            .collect::<BTreeMap<_, _>>();
+
+        for instr in fun.blocks.iter().flat_map(|block| &block.instrs) {
+            let next = u32::try_from(locals.len()).unwrap();
+            locals.entry(instr.dest).or_insert(next);
+        }
+
+        Self {
+            fun,
+            funs,
+            reg_tys,
+            doms,
+            rpo,
+            locals,
+            context: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    fn lower(mut self, ty: u32) -> Func {
+        if let Some(entry) = self.fun.blocks.first() {
+            self.do_tree(entry.id);
+        }
+
+        self.body.push(Instr::Unreachable);
+
+        let mut locals = self
+            .locals
+            .iter()
+            .filter(|(_, idx)| **idx as usize >= self.fun.params.len())
+            .map(|(reg, idx)| (*idx, self.reg_tys.get(*reg).map_or(ValType::I32, val_type)))
+            .collect::<Vec<_>>();
+
+        locals.sort_unstable_by_key(|(idx, _)| *idx);
+
+        Func {
+            name: self.fun.name.clone(),
+            ty,
+            locals: locals.into_iter().map(|(_, ty)| ty).collect(),
+            body: self.body,
+        }
+    }
+
+    fn do_tree(&mut self, id: BlockID) {
+        let mut merges = self
+            .doms
+            .children(id)
+            .iter()
+            .copied()
+            .filter(|child| self.is_merge(*child))
+            .collect::<Vec<_>>();
+
+        merges.sort_by_key(|merge| std::cmp::Reverse(self.rpo[merge]));
+
+        if self.is_loop_header(id) {
+            self.body.push(Instr::Loop);
+            self.context.push(Frame::LoopHeadedBy(id));
+            self.node_within(id, &merges);
+            self.context.pop();
+            self.body.push(Instr::End);
+        } else {
+            self.node_within(id, &merges);
+        }
+    }
+
+    fn node_within(&mut self, id: BlockID, merges: &[BlockID]) {
+        let Some((merge, rest)) = merges.split_first() else {
+            let fun = self.fun;
+            return self.block(&fun.blocks[id]);
+        };
+
+        self.body.push(Instr::Block);
+        self.context.push(Frame::BlockFollowedBy(*merge));
+        self.node_within(id, rest);
+        self.context.pop();
+        self.body.push(Instr::End);
+
+        self.do_tree(*merge);
+    }
+
+    fn block(&mut self, block: &BasicBlock) {
+        for instr in &block.instrs {
+            self.instr(instr.dest, &instr.kind);
+        }
+
+        match &block.term {
+            Some(Term::Jump { target }) => self.do_branch(block.id, *target),
+
+            Some(Term::Branch {
+                cond,
+                then_block,
+                else_block,
+            }) => self.branch(block.id, *cond, *then_block, *else_block),
+
+            Some(Term::Return { value }) => {
+                if let Some(value) = value {
+                    self.operand(*value);
+
+                    if self.fun.name == "main"
+                        && self.reg_tys.operand(*value) == Some(MirType::Float)
+                    {
+                        self.body.push(Instr::I32TruncSatF64S);
+                    }
+                }
+
+                self.body.push(Instr::Return);
+            }
+
+            None => self.body.push(Instr::Return),
+        }
+    }
+
+    fn branch(&mut self, id: BlockID, cond: Operand, then_block: BlockID, else_block: BlockID) {
+        if then_block == else_block {
+            return self.do_branch(id, then_block);
+        }
+
+        self.operand(cond);
+
+        if self.is_br(id, then_block) {
+            self.body.push(Instr::BrIf(self.label(then_block)));
+            self.do_branch(id, else_block);
+        } else if self.is_br(id, else_block) {
+            self.body.push(Instr::I32Eqz);
+            self.body.push(Instr::BrIf(self.label(else_block)));
+            self.do_branch(id, then_block);
+        } else {
+            self.body.push(Instr::If);
+            self.context.push(Frame::IfThenElse);
+            self.do_branch(id, then_block);
+            self.body.push(Instr::Else);
+            self.do_branch(id, else_block);
+            self.context.pop();
+            self.body.push(Instr::End);
+        }
+    }
+
+    fn do_branch(&mut self, source: BlockID, target: BlockID) {
+        if self.is_br(source, target) {
+            self.body.push(Instr::Br(self.label(target)));
+        } else {
+            self.do_tree(target);
+        }
+    }
+
+    fn unary(&mut self, op: UnOp, arg: Operand) {
+        let wide = self.wide(arg);
+
+        match op {
+            UnOp::Negate => {
+                self.push(wide, Instr::I32Const(0));
+                self.operand(arg);
+                self.push(wide, Instr::I32Sub);
+                self.extend(arg);
+            }
+
+            UnOp::Not => {
+                self.operand(arg);
+                self.body.push(Instr::I32Eqz);
+            }
+
+            UnOp::BitNot => {
+                self.operand(arg);
+                self.push(wide, Instr::I32Const(-1));
+                self.push(wide, Instr::I32Xor);
+                self.extend(arg);
+            }
+
+            UnOp::FNegate => {
+                self.operand(arg);
+                self.body.push(Instr::F64Neg);
+            }
+        }
+    }
+
+    fn instr(&mut self, dest: Reg, kind: &InstrKind) {
+        match kind {
+            InstrKind::Copy { src } => self.operand(*src),
+
+            InstrKind::Cast { arg, ty } if self.reg_tys.operand(*arg) == Some(MirType::Float) => {
+                self.float_to_int(*arg, ty);
+            }
+
+            InstrKind::Cast {
+                arg,
+                ty: MirType::Float,
+            } => self.int_to_float(*arg),
+
+            InstrKind::Cast {
+                arg,
+                ty: MirType::Num | MirType::Bool,
+            } => {
+                self.operand(*arg);
+
+                if self.wide(*arg) {
+                    self.body.push(Instr::I32WrapI64);
+                }
+            }
+
+            InstrKind::Cast {
+                arg,
+                ty: MirType::Int(ty),
+            } => self.cast_int(*arg, *ty),
+
+            InstrKind::Cast {
+                ty: MirType::Str, ..
+            } => unreachable!(),
+
+            InstrKind::Unary { op, arg } => self.unary(*op, *arg),
+
+            InstrKind::Binary {
+                op: BinOp::Div | BinOp::IDiv(IntType::I64),
+                lhs,
+                rhs,
+            } => self.div(*lhs, *rhs),
+
+            InstrKind::Binary {
+                op:
+                    op @ (BinOp::IAdd(ty)
+                    | BinOp::ISub(ty)
+                    | BinOp::IMul(ty)
+                    | BinOp::IDiv(ty)
+                    | BinOp::UDiv(ty)
+                    | BinOp::IRem(ty)
+                    | BinOp::URem(ty)
+                    | BinOp::IShl(ty)
+                    | BinOp::IShr(ty)
+                    | BinOp::UShr(ty)),
+                lhs,
+                rhs,
+            } => self.int_binary(*op, *ty, *lhs, *rhs),
+
+            InstrKind::Binary { op, lhs, rhs } => self.binary(*op, *lhs, *rhs),
+
+            InstrKind::Call { name, args } => {
+                for arg in args {
+                    self.operand(*arg);
+                }
+
+                let (idx, result) = self.funs[name.as_str()];
+                self.body.push(Instr::Call(idx));
+
+                if !result {
+                    return;
+                }
+            }
+        }
+
+        self.body.push(Instr::LocalSet(self.locals[&dest]));
+    }
+
+    fn binary(&mut self, op: BinOp, lhs: Operand, rhs: Operand) {
+        let wide = self.wide(lhs);
+
+        self.operand(lhs);
+        self.operand(rhs);
+
+        let instr = match op {
+            BinOp::Add => Instr::I32Add,
+            BinOp::Sub => Instr::I32Sub,
+            BinOp::Mul => Instr::I32Mul,
+            BinOp::Div => Instr::I32DivS,
+            BinOp::Rem => Instr::I32RemS,
+            BinOp::And | BinOp::BitAnd | BinOp::IAnd(..) => Instr::I32And,
+            BinOp::Or | BinOp::BitOr | BinOp::IOr(..) => Instr::I32Or,
+            BinOp::BitXor | BinOp::IXor(..) => Instr::I32Xor,
+            BinOp::Shl => Instr::I32Shl,
+            BinOp::Shr => Instr::I32ShrS,
+            BinOp::Eq => Instr::I32Eq,
+            BinOp::NotEq => Instr::I32Ne,
+            BinOp::Lesser => Instr::I32LtS,
+            BinOp::LesserEq => Instr::I32LeS,
+            BinOp::Greater => Instr::I32GtS,
+            BinOp::GreaterEq => Instr::I32GeS,
+            BinOp::ULesser => Instr::I32LtU,
+            BinOp::ULesserEq => Instr::I32LeU,
+            BinOp::UGreater => Instr::I32GtU,
+            BinOp::UGreaterEq => Instr::I32GeU,
+            BinOp::FAdd => Instr::F64Add,
+            BinOp::FSub => Instr::F64Sub,
+            BinOp::FMul => Instr::F64Mul,
+            BinOp::FDiv => Instr::F64Div,
+            BinOp::FEq => Instr::F64Eq,
+            BinOp::FNotEq => Instr::F64Ne,
+            BinOp::FLesser => Instr::F64Lt,
+            BinOp::FLesserEq => Instr::F64Le,
+            BinOp::FGreater => Instr::F64Gt,
+            BinOp::FGreaterEq => Instr::F64Ge,
+            BinOp::IAdd(..)
+            | BinOp::ISub(..)
+            | BinOp::IMul(..)
+            | BinOp::IDiv(..)
+            | BinOp::UDiv(..)
+            | BinOp::IRem(..)
+            | BinOp::URem(..)
+            | BinOp::IShl(..)
+            | BinOp::IShr(..)
+            | BinOp::UShr(..)
+            | BinOp::Concat => unreachable!(),
+        };
+
+        self.push(wide, instr);
+    }
+
+    /// Signed division of `num`s or `i64`s, where dividing the minimum by -1 wraps instead of
+    /// trapping.
+    fn div(&mut self, lhs: Operand, rhs: Operand) {
+        let wide = self.wide(lhs);
+
+        if rhs.is_const() && rhs.as_int() == -1 {
+            self.push(wide, Instr::I32Const(0));
+            self.operand(lhs);
+            self.push(wide, Instr::I32Sub);
+            return;
+        }
+
+        if rhs.is_const() {
+            self.operand(lhs);
+            self.operand(rhs);
+            self.push(wide, Instr::I32DivS);
+            return;
+        }
+
+        self.push(wide, Instr::I32Const(0));
+        self.operand(lhs);
+        self.push(wide, Instr::I32Sub);
+
+        self.operand(lhs);
+        self.push(wide, Instr::I32Const(1));
+        self.operand(rhs);
+        self.is_minus_one(rhs);
+        self.body.push(Instr::Select);
+        self.push(wide, Instr::I32DivS);
+
+        self.is_minus_one(rhs);
+        self.body.push(Instr::Select);
+    }
+
+    /// Narrow sized integers live sign- or zero-extended in 32 bits, so casts between them and
+    /// `i64` or `u64` extend or wrap.
+    fn cast_int(&mut self, arg: Operand, ty: IntType) {
+        self.operand(arg);
+
+        match (self.wide(arg), ty.bits() == 64) {
+            (true, true) => {}
+
+            (true, false) => {
+                self.body.push(Instr::I32WrapI64);
+                self.extend_int(ty);
+            }
+
+            (false, true) => self.body.push(
+                if let Some(MirType::Num | MirType::Int(IntType::I8 | IntType::I16)) =
+                    self.reg_tys.operand(arg)
+                {
+                    Instr::I64ExtendI32S
+                } else {
+                    Instr::I64ExtendI32U
+                },
+            ),
+
+            (false, false) => self.extend_int(ty),
+        }
+    }
+
+    /// Float to integer casts saturate at the bounds of the target type and map NaN to 0, like `as`
+    /// in Rust. Narrow sized integers are clamped to their bounds before truncating.
+    fn float_to_int(&mut self, arg: Operand, ty: &MirType) {
+        self.operand(arg);
+
+        let instr = match ty {
+            MirType::Float => return,
+            MirType::Num => Instr::I32TruncSatF64S,
+            MirType::Int(IntType::U32) => Instr::I32TruncSatF64U,
+            MirType::Int(IntType::I64) => Instr::I64TruncSatF64S,
+            MirType::Int(IntType::U64) => Instr::I64TruncSatF64U,
+
+            MirType::Int(ty) => {
+                let bound =
+                    |value| Instr::F64Const(f64::from(i32::try_from(value).unwrap()).into());
+
+                self.body.push(bound(ty.min_value()));
+                self.body.push(Instr::F64Max);
+                self.body.push(bound(ty.max_value()));
+                self.body.push(Instr::F64Min);
+
+                Instr::I32TruncSatF64S
+            }
+
+            MirType::Bool | MirType::Str => unreachable!(),
+        };
+
+        self.body.push(instr);
+    }
+
+    fn int_to_float(&mut self, arg: Operand) {
+        self.operand(arg);
+
+        self.body.push(match self.reg_tys.operand(arg) {
+            Some(MirType::Int(IntType::I64)) => Instr::F64ConvertI64S,
+            Some(MirType::Int(IntType::U64)) => Instr::F64ConvertI64U,
+            Some(MirType::Int(IntType::U32)) => Instr::F64ConvertI32U,
+            _ => Instr::F64ConvertI32S,
+        });
+    }
+
+    /// Sized integers live sign- or zero-extended in 32 bits, or in an `i64` if they're 64 bits
+    /// wide. Narrow division can't overflow, and shift amounts are masked to the width of the type.
+    fn int_binary(&mut self, op: BinOp, ty: IntType, lhs: Operand, rhs: Operand) {
+        self.operand(lhs);
+        self.operand(rhs);
+
+        if matches!(op, BinOp::IShl(..) | BinOp::IShr(..) | BinOp::UShr(..)) && ty.bits() < 32 {
+            self.body
+                .push(Instr::I32Const(i32::try_from(ty.bits() - 1).unwrap()));
+            self.body.push(Instr::I32And);
+        }
+
+        let instr = match op {
+            BinOp::IAdd(..) => Instr::I32Add,
+            BinOp::ISub(..) => Instr::I32Sub,
+            BinOp::IMul(..) => Instr::I32Mul,
+            BinOp::IDiv(..) => Instr::I32DivS,
+            BinOp::UDiv(..) => Instr::I32DivU,
+            BinOp::IRem(..) => Instr::I32RemS,
+            BinOp::URem(..) => Instr::I32RemU,
+            BinOp::IShl(..) => Instr::I32Shl,
+            BinOp::IShr(..) => Instr::I32ShrS,
+            BinOp::UShr(..) => Instr::I32ShrU,
+            _ => unreachable!(),
+        };
+
+        self.push(ty.bits() == 64, instr);
+
+        self.extend_int(ty);
+    }
+
+    fn extend(&mut self, operand: Operand) {
+        if let Some(MirType::Int(ty)) = self.reg_tys.operand(operand) {
+            self.extend_int(ty);
+        }
+    }
+
+    fn extend_int(&mut self, ty: IntType) {
+        match (ty.bits(), ty.is_signed()) {
+            (8, true) => self.body.push(Instr::I32Extend8S),
+            (16, true) => self.body.push(Instr::I32Extend16S),
+
+            (bits @ (8 | 16), false) => {
+                self.body.push(Instr::I32Const((1 << bits) - 1));
+                self.body.push(Instr::I32And);
+            }
+
+            _ => {}
+        }
+    }
+
+    fn is_minus_one(&mut self, operand: Operand) {
+        let wide = self.wide(operand);
+
+        self.operand(operand);
+        self.push(wide, Instr::I32Const(-1));
+        self.push(wide, Instr::I32Eq);
+    }
+
+    /// Pushes an `i32` instruction, or its `i64` version if `wide`.
+    fn push(&mut self, wide: bool, instr: Instr) {
+        self.body.push(if wide { widen(instr) } else { instr });
+    }
+
+    fn wide(&self, operand: Operand) -> bool {
+        self.reg_tys
+            .operand(operand)
+            .is_some_and(|ty| val_type(&ty) == ValType::I64)
+    }
+
+    fn operand(&mut self, operand: Operand) {
+        self.body.push(match operand {
+            Operand::Num(value) => Instr::I32Const(value),
+            Operand::Bool(value) => Instr::I32Const(i32::from(value)),
+            Operand::Int(value) if value.ty().bits() == 64 => {
+                Instr::I64Const(codegen::wide_int_bits(value))
+            }
+            Operand::Int(value) => Instr::I32Const(codegen::int_bits(value)),
+            Operand::Float(value) => Instr::F64Const(value),
+            Operand::Str(..) => unreachable!(),
+            Operand::Reg(reg) => Instr::LocalGet(self.locals[&reg]),
+        });
+    }
+
+    fn label(&self, target: BlockID) -> u32 {
+        let depth = self
+            .context
+            .iter()
+            .rev()
+            .position(|frame| match frame {
+                Frame::LoopHeadedBy(id) | Frame::BlockFollowedBy(id) => *id == target,
+                Frame::IfThenElse => false,
+            })
+            .unwrap();
+
+        u32::try_from(depth).unwrap()
+    }
+
+    fn is_br(&self, source: BlockID, target: BlockID) -> bool {
+        self.rpo[&target] <= self.rpo[&source] || self.is_merge(target)
+    }
+
+    fn is_merge(&self, id: BlockID) -> bool {
+        self.forward_preds(id) >= 2
+    }
+
+    fn is_loop_header(&self, id: BlockID) -> bool {
+        self.fun.cfg.predecessors(id).iter().any(|pred| {
+            self.rpo
+                .get(pred)
+                .is_some_and(|pred| *pred >= self.rpo[&id])
+        })
+    }
+
+    fn forward_preds(&self, id: BlockID) -> usize {
+        self.fun
+            .cfg
+            .predecessors(id)
+            .iter()
+            .filter(|pred| self.rpo.get(pred).is_some_and(|pred| *pred < self.rpo[&id]))
+            .count()
+    }
+}
+
+fn val_type(ty: &MirType) -> ValType {
+    match ty {
+        MirType::Int(ty) if ty.bits() == 64 => ValType::I64,
+        MirType::Float => ValType::F64,
+        _ => ValType::I32,
+    }
+}
+
+/// `main` returns an exit code, so a float result is truncated to a `num`.
+fn result_type(fun: &MirFun, ty: &MirType) -> ValType {
+    if fun.name == "main" && *ty == MirType::Float {
+        ValType::I32
+    } else {
+        val_type(ty)
+    }
+}
+
+/// The `i64` version of an `i32` instruction.
+fn widen(instr: Instr) -> Instr {
+    match instr {
+        Instr::I32Const(value) => Instr::I64Const(value.into()),
+
+        Instr::I32Eqz => Instr::I64Eqz,
+        Instr::I32Eq => Instr::I64Eq,
+        Instr::I32Ne => Instr::I64Ne,
+        Instr::I32LtS => Instr::I64LtS,
+        Instr::I32LtU => Instr::I64LtU,
+        Instr::I32GtS => Instr::I64GtS,
+        Instr::I32GtU => Instr::I64GtU,
+        Instr::I32LeS => Instr::I64LeS,
+        Instr::I32LeU => Instr::I64LeU,
+        Instr::I32GeS => Instr::I64GeS,
+        Instr::I32GeU => Instr::I64GeU,
+
+        Instr::I32Add => Instr::I64Add,
+        Instr::I32Sub => Instr::I64Sub,
+        Instr::I32Mul => Instr::I64Mul,
+        Instr::I32DivS => Instr::I64DivS,
+        Instr::I32DivU => Instr::I64DivU,
+        Instr::I32RemS => Instr::I64RemS,
+        Instr::I32RemU => Instr::I64RemU,
+        Instr::I32And => Instr::I64And,
+        Instr::I32Or => Instr::I64Or,
+        Instr::I32Xor => Instr::I64Xor,
+        Instr::I32Shl => Instr::I64Shl,
+        Instr::I32ShrS => Instr::I64ShrS,
+        Instr::I32ShrU => Instr::I64ShrU,
+
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt::{self, Display, Formatter};
+
+use itertools::Itertools as _;
+
+use crate::{
+    codegen::wasm::{FuncType, Instr, ValType, WasmModule},
+    float::Float,
+};
+
+impl Display for WasmModule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "(module")?;
+
+        for (idx, ty) in self.types.iter().enumerate() {
+            writeln!(f, "  (type (;{idx};) (func{ty}))")?;
+        }
+
+        for (idx, func) in self.funcs.iter().enumerate() {
+            let ty = &self.types[func.ty as usize];
+            writeln!(f, "  (func ${} (;{idx};) (type {}){ty}", func.name, func.ty)?;
+
+            if !func.locals.is_empty() {
+                writeln!(f, "    (local {})", func.locals.iter().join(" "))?;
+            }
+
+            let mut depth = 2;
+
+            for instr in &func.body {
+                if matches!(instr, Instr::Else | Instr::End) {
+                    depth -= 1;
+                }
+
+                write!(f, "{}", "  ".repeat(depth))?;
+
+                if let Instr::Call(callee) = instr {
+                    writeln!(f, "call ${}", self.funcs[*callee as usize].name)?;
+                } else {
+                    writeln!(f, "{instr}")?;
+                }
+
+                if matches!(instr, Instr::Block | Instr::Loop | Instr::If | Instr::Else) {
+                    depth += 1;
+                }
+            }
+
+            writeln!(f, "  )")?;
+        }
+
+        for func in &self.funcs {
+            writeln!(f, "  (export \"{0}\" (func ${0}))", func.name)?;
+        }
+
+        write!(f, ")")
+    }
+}
+
+impl Display for FuncType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.params.is_empty() {
+            write!(f, " (param {})", self.params.iter().join(" "))?;
+        }
+
+        if let Some(result) = self.result {
+            write!(f, " (result {result})")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for ValType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::I32 => write!(f, "i32"),
+            Self::I64 => write!(f, "i64"),
+            Self::F64 => write!(f, "f64"),
+        }
+    }
+}
+
+impl Display for Instr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreachable => write!(f, "unreachable"),
+            Self::Block => write!(f, "block"),
+            Self::Loop => write!(f, "loop"),
+            Self::If => write!(f, "if"),
+            Self::Else => write!(f, "else"),
+            Self::End => write!(f, "end"),
+            Self::Br(label) => write!(f, "br {label}"),
+            Self::BrIf(label) => write!(f, "br_if {label}"),
+            Self::Return => write!(f, "return"),
+            Self::Call(func) => write!(f, "call {func}"),
+            Self::Select => write!(f, "select"),
+
+            Self::LocalGet(local) => write!(f, "local.get {local}"),
+            Self::LocalSet(local) => write!(f, "local.set {local}"),
+            Self::I32Const(value) => write!(f, "i32.const {value}"),
+            Self::I64Const(value) => write!(f, "i64.const {value}"),
+            Self::F64Const(value) => write!(f, "f64.const {}", float_literal(*value)),
+
+            Self::I32Eqz => write!(f, "i32.eqz"),
+            Self::I32Eq => write!(f, "i32.eq"),
+            Self::I32Ne => write!(f, "i32.ne"),
+            Self::I32LtS => write!(f, "i32.lt_s"),
+            Self::I32LtU => write!(f, "i32.lt_u"),
+            Self::I32GtS => write!(f, "i32.gt_s"),
+            Self::I32GtU => write!(f, "i32.gt_u"),
+            Self::I32LeS => write!(f, "i32.le_s"),
+            Self::I32LeU => write!(f, "i32.le_u"),
+            Self::I32GeS => write!(f, "i32.ge_s"),
+            Self::I32GeU => write!(f, "i32.ge_u"),
+
+            Self::I32Add => write!(f, "i32.add"),
+            Self::I32Sub => write!(f, "i32.sub"),
+            Self::I32Mul => write!(f, "i32.mul"),
+            Self::I32DivS => write!(f, "i32.div_s"),
+            Self::I32DivU => write!(f, "i32.div_u"),
+            Self::I32RemS => write!(f, "i32.rem_s"),
+            Self::I32RemU => write!(f, "i32.rem_u"),
+            Self::I32And => write!(f, "i32.and"),
+            Self::I32Or => write!(f, "i32.or"),
+            Self::I32Xor => write!(f, "i32.xor"),
+            Self::I32Shl => write!(f, "i32.shl"),
+            Self::I32ShrS => write!(f, "i32.shr_s"),
+            Self::I32ShrU => write!(f, "i32.shr_u"),
+
+            Self::I32Extend8S => write!(f, "i32.extend8_s"),
+            Self::I32Extend16S => write!(f, "i32.extend16_s"),
+
+            Self::I64Eqz => write!(f, "i64.eqz"),
+            Self::I64Eq => write!(f, "i64.eq"),
+            Self::I64Ne => write!(f, "i64.ne"),
+            Self::I64LtS => write!(f, "i64.lt_s"),
+            Self::I64LtU => write!(f, "i64.lt_u"),
+            Self::I64GtS => write!(f, "i64.gt_s"),
+            Self::I64GtU => write!(f, "i64.gt_u"),
+            Self::I64LeS => write!(f, "i64.le_s"),
+            Self::I64LeU => write!(f, "i64.le_u"),
+            Self::I64GeS => write!(f, "i64.ge_s"),
+            Self::I64GeU => write!(f, "i64.ge_u"),
+
+            Self::I64Add => write!(f, "i64.add"),
+            Self::I64Sub => write!(f, "i64.sub"),
+            Self::I64Mul => write!(f, "i64.mul"),
+            Self::I64DivS => write!(f, "i64.div_s"),
+            Self::I64DivU => write!(f, "i64.div_u"),
+            Self::I64RemS => write!(f, "i64.rem_s"),
+            Self::I64RemU => write!(f, "i64.rem_u"),
+            Self::I64And => write!(f, "i64.and"),
+            Self::I64Or => write!(f, "i64.or"),
+            Self::I64Xor => write!(f, "i64.xor"),
+            Self::I64Shl => write!(f, "i64.shl"),
+            Self::I64ShrS => write!(f, "i64.shr_s"),
+            Self::I64ShrU => write!(f, "i64.shr_u"),
+
+            Self::F64Eq => write!(f, "f64.eq"),
+            Self::F64Ne => write!(f, "f64.ne"),
+            Self::F64Lt => write!(f, "f64.lt"),
+            Self::F64Gt => write!(f, "f64.gt"),
+            Self::F64Le => write!(f, "f64.le"),
+            Self::F64Ge => write!(f, "f64.ge"),
+
+            Self::F64Neg => write!(f, "f64.neg"),
+            Self::F64Add => write!(f, "f64.add"),
+            Self::F64Sub => write!(f, "f64.sub"),
+            Self::F64Mul => write!(f, "f64.mul"),
+            Self::F64Div => write!(f, "f64.div"),
+            Self::F64Min => write!(f, "f64.min"),
+            Self::F64Max => write!(f, "f64.max"),
+
+            Self::I32WrapI64 => write!(f, "i32.wrap_i64"),
+            Self::I64ExtendI32S => write!(f, "i64.extend_i32_s"),
+            Self::I64ExtendI32U => write!(f, "i64.extend_i32_u"),
+
+            Self::I32TruncSatF64S => write!(f, "i32.trunc_sat_f64_s"),
+            Self::I32TruncSatF64U => write!(f, "i32.trunc_sat_f64_u"),
+            Self::I64TruncSatF64S => write!(f, "i64.trunc_sat_f64_s"),
+            Self::I64TruncSatF64U => write!(f, "i64.trunc_sat_f64_u"),
+            Self::F64ConvertI32S => write!(f, "f64.convert_i32_s"),
+            Self::F64ConvertI32U => write!(f, "f64.convert_i32_u"),
+            Self::F64ConvertI64S => write!(f, "f64.convert_i64_s"),
+            Self::F64ConvertI64U => write!(f, "f64.convert_i64_u"),
+        }
+    }
+}
+
+/// NaN and infinities are spelled the way the text format expects.
+fn float_literal(value: Float) -> String {
+    let value = value.value();
+
+    if value.is_nan() {
+        "nan".into()
+    } else if value.is_infinite() {
+        if value > 0.0 { "inf" } else { "-inf" }.into()
+    } else {
+        format!("{value:?}")
+    }
+}
@@ -0,0 +1,782 @@
+use crate::{
+    codegen::{self, error::CodegenError},
+    int::IntType,
+    mir::{BasicBlock, BlockID, Instr, InstrKind, MirFun, MirModule, MirType, Operand, Reg, Term},
+    mir_analysis::reg_types::RegTypes,
+    mir_passes,
+    ops::{BinOp, UnOp},
+    regalloc::{
+        Allocation, LinearScan, Location,
+        machine::{Machine, PhysReg},
+    },
+};
+
+mod assembler;
+pub(crate) mod encode;
+
+const ARGS: [&str; 6] = ["rdi", "rsi", "rdx", "rcx", "r8", "r9"];
+const TEMP: &str = "%eax";
+const SCRATCH: &str = "%r10d";
+const WIDE_SCRATCH: &str = "%r10";
+
+pub fn machine() -> Machine {
+    let regs = [
+        "rcx", "rdx", "rsi", "rdi", "r8", "r9", "rbx", "r12", "r13", "r14", "r15",
+    ];
+
+    let mut machine = Machine {
+        regs: regs.map(String::from).to_vec(),
+        caller_saved: (0..6).map(PhysReg).collect(),
+        callee_saved: (6..11).map(PhysReg).collect(),
+        args: Vec::new(),
+    };
+
+    machine.args = ARGS.iter().map(|name| machine.reg(name).unwrap()).collect();
+
+    machine
+}
+
+/// Every value lives in general-purpose registers, so floats, which would need SSE registers and
+/// their own calling convention, are rejected along with strings.
+pub fn emit_module(module: &MirModule) -> Result<String, CodegenError> {
+    codegen::check_types(module, "x86-64", codegen::is_integral)?;
+
+    let mut module = module.clone();
+    mir_passes::out_of_ssa(&mut module);
+
+    let machine = machine();
+    let mut out = String::from("\t.text\n");
+
+    for fun in &module.funs {
+        let reg_tys = RegTypes::new(&module, fun);
+        FunEmitter::new(&machine, fun, reg_tys, &mut out).emit();
+    }
+
+    out.push_str("\t.section .note.GNU-stack,\"\",@progbits\n");
+    Ok(out)
+}
+
+struct FunEmitter<'a> {
+    machine: &'a Machine,
+    fun: &'a MirFun,
+    reg_tys: RegTypes,
+    allocation: Allocation,
+    out: &'a mut String,
+    frame: usize,
+    labels: usize,
+}
+
+impl<'a> FunEmitter<'a> {
+    fn new(machine: &'a Machine, fun: &'a MirFun, reg_tys: RegTypes, out: &'a mut String) -> Self {
+        Self {
+            machine,
+            fun,
+            reg_tys,
+            allocation: LinearScan::new(machine).allocate(fun),
+            out,
+            frame: 0,
+            labels: 0,
+        }
+    }
+
+    fn emit(mut self) {
+        let name = &self.fun.name;
+
+        self.raw(&format!("\t.globl {name}"));
+        self.raw(&format!("\t.type {name}, @function"));
+        self.raw(&format!("{name}:"));
+
+        self.prologue();
+
+        // Only the low byte of a bool argument is defined, the rest is cleared before it's used.
+        for ((_, ty), arg) in self.fun.params.iter().zip(ARGS) {
+            if *ty == MirType::Bool {
+                self.ins(&format!("movzbl %{}, %{}", reg8(arg), reg32(arg)));
+            }
+        }
+
+        let moves = self
+            .fun
+            .params
+            .iter()
+            .zip(ARGS)
+            .map(|((reg, ty), arg)| (self.reg(*reg), format!("%{}", reg32(arg)), is_wide(ty)))
+            .collect();
+
+        self.parallel_move(moves);
+
+        for (idx, (reg, ty)) in self.fun.params.iter().enumerate().skip(ARGS.len()) {
+            let src = format!("{}(%rbp)", 16 + 8 * (idx - ARGS.len()));
+            let dest = self.reg(*reg);
+
+            if *ty == MirType::Bool {
+                self.ins(&format!("movzbl {src}, {SCRATCH}"));
+                self.mov(&dest, SCRATCH);
+            } else {
+                self.mov_sized(is_wide(ty), &dest, &src);
+            }
+        }
+
+        for (idx, block) in self.fun.blocks.iter().enumerate() {
+            let next = self.fun.blocks.get(idx + 1).map(|block| block.id);
+            self.block(block, next);
+        }
+
+        self.raw(&format!("{}:", self.ret_label()));
+        self.epilogue();
+        self.raw(&format!("\t.size {name}, .-{name}"));
+    }
+
+    fn prologue(&mut self) {
+        self.ins("pushq %rbp");
+        self.ins("movq %rsp, %rbp");
+
+        for reg in self.allocation.used_callee_saved.clone() {
+            self.ins(&format!("pushq %{}", self.machine.name(reg)));
+        }
+
+        self.frame = self.allocation.stack_slots * 8;
+
+        if !(self.allocation.used_callee_saved.len() * 8 + self.frame).is_multiple_of(16) {
+            self.frame += 8;
+        }
+
+        if self.frame > 0 {
+            self.ins(&format!("subq ${}, %rsp", self.frame));
+        }
+    }
+
+    fn epilogue(&mut self) {
+        if self.frame > 0 {
+            self.ins(&format!("addq ${}, %rsp", self.frame));
+        }
+
+        for reg in self.allocation.used_callee_saved.clone().into_iter().rev() {
+            self.ins(&format!("popq %{}", self.machine.name(reg)));
+        }
+
+        self.ins("popq %rbp");
+        self.ins("ret");
+    }
+
+    fn block(&mut self, block: &BasicBlock, next: Option<BlockID>) {
+        self.raw(&format!("{}:", self.block_label(block.id)));
+
+        for instr in &block.instrs {
+            self.instr(instr);
+        }
+
+        match &block.term {
+            Some(Term::Jump { target }) => self.jump(*target, next),
+
+            Some(Term::Branch {
+                cond: Operand::Bool(cond),
+                then_block,
+                else_block,
+            }) => self.jump(if *cond { *then_block } else { *else_block }, next),
+
+            Some(Term::Branch {
+                cond,
+                then_block,
+                else_block,
+            }) => {
+                self.ins(&format!("cmpb $0, {}", self.byte(*cond)));
+
+                if next == Some(*then_block) {
+                    self.ins(&format!("je {}", self.block_label(*else_block)));
+                } else {
+                    self.ins(&format!("jne {}", self.block_label(*then_block)));
+                    self.jump(*else_block, next);
+                }
+            }
+
+            Some(Term::Return { value: Some(value) }) => {
+                let wide = self.fun.return_ty.as_ref().is_some_and(is_wide);
+                self.mov_sized(wide, TEMP, &self.operand(*value));
+                self.ret(next);
+            }
+
+            Some(Term::Return { value: None }) | None => {
+                if self.fun.name == "main" {
+                    self.ins("xorl %eax, %eax");
+                }
+
+                self.ret(next);
+            }
+        }
+    }
+
+    fn instr(&mut self, instr: &Instr) {
+        let dest = self.reg(instr.dest);
+        let wide = self.reg_tys.get(instr.dest).is_some_and(is_wide);
+
+        match &instr.kind {
+            InstrKind::Copy { src }
+            | InstrKind::Cast {
+                arg: src,
+                ty: MirType::Num | MirType::Bool,
+            } => {
+                self.mov_sized(wide, &dest, &self.operand(*src));
+                return;
+            }
+
+            InstrKind::Cast { arg, .. } if wide => self.widen(*arg),
+
+            InstrKind::Cast {
+                arg,
+                ty: MirType::Int(ty),
+            } => {
+                self.mov(TEMP, &self.operand(*arg));
+                self.extend(*ty);
+            }
+
+            InstrKind::Cast {
+                ty: MirType::Float | MirType::Str,
+                ..
+            } => unreachable!(),
+
+            InstrKind::Unary { op, arg } if wide => {
+                self.movq(TEMP, &self.operand(*arg));
+
+                match op {
+                    UnOp::Negate => self.ins("negq %rax"),
+                    UnOp::BitNot => self.ins("notq %rax"),
+                    UnOp::Not | UnOp::FNegate => unreachable!(),
+                }
+            }
+
+            InstrKind::Unary { op, arg } => {
+                self.mov(TEMP, &self.operand(*arg));
+
+                match op {
+                    UnOp::Negate => self.ins("negl %eax"),
+                    UnOp::Not => self.ins("xorb $1, %al"),
+                    UnOp::BitNot => self.ins("notl %eax"),
+                    UnOp::FNegate => unreachable!(),
+                }
+
+                if let Some(MirType::Int(ty)) = self.reg_tys.operand(*arg) {
+                    self.extend(ty);
+                }
+            }
+
+            InstrKind::Binary {
+                op: op @ (BinOp::Div | BinOp::Rem),
+                lhs,
+                rhs,
+            } => self.div(*op, false, *lhs, *rhs),
+
+            InstrKind::Binary {
+                op: op @ (BinOp::IDiv(IntType::I64) | BinOp::IRem(IntType::I64)),
+                lhs,
+                rhs,
+            } => self.div(*op, true, *lhs, *rhs),
+
+            InstrKind::Binary {
+                op: op @ (BinOp::IDiv(ty) | BinOp::UDiv(ty) | BinOp::IRem(ty) | BinOp::URem(ty)),
+                lhs,
+                rhs,
+            } => self.int_div(*op, *ty, *lhs, *rhs),
+
+            InstrKind::Binary {
+                op:
+                    op @ (BinOp::Shl | BinOp::Shr | BinOp::IShl(..) | BinOp::IShr(..) | BinOp::UShr(..)),
+                lhs,
+                rhs,
+            } => self.shift(*op, *lhs, *rhs),
+
+            InstrKind::Binary { op, lhs, rhs } => self.binary(*op, *lhs, *rhs),
+
+            InstrKind::Call { name, args } => {
+                self.call(name, args);
+
+                if self.reg_tys.get(instr.dest) == Some(&MirType::Bool) {
+                    self.ins("movzbl %al, %eax");
+                }
+            }
+        }
+
+        self.mov_sized(wide, &dest, TEMP);
+    }
+
+    fn binary(&mut self, op: BinOp, lhs: Operand, rhs: Operand) {
+        if let BinOp::And | BinOp::Or = op {
+            let ins = if op == BinOp::And { "andb" } else { "orb" };
+            self.mov(TEMP, &self.operand(lhs));
+            self.ins(&format!("{ins} {}, %al", self.byte(rhs)));
+            return;
+        }
+
+        let (suffix, acc, rhs) = if self.wide(lhs) {
+            self.movq(TEMP, &self.operand(lhs));
+            ("q", "%rax", self.wide_operand(rhs))
+        } else {
+            self.mov(TEMP, &self.operand(lhs));
+            ("l", TEMP, self.operand(rhs))
+        };
+
+        match op {
+            BinOp::Add | BinOp::IAdd(..) => self.ins(&format!("add{suffix} {rhs}, {acc}")),
+            BinOp::Sub | BinOp::ISub(..) => self.ins(&format!("sub{suffix} {rhs}, {acc}")),
+            BinOp::Mul | BinOp::IMul(..) => self.ins(&format!("imul{suffix} {rhs}, {acc}")),
+            BinOp::BitAnd | BinOp::IAnd(..) => self.ins(&format!("and{suffix} {rhs}, {acc}")),
+            BinOp::BitOr | BinOp::IOr(..) => self.ins(&format!("or{suffix} {rhs}, {acc}")),
+            BinOp::BitXor | BinOp::IXor(..) => self.ins(&format!("xor{suffix} {rhs}, {acc}")),
+
+            BinOp::Eq
+            | BinOp::NotEq
+            | BinOp::Lesser
+            | BinOp::LesserEq
+            | BinOp::Greater
+            | BinOp::GreaterEq
+            | BinOp::ULesser
+            | BinOp::ULesserEq
+            | BinOp::UGreater
+            | BinOp::UGreaterEq => {
+                self.ins(&format!("cmp{suffix} {rhs}, {acc}"));
+                self.ins(&format!("{} %al", setcc(op)));
+                self.ins("movzbl %al, %eax");
+            }
+
+            BinOp::And
+            | BinOp::Or
+            | BinOp::Div
+            | BinOp::Rem
+            | BinOp::Shl
+            | BinOp::Shr
+            | BinOp::IDiv(..)
+            | BinOp::UDiv(..)
+            | BinOp::IRem(..)
+            | BinOp::URem(..)
+            | BinOp::IShl(..)
+            | BinOp::IShr(..)
+            | BinOp::UShr(..)
+            | BinOp::FAdd
+            | BinOp::FSub
+            | BinOp::FMul
+            | BinOp::FDiv
+            | BinOp::FEq
+            | BinOp::FNotEq
+            | BinOp::FLesser
+            | BinOp::FLesserEq
+            | BinOp::FGreater
+            | BinOp::FGreaterEq
+            | BinOp::Concat => unreachable!(),
+        }
+
+        if let BinOp::IAdd(ty) | BinOp::ISub(ty) | BinOp::IMul(ty) = op {
+            self.extend(ty);
+        }
+    }
+
+    /// Signed division of `num`s, or of `i64`s if `wide`, where dividing the minimum by -1
+    /// overflows. It wraps instead of trapping.
+    fn div(&mut self, op: BinOp, wide: bool, lhs: Operand, rhs: Operand) {
+        let rem = matches!(op, BinOp::Rem | BinOp::IRem(..));
+        let (suffix, scratch) = if wide {
+            ("q", WIDE_SCRATCH)
+        } else {
+            ("l", SCRATCH)
+        };
+
+        self.mov_sized(wide, TEMP, &self.operand(lhs));
+
+        let minus_one = if rem {
+            "xorl %eax, %eax".into()
+        } else {
+            format!("neg{suffix} {}", if wide { "%rax" } else { TEMP })
+        };
+
+        if rhs.is_const() && rhs.as_int() == -1 {
+            self.ins(&minus_one);
+            return;
+        }
+
+        self.mov_sized(wide, SCRATCH, &self.operand(rhs));
+        self.ins("movq %rdx, %r11");
+
+        if rhs.is_const() {
+            self.idiv(wide, rem);
+        } else {
+            let label = self.fresh_label();
+
+            self.ins(&format!("cmp{suffix} $-1, {scratch}"));
+            self.ins(&format!("jne {label}.div"));
+            self.ins(&minus_one);
+            self.ins(&format!("jmp {label}.done"));
+            self.raw(&format!("{label}.div:"));
+            self.idiv(wide, rem);
+            self.raw(&format!("{label}.done:"));
+        }
+
+        self.ins("movq %r11, %rdx");
+    }
+
+    fn idiv(&mut self, wide: bool, rem: bool) {
+        if wide {
+            self.ins("cqto");
+            self.ins(&format!("idivq {WIDE_SCRATCH}"));
+        } else {
+            self.ins("cltd");
+            self.ins(&format!("idivl {SCRATCH}"));
+        }
+
+        if rem {
+            self.mov_sized(wide, TEMP, "%edx");
+        }
+    }
+
+    /// Narrow sized integers live sign- or zero-extended in 32 bits, so their division can't
+    /// overflow. `i64` division goes through [`Self::div`].
+    fn int_div(&mut self, op: BinOp, ty: IntType, lhs: Operand, rhs: Operand) {
+        let wide = ty.bits() == 64;
+        let (suffix, scratch) = if wide {
+            ("q", WIDE_SCRATCH)
+        } else {
+            ("l", SCRATCH)
+        };
+
+        self.mov_sized(wide, TEMP, &self.operand(lhs));
+        self.mov_sized(wide, SCRATCH, &self.operand(rhs));
+        self.ins("movq %rdx, %r11");
+
+        if ty.is_signed() {
+            self.ins("cltd");
+            self.ins(&format!("idivl {SCRATCH}"));
+        } else {
+            self.ins("xorl %edx, %edx");
+            self.ins(&format!("div{suffix} {scratch}"));
+        }
+
+        if matches!(op, BinOp::IRem(..) | BinOp::URem(..)) {
+            self.mov_sized(wide, TEMP, "%edx");
+        }
+
+        self.ins("movq %r11, %rdx");
+        self.extend(ty);
+    }
+
+    fn shift(&mut self, op: BinOp, lhs: Operand, rhs: Operand) {
+        let (ins, bits) = match op {
+            BinOp::Shl => ("shl", 32),
+            BinOp::Shr => ("sar", 32),
+            BinOp::IShl(ty) => ("shl", ty.bits()),
+            BinOp::IShr(ty) => ("sar", ty.bits()),
+            BinOp::UShr(ty) => ("shr", ty.bits()),
+            _ => unreachable!(),
+        };
+
+        let (ins, acc) = if bits == 64 {
+            (format!("{ins}q"), "%rax")
+        } else {
+            (format!("{ins}l"), TEMP)
+        };
+
+        let mask = bits - 1;
+        self.mov_sized(bits == 64, TEMP, &self.operand(lhs));
+
+        if rhs.is_const() {
+            self.ins(&format!(
+                "{ins} ${}, {acc}",
+                rhs.as_int() & i128::from(mask)
+            ));
+        } else {
+            self.ins("movq %rcx, %r11");
+            self.mov("%ecx", &self.operand(rhs));
+
+            if bits < 32 {
+                self.ins(&format!("andl ${mask}, %ecx"));
+            }
+
+            self.ins(&format!("{ins} %cl, {acc}"));
+            self.ins("movq %r11, %rcx");
+        }
+
+        if let BinOp::IShl(ty) = op {
+            self.extend(ty);
+        }
+    }
+
+    /// Extends a `num`, bool or narrow sized integer to 64 bits. Signed values are kept
+    /// sign-extended in 32 bits and unsigned ones zero-extended, so this only has to carry that on.
+    fn widen(&mut self, arg: Operand) {
+        let src = self.operand(arg);
+
+        match self.reg_tys.operand(arg) {
+            Some(MirType::Int(ty)) if ty.bits() == 64 => self.movq(TEMP, &src),
+            Some(MirType::Num | MirType::Int(IntType::I8 | IntType::I16)) if !arg.is_const() => {
+                self.ins(&format!("movslq {src}, %rax"));
+            }
+            Some(MirType::Num | MirType::Int(IntType::I8 | IntType::I16)) => {
+                self.movq(TEMP, &src);
+            }
+            _ => self.mov(TEMP, &src),
+        }
+    }
+
+    fn extend(&mut self, ty: IntType) {
+        let ins = match (ty.bits(), ty.is_signed()) {
+            (8, true) => "movsbl %al, %eax",
+            (8, false) => "movzbl %al, %eax",
+            (16, true) => "movswl %ax, %eax",
+            (16, false) => "movzwl %ax, %eax",
+            _ => return,
+        };
+
+        self.ins(ins);
+    }
+
+    fn call(&mut self, name: &str, args: &[Operand]) {
+        let stack_args = args.get(ARGS.len()..).unwrap_or_default();
+        let padding = stack_args.len() % 2;
+
+        if padding != 0 {
+            self.ins("subq $8, %rsp");
+        }
+
+        for arg in stack_args.iter().rev() {
+            self.mov_sized(self.wide(*arg), TEMP, &self.operand(*arg));
+            self.ins("pushq %rax");
+        }
+
+        let moves = args
+            .iter()
+            .zip(ARGS)
+            .map(|(arg, reg)| {
+                (
+                    format!("%{}", reg32(reg)),
+                    self.operand(*arg),
+                    self.wide(*arg),
+                )
+            })
+            .collect();
+
+        self.parallel_move(moves);
+        self.ins(&format!("call {name}"));
+
+        if !stack_args.is_empty() {
+            self.ins(&format!("addq ${}, %rsp", 8 * (stack_args.len() + padding)));
+        }
+    }
+
+    /// Moves `(dest, src, wide)` triples as if all at once. Locations are named by their 32-bit
+    /// names, `wide` picks 64-bit moves.
+    fn parallel_move(&mut self, moves: Vec<(String, String, bool)>) {
+        let mut pending = moves
+            .into_iter()
+            .filter(|(dest, src, _)| dest != src)
+            .collect::<Vec<_>>();
+
+        while !pending.is_empty() {
+            let ready = pending
+                .iter()
+                .position(|(dest, ..)| pending.iter().all(|(_, src, _)| src != dest));
+
+            if let Some(idx) = ready {
+                let (dest, src, wide) = pending.remove(idx);
+                self.mov_sized(wide, &dest, &src);
+                continue;
+            }
+
+            let blocked = pending[0].0.clone();
+            let wide = pending
+                .iter()
+                .any(|(_, src, wide)| *src == blocked && *wide);
+            self.mov_sized(wide, TEMP, &blocked);
+
+            for (_, src, _) in &mut pending {
+                if *src == blocked {
+                    *src = TEMP.into();
+                }
+            }
+        }
+    }
+
+    fn jump(&mut self, target: BlockID, next: Option<BlockID>) {
+        if next != Some(target) {
+            self.ins(&format!("jmp {}", self.block_label(target)));
+        }
+    }
+
+    fn ret(&mut self, next: Option<BlockID>) {
+        if next.is_some() {
+            self.ins(&format!("jmp {}", self.ret_label()));
+        }
+    }
+
+    fn mov(&mut self, dest: &str, src: &str) {
+        if dest == src {
+            return;
+        }
+
+        if is_memory(dest) && is_memory(src) {
+            self.ins(&format!("movl {src}, {SCRATCH}"));
+            self.ins(&format!("movl {SCRATCH}, {dest}"));
+        } else {
+            self.ins(&format!("movl {src}, {dest}"));
+        }
+    }
+
+    fn mov_sized(&mut self, wide: bool, dest: &str, src: &str) {
+        if wide {
+            self.movq(dest, src);
+        } else {
+            self.mov(dest, src);
+        }
+    }
+
+    /// Moves 64 bits between locations given by their 32-bit names. Immediates that don't fit in
+    /// 32 bits can only be loaded into a register.
+    fn movq(&mut self, dest: &str, src: &str) {
+        if dest == src {
+            return;
+        }
+
+        let (dest, src) = (quad(dest), quad(src));
+
+        if is_wide_imm(&src) {
+            if is_memory(&dest) {
+                self.ins(&format!("movabsq {src}, {WIDE_SCRATCH}"));
+                self.ins(&format!("movq {WIDE_SCRATCH}, {dest}"));
+            } else {
+                self.ins(&format!("movabsq {src}, {dest}"));
+            }
+        } else if is_memory(&dest) && is_memory(&src) {
+            self.ins(&format!("movq {src}, {WIDE_SCRATCH}"));
+            self.ins(&format!("movq {WIDE_SCRATCH}, {dest}"));
+        } else {
+            self.ins(&format!("movq {src}, {dest}"));
+        }
+    }
+
+    /// The 64-bit source operand of an ALU op, loading immediates that don't fit in 32 bits.
+    fn wide_operand(&mut self, operand: Operand) -> String {
+        let src = self.operand(operand);
+
+        if is_wide_imm(&src) {
+            self.ins(&format!("movabsq {src}, {WIDE_SCRATCH}"));
+            return WIDE_SCRATCH.into();
+        }
+
+        quad(&src)
+    }
+
+    fn wide(&self, operand: Operand) -> bool {
+        self.reg_tys.operand(operand).is_some_and(|ty| is_wide(&ty))
+    }
+
+    fn operand(&self, operand: Operand) -> String {
+        match operand {
+            Operand::Num(value) => format!("${value}"),
+            Operand::Bool(value) => format!("${}", i32::from(value)),
+            Operand::Int(value) if value.ty().bits() == 64 => {
+                format!("${}", codegen::wide_int_bits(value))
+            }
+            Operand::Int(value) => format!("${}", codegen::int_bits(value)),
+            Operand::Str(..) | Operand::Float(..) => unreachable!(),
+            Operand::Reg(reg) => self.reg(reg),
+        }
+    }
+
+    /// The low byte of a bool operand. Bools are kept zero-extended, so byte ops leave the upper
+    /// bits clear.
+    fn byte(&self, operand: Operand) -> String {
+        match operand {
+            Operand::Reg(reg) => match self.allocation.location(reg) {
+                Location::Reg(phys) => format!("%{}", reg8(self.machine.name(phys))),
+                Location::Stack(..) => self.reg(reg),
+            },
+            operand => self.operand(operand),
+        }
+    }
+
+    fn reg(&self, reg: Reg) -> String {
+        match self.allocation.location(reg) {
+            Location::Reg(phys) => format!("%{}", reg32(self.machine.name(phys))),
+            Location::Stack(slot) => {
+                let offset = 8 * (self.allocation.used_callee_saved.len() + slot + 1);
+                format!("-{offset}(%rbp)")
+            }
+        }
+    }
+
+    fn block_label(&self, id: BlockID) -> String {
+        format!(".L{}.{id}", self.fun.name)
+    }
+
+    fn ret_label(&self) -> String {
+        format!(".L{}.ret", self.fun.name)
+    }
+
+    fn fresh_label(&mut self) -> String {
+        self.labels += 1;
+        format!(".L{}.{}", self.fun.name, self.labels)
+    }
+
+    fn ins(&mut self, line: &str) {
+        self.out.push('\t');
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+
+    fn raw(&mut self, line: &str) {
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+}
+
+fn reg32(name: &str) -> String {
+    match name {
+        "rax" | "rbx" | "rcx" | "rdx" => format!("e{}x", &name[1..2]),
+        "rsi" | "rdi" => format!("e{}", &name[1..]),
+        _ => format!("{name}d"),
+    }
+}
+
+fn reg8(name: &str) -> String {
+    match name {
+        "rax" | "rbx" | "rcx" | "rdx" => format!("{}l", &name[1..2]),
+        "rsi" | "rdi" => format!("{}l", &name[1..]),
+        _ => format!("{name}b"),
+    }
+}
+
+/// The 64-bit name of a register given by its 32-bit name. Memory and immediates are unchanged.
+fn quad(operand: &str) -> String {
+    match operand.strip_prefix("%e") {
+        Some(name) => format!("%r{name}"),
+        None if operand.starts_with("%r") => operand.trim_end_matches('d').into(),
+        None => operand.into(),
+    }
+}
+
+fn is_memory(operand: &str) -> bool {
+    operand.ends_with("(%rbp)")
+}
+
+fn is_wide_imm(operand: &str) -> bool {
+    operand
+        .strip_prefix('$')
+        .and_then(|value| value.parse::<i64>().ok())
+        .is_some_and(|value| i32::try_from(value).is_err())
+}
+
+fn is_wide(ty: &MirType) -> bool {
+    matches!(ty, MirType::Int(ty) if ty.bits() == 64)
+}
+
+fn setcc(op: BinOp) -> &'static str {
+    match op {
+        BinOp::Eq => "sete",
+        BinOp::NotEq => "setne",
+        BinOp::Lesser => "setl",
+        BinOp::LesserEq => "setle",
+        BinOp::Greater => "setg",
+        BinOp::GreaterEq => "setge",
+        BinOp::ULesser => "setb",
+        BinOp::ULesserEq => "setbe",
+        BinOp::UGreater => "seta",
+        BinOp::UGreaterEq => "setae",
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,245 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gpr {
+    Eax = 0,
+    Ecx = 1,
+    Edx = 2,
+    Esi = 6,
+    Edi = 7,
+    R8d = 8,
+    R9d = 9,
+}
+
+pub const ARGS: [Gpr; 6] = [Gpr::Edi, Gpr::Esi, Gpr::Edx, Gpr::Ecx, Gpr::R8d, Gpr::R9d];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alu {
+    Add = 0x01,
+    Or = 0x09,
+    And = 0x21,
+    Sub = 0x29,
+    Xor = 0x31,
+    Cmp = 0x39,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cond {
+    Below = 0x2,
+    AboveEq = 0x3,
+    Equal = 0x4,
+    NotEqual = 0x5,
+    BelowEq = 0x6,
+    Above = 0x7,
+    Less = 0xc,
+    GreaterEq = 0xd,
+    LessEq = 0xe,
+    Greater = 0xf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Label(usize);
+
+#[derive(Debug, Default)]
+pub struct Assembler {
+    code: Vec<u8>,
+    labels: Vec<Option<usize>>,
+    fixups: Vec<(usize, Label)>,
+}
+
+impl Assembler {
+    pub fn new_label(&mut self) -> Label {
+        self.labels.push(None);
+        Label(self.labels.len() - 1)
+    }
+
+    pub fn bind(&mut self, label: Label) {
+        self.labels[label.0] = Some(self.code.len());
+    }
+
+    pub fn offset(&self, label: Label) -> usize {
+        self.labels[label.0].unwrap()
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        for (pos, label) in std::mem::take(&mut self.fixups) {
+            let target = i64::try_from(self.offset(label)).unwrap();
+            let next = i64::try_from(pos + 4).unwrap();
+            let rel = i32::try_from(target - next).unwrap();
+
+            self.code[pos..pos + 4].copy_from_slice(&rel.to_le_bytes());
+        }
+
+        self.code
+    }
+
+    pub fn position(&self) -> usize {
+        self.code.len()
+    }
+
+    pub fn prologue(&mut self, frame: u32) {
+        self.emit(&[0x55, 0x48, 0x89, 0xe5]);
+        self.sub_rsp(frame);
+    }
+
+    pub fn sub_rsp(&mut self, value: u32) {
+        self.emit(&[0x48, 0x81, 0xec]);
+        self.emit(&value.to_le_bytes());
+    }
+
+    pub fn add_rsp(&mut self, value: u32) {
+        self.emit(&[0x48, 0x81, 0xc4]);
+        self.emit(&value.to_le_bytes());
+    }
+
+    pub fn push_rax(&mut self) {
+        self.emit(&[0x50]);
+    }
+
+    pub fn leave_ret(&mut self) {
+        self.emit(&[0xc9, 0xc3]);
+    }
+
+    pub fn load(&mut self, reg: Gpr, disp: i32) {
+        self.rex_r(reg);
+        self.emit(&[0x8b, 0x85 | ((reg as u8 & 7) << 3)]);
+        self.emit(&disp.to_le_bytes());
+    }
+
+    pub fn store(&mut self, disp: i32, reg: Gpr) {
+        self.rex_r(reg);
+        self.emit(&[0x89, 0x85 | ((reg as u8 & 7) << 3)]);
+        self.emit(&disp.to_le_bytes());
+    }
+
+    pub fn mov_imm(&mut self, reg: Gpr, value: i32) {
+        if reg as u8 >= 8 {
+            self.emit(&[0x41]);
+        }
+
+        self.emit(&[0xb8 + (reg as u8 & 7)]);
+        self.emit(&value.to_le_bytes());
+    }
+
+    pub fn alu_eax_ecx(&mut self, op: Alu) {
+        self.emit(&[op as u8, 0xc8]);
+    }
+
+    pub fn imul_eax_ecx(&mut self) {
+        self.emit(&[0x0f, 0xaf, 0xc1]);
+    }
+
+    pub fn setcc_eax(&mut self, cond: Cond) {
+        self.emit(&[0x0f, 0x90 + cond as u8, 0xc0, 0x0f, 0xb6, 0xc0]);
+    }
+
+    pub fn neg_eax(&mut self) {
+        self.emit(&[0xf7, 0xd8]);
+    }
+
+    pub fn not_eax(&mut self) {
+        self.emit(&[0xf7, 0xd0]);
+    }
+
+    pub fn xor_eax_one(&mut self) {
+        self.emit(&[0x83, 0xf0, 0x01]);
+    }
+
+    pub fn shl_eax_cl(&mut self) {
+        self.emit(&[0xd3, 0xe0]);
+    }
+
+    pub fn sar_eax_cl(&mut self) {
+        self.emit(&[0xd3, 0xf8]);
+    }
+
+    pub fn shr_eax_cl(&mut self) {
+        self.emit(&[0xd3, 0xe8]);
+    }
+
+    pub fn and_ecx_imm8(&mut self, value: i8) {
+        self.emit(&[0x83, 0xe1, value.to_le_bytes()[0]]);
+    }
+
+    /// Sign- or zero-extends the low `bits` of eax, for integers narrower than 32 bits.
+    pub fn extend_eax(&mut self, bits: u32, signed: bool) {
+        let opcode = match (bits, signed) {
+            (8, true) => 0xbe,
+            (8, false) => 0xb6,
+            (16, true) => 0xbf,
+            (16, false) => 0xb7,
+            _ => return,
+        };
+
+        self.emit(&[0x0f, opcode, 0xc0]);
+    }
+
+    pub fn cdq_idiv_ecx(&mut self) {
+        self.emit(&[0x99, 0xf7, 0xf9]);
+    }
+
+    pub fn xor_edx_div_ecx(&mut self) {
+        self.emit(&[0x31, 0xd2, 0xf7, 0xf1]);
+    }
+
+    pub fn mov_eax_edx(&mut self) {
+        self.emit(&[0x89, 0xd0]);
+    }
+
+    pub fn test(&mut self, reg: Gpr) {
+        let reg = reg as u8 & 7;
+        self.emit(&[0x85, 0xc0 | (reg << 3) | reg]);
+    }
+
+    pub fn cmp_ecx_imm8(&mut self, value: i8) {
+        self.emit(&[0x83, 0xf9, value.to_le_bytes()[0]]);
+    }
+
+    pub fn jmp(&mut self, label: Label) {
+        self.emit(&[0xe9]);
+        self.fixup(label);
+    }
+
+    pub fn jcc(&mut self, cond: Cond, label: Label) {
+        self.emit(&[0x0f, 0x80 + cond as u8]);
+        self.fixup(label);
+    }
+
+    pub fn call(&mut self) -> usize {
+        self.emit(&[0xe8, 0, 0, 0, 0]);
+        self.code.len() - 4
+    }
+
+    pub fn set_flag(&mut self, addr: u64) {
+        self.emit(&[0x48, 0xb8]);
+        self.emit(&addr.to_le_bytes());
+        self.emit(&[0xc6, 0x00, 0x01]);
+    }
+
+    pub fn check_flag(&mut self, addr: u64, label: Label) {
+        self.emit(&[0x48, 0xb9]);
+        self.emit(&addr.to_le_bytes());
+        self.emit(&[0x80, 0x39, 0x00]);
+        self.jcc(Cond::NotEqual, label);
+    }
+
+    pub fn check_stack(&mut self, addr: u64, label: Label) {
+        self.emit(&[0x48, 0xb8]);
+        self.emit(&addr.to_le_bytes());
+        self.emit(&[0x48, 0x3b, 0x20]);
+        self.jcc(Cond::Below, label);
+    }
+
+    fn rex_r(&mut self, reg: Gpr) {
+        if reg as u8 >= 8 {
+            self.emit(&[0x44]);
+        }
+    }
+
+    fn fixup(&mut self, label: Label) {
+        self.fixups.push((self.code.len(), label));
+        self.emit(&[0; 4]);
+    }
+
+    fn emit(&mut self, bytes: &[u8]) {
+        self.code.extend_from_slice(bytes);
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    codegen::{
+        self,
+        x86_64::assembler::{ARGS, Alu, Assembler, Cond, Gpr, Label},
+    },
+    int::IntType,
+    mir::{BasicBlock, BlockID, InstrKind, MirFun, MirModule, MirType, Operand, Reg, Term},
+    mir_analysis::reg_types::RegTypes,
+    ops::{BinOp, UnOp},
+};
+
+#[derive(Debug, Clone)]
+pub struct MachineCode {
+    pub code: Vec<u8>,
+    pub symbols: Vec<Symbol>,
+    pub relocs: Vec<Reloc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// A call whose rel32 operand at `offset` must be patched to point at `symbol`.
+#[derive(Debug, Clone)]
+pub struct Reloc {
+    pub offset: usize,
+    pub symbol: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Checks {
+    Native,
+    Bail { flag: u64, limit: u64 },
+}
+
+/// Encodes `funs`, which must belong to `module` and only hold values that fit in 32 bits.
+pub fn encode(module: &MirModule, funs: &[&MirFun], checks: Checks) -> MachineCode {
+    let mut asm = Assembler::default();
+    let mut symbols = Vec::new();
+    let mut relocs = Vec::new();
+
+    for fun in funs {
+        let offset = asm.position();
+
+        let reg_tys = RegTypes::new(module, fun);
+        FunEncoder::new(fun, reg_tys, checks, &mut asm, &mut relocs).encode();
+
+        symbols.push(Symbol {
+            name: fun.name.clone(),
+            offset,
+            size: asm.position() - offset,
+        });
+    }
+
+    MachineCode {
+        code: asm.finish(),
+        symbols,
+        relocs,
+    }
+}
+
+struct FunEncoder<'a> {
+    fun: &'a MirFun,
+    reg_tys: RegTypes,
+    checks: Checks,
+    asm: &'a mut Assembler,
+    relocs: &'a mut Vec<Reloc>,
+    slots: BTreeMap<Reg, i32>,
+    blocks: Vec<Label>,
+    bail: Label,
+}
+
+impl<'a> FunEncoder<'a> {
+    fn new(
+        fun: &'a MirFun,
+        reg_tys: RegTypes,
+        checks: Checks,
+        asm: &'a mut Assembler,
+        relocs: &'a mut Vec<Reloc>,
+    ) -> Self {
+        let mut slots = BTreeMap::new();
+
+        let regs = fun.params.iter().map(|(reg, _)| *reg).chain(
+            fun.blocks
+                .iter()
+                .flat_map(|block| &block.instrs)
+                .map(|instr| instr.dest),
+        );
+
+        for reg in regs {
+            let disp = -8 * (i32::try_from(slots.len()).unwrap() + 1);
+            slots.entry(reg).or_insert(disp);
+        }
+
+        let blocks = fun.blocks.iter().map(|_| asm.new_label()).collect();
+        let bail = asm.new_label();
+
+        Self {
+            fun,
+            reg_tys,
+            checks,
+            asm,
+            relocs,
+            slots,
+            blocks,
+            bail,
+        }
+    }
+
+    fn encode(mut self) {
+        let frame = u32::try_from(self.slots.len().next_multiple_of(2) * 8).unwrap();
+
+        self.asm.prologue(frame);
+
+        if let Checks::Bail { limit, .. } = self.checks {
+            self.asm.check_stack(limit, self.bail);
+        }
+
+        for (idx, (reg, _)) in self.fun.params.iter().enumerate() {
+            if let Some(arg) = ARGS.get(idx) {
+                self.asm.store(self.slots[reg], *arg);
+            } else {
+                let disp = 16 + 8 * i32::try_from(idx - ARGS.len()).unwrap();
+                self.asm.load(Gpr::Eax, disp);
+                self.asm.store(self.slots[reg], Gpr::Eax);
+            }
+        }
+
+        for (idx, block) in self.fun.blocks.iter().enumerate() {
+            let next = self.fun.blocks.get(idx + 1).map(|block| block.id);
+            self.block(block, next);
+        }
+
+        if let Checks::Bail { flag, .. } = self.checks {
+            self.asm.bind(self.bail);
+            self.asm.set_flag(flag);
+            self.asm.leave_ret();
+        }
+    }
+
+    fn block(&mut self, block: &BasicBlock, next: Option<BlockID>) {
+        self.asm.bind(self.blocks[block.id.0]);
+
+        for instr in &block.instrs {
+            match &instr.kind {
+                InstrKind::Copy { src }
+                | InstrKind::Cast {
+                    arg: src,
+                    ty: MirType::Num | MirType::Bool,
+                } => self.load(Gpr::Eax, *src),
+
+                InstrKind::Cast {
+                    arg,
+                    ty: MirType::Int(ty),
+                } => {
+                    self.load(Gpr::Eax, *arg);
+                    self.extend(*ty);
+                }
+
+                InstrKind::Cast {
+                    ty: MirType::Float | MirType::Str,
+                    ..
+                } => unreachable!(),
+
+                InstrKind::Unary { op, arg } => {
+                    self.load(Gpr::Eax, *arg);
+
+                    match op {
+                        UnOp::Negate => self.asm.neg_eax(),
+                        UnOp::Not => self.asm.xor_eax_one(),
+                        UnOp::BitNot => self.asm.not_eax(),
+                        UnOp::FNegate => unreachable!(),
+                    }
+
+                    if let Some(MirType::Int(ty)) = self.reg_tys.operand(*arg) {
+                        self.extend(ty);
+                    }
+                }
+
+                InstrKind::Binary { op, lhs, rhs } => {
+                    self.load(Gpr::Eax, *lhs);
+                    self.load(Gpr::Ecx, *rhs);
+                    self.binary(*op);
+                }
+
+                InstrKind::Call { name, args } => self.call(name, args),
+            }
+
+            self.asm.store(self.slots[&instr.dest], Gpr::Eax);
+        }
+
+        match &block.term {
+            Some(Term::Jump { target }) => self.jump(*target, next),
+
+            Some(Term::Branch {
+                cond: Operand::Bool(cond),
+                then_block,
+                else_block,
+            }) => self.jump(if *cond { *then_block } else { *else_block }, next),
+
+            Some(Term::Branch {
+                cond,
+                then_block,
+                else_block,
+            }) => {
+                self.load(Gpr::Eax, *cond);
+                self.asm.test(Gpr::Eax);
+                self.asm.jcc(Cond::NotEqual, self.blocks[then_block.0]);
+                self.jump(*else_block, next);
+            }
+
+            Some(Term::Return { value: Some(value) }) => {
+                self.load(Gpr::Eax, *value);
+                self.asm.leave_ret();
+            }
+
+            Some(Term::Return { value: None }) | None => {
+                if self.fun.name == "main" {
+                    self.asm.mov_imm(Gpr::Eax, 0);
+                }
+
+                self.asm.leave_ret();
+            }
+        }
+    }
+
+    fn call(&mut self, name: &str, args: &[Operand]) {
+        let stack_args = args.get(ARGS.len()..).unwrap_or_default();
+        let padding = stack_args.len() % 2;
+
+        if padding != 0 {
+            self.asm.sub_rsp(8);
+        }
+
+        for arg in stack_args.iter().rev() {
+            self.load(Gpr::Eax, *arg);
+            self.asm.push_rax();
+        }
+
+        for (arg, reg) in args.iter().zip(ARGS) {
+            self.load(reg, *arg);
+        }
+
+        let offset = self.asm.call();
+
+        self.relocs.push(Reloc {
+            offset,
+            symbol: name.into(),
+        });
+
+        if !stack_args.is_empty() {
+            let size = 8 * (stack_args.len() + padding);
+            self.asm.add_rsp(u32::try_from(size).unwrap());
+        }
+
+        if let Checks::Bail { flag, .. } = self.checks {
+            self.asm.check_flag(flag, self.bail);
+        }
+    }
+
+    fn binary(&mut self, op: BinOp) {
+        let cond = match op {
+            BinOp::Add => return self.asm.alu_eax_ecx(Alu::Add),
+            BinOp::Sub => return self.asm.alu_eax_ecx(Alu::Sub),
+            BinOp::And | BinOp::BitAnd | BinOp::IAnd(..) => return self.asm.alu_eax_ecx(Alu::And),
+            BinOp::Or | BinOp::BitOr | BinOp::IOr(..) => return self.asm.alu_eax_ecx(Alu::Or),
+            BinOp::BitXor | BinOp::IXor(..) => return self.asm.alu_eax_ecx(Alu::Xor),
+            BinOp::Shl => return self.asm.shl_eax_cl(),
+            BinOp::Shr => return self.asm.sar_eax_cl(),
+            BinOp::Mul => return self.asm.imul_eax_ecx(),
+            BinOp::Div | BinOp::Rem => return self.div(op),
+            BinOp::IAdd(ty)
+            | BinOp::ISub(ty)
+            | BinOp::IMul(ty)
+            | BinOp::IDiv(ty)
+            | BinOp::UDiv(ty)
+            | BinOp::IRem(ty)
+            | BinOp::URem(ty)
+            | BinOp::IShl(ty)
+            | BinOp::IShr(ty)
+            | BinOp::UShr(ty) => return self.int_binary(op, ty),
+            BinOp::Eq => Cond::Equal,
+            BinOp::NotEq => Cond::NotEqual,
+            BinOp::Lesser => Cond::Less,
+            BinOp::LesserEq => Cond::LessEq,
+            BinOp::Greater => Cond::Greater,
+            BinOp::GreaterEq => Cond::GreaterEq,
+            BinOp::ULesser => Cond::Below,
+            BinOp::ULesserEq => Cond::BelowEq,
+            BinOp::UGreater => Cond::Above,
+            BinOp::UGreaterEq => Cond::AboveEq,
+            BinOp::FAdd
+            | BinOp::FSub
+            | BinOp::FMul
+            | BinOp::FDiv
+            | BinOp::FEq
+            | BinOp::FNotEq
+            | BinOp::FLesser
+            | BinOp::FLesserEq
+            | BinOp::FGreater
+            | BinOp::FGreaterEq
+            | BinOp::Concat => unreachable!(),
+        };
+
+        self.asm.alu_eax_ecx(Alu::Cmp);
+        self.asm.setcc_eax(cond);
+    }
+
+    /// Sized integers live sign- or zero-extended in 32 bits. Narrow division can't overflow, so
+    /// only a zero divisor needs checking.
+    fn int_binary(&mut self, op: BinOp, ty: IntType) {
+        if matches!(op, BinOp::IShl(..) | BinOp::IShr(..) | BinOp::UShr(..)) && ty.bits() < 32 {
+            self.asm.and_ecx_imm8(i8::try_from(ty.bits() - 1).unwrap());
+        }
+
+        if matches!(
+            op,
+            BinOp::IDiv(..) | BinOp::UDiv(..) | BinOp::IRem(..) | BinOp::URem(..)
+        ) && let Checks::Bail { .. } = self.checks
+        {
+            self.asm.test(Gpr::Ecx);
+            self.asm.jcc(Cond::Equal, self.bail);
+        }
+
+        match op {
+            BinOp::IAdd(..) => self.asm.alu_eax_ecx(Alu::Add),
+            BinOp::ISub(..) => self.asm.alu_eax_ecx(Alu::Sub),
+            BinOp::IMul(..) => self.asm.imul_eax_ecx(),
+            BinOp::IDiv(..) => self.asm.cdq_idiv_ecx(),
+            BinOp::UDiv(..) => self.asm.xor_edx_div_ecx(),
+            BinOp::IShl(..) => self.asm.shl_eax_cl(),
+            BinOp::IShr(..) => self.asm.sar_eax_cl(),
+            BinOp::UShr(..) => self.asm.shr_eax_cl(),
+
+            BinOp::IRem(..) => {
+                self.asm.cdq_idiv_ecx();
+                self.asm.mov_eax_edx();
+            }
+
+            BinOp::URem(..) => {
+                self.asm.xor_edx_div_ecx();
+                self.asm.mov_eax_edx();
+            }
+
+            _ => unreachable!(),
+        }
+
+        self.extend(ty);
+    }
+
+    fn extend(&mut self, ty: IntType) {
+        self.asm.extend_eax(ty.bits(), ty.is_signed());
+    }
+
+    fn div(&mut self, op: BinOp) {
+        let (div, done) = (self.asm.new_label(), self.asm.new_label());
+
+        if let Checks::Bail { .. } = self.checks {
+            self.asm.test(Gpr::Ecx);
+            self.asm.jcc(Cond::Equal, self.bail);
+        }
+
+        self.asm.cmp_ecx_imm8(-1);
+        self.asm.jcc(Cond::NotEqual, div);
+
+        if op == BinOp::Rem {
+            self.asm.mov_imm(Gpr::Eax, 0);
+        } else {
+            self.asm.neg_eax();
+        }
+
+        self.asm.jmp(done);
+        self.asm.bind(div);
+        self.asm.cdq_idiv_ecx();
+
+        if op == BinOp::Rem {
+            self.asm.mov_eax_edx();
+        }
+
+        self.asm.bind(done);
+    }
+
+    fn jump(&mut self, target: BlockID, next: Option<BlockID>) {
+        if next != Some(target) {
+            self.asm.jmp(self.blocks[target.0]);
+        }
+    }
+
+    fn load(&mut self, reg: Gpr, operand: Operand) {
+        match operand {
+            Operand::Reg(src) => self.asm.load(reg, self.slots[&src]),
+            Operand::Num(value) => self.asm.mov_imm(reg, value),
+            Operand::Bool(value) => self.asm.mov_imm(reg, i32::from(value)),
+            Operand::Int(value) => self.asm.mov_imm(reg, codegen::int_bits(value)),
+            Operand::Str(..) | Operand::Float(..) => unreachable!(),
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use serde::Serialize;
 use std::{collections::HashMap, fmt::Display};
 
-use crate::ops::{BinOp, UnOp};
+use crate::{
+    float::Float,
+    int::IntType,
+    ops::{BinOp, UnOp},
+};
 
 mod expr;
 mod stmt;
@@ -15,6 +19,13 @@ pub struct HirModule {
 pub struct HirFun {
     pub ty: HirFunType,
     pub body: Vec<Stmt>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inline: Option<InlineHint>,
+
+    /// The `///` comments preceding the function, one line each without the markers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Clone)]
@@ -23,13 +34,27 @@ pub struct HirFunType {
     pub returns: HirType,
 }
 
+#[derive(Debug, PartialEq, Eq, Serialize, Clone, Copy)]
+pub enum InlineHint {
+    Always,
+    Never,
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize)]
 #[serde(tag = "kind")]
 pub enum Stmt {
-    Break,
+    Break {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
+
+    Continue {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
 
     Block {
-        body: Vec<Stmt>,
+        body: Vec<Self>,
     },
 
     Return {
@@ -37,15 +62,28 @@ pub enum Stmt {
     },
 
     Loop {
-        body: Vec<Stmt>,
+        body: Vec<Self>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
+
+    For {
+        var: String,
+        ty: Option<HirType>,
+        range: Range,
+        body: Vec<Self>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
     },
 
     If {
         cond: Expr,
-        body: Vec<Stmt>,
+        body: Vec<Self>,
 
         #[serde(rename = "else")]
-        else_: Option<Vec<Stmt>>,
+        else_: Option<Vec<Self>>,
     },
 
     Let {
@@ -54,8 +92,13 @@ pub enum Stmt {
         expr: Option<Expr>,
     },
 
+    /// A plain `=` if `op` is none, otherwise a compound assignment such as `+=`.
     Assign {
         name: String,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        op: Option<BinOp>,
+
         expr: Expr,
     },
 
@@ -65,6 +108,15 @@ pub enum Stmt {
     },
 }
 
+/// Counts up from `start` by `step`, or by 1 if there is none, until passing `end`.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Range {
+    pub start: Expr,
+    pub end: Expr,
+    pub inclusive: bool,
+    pub step: Option<Expr>,
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize)]
 #[serde(tag = "kind")]
 pub enum Expr {
@@ -76,24 +128,42 @@ pub enum Expr {
         value: i32,
     },
 
+    Int {
+        value: u64,
+        ty: Option<IntType>,
+    },
+
+    Float {
+        value: Float,
+    },
+
+    Str {
+        value: String,
+    },
+
     Var {
         name: String,
     },
 
     Call {
         name: String,
-        args: Vec<Expr>,
+        args: Vec<Self>,
     },
 
     Unary {
         op: UnOp,
-        expr: Box<Expr>,
+        expr: Box<Self>,
     },
 
     Binary {
         op: BinOp,
-        lhs: Box<Expr>,
-        rhs: Box<Expr>,
+        lhs: Box<Self>,
+        rhs: Box<Self>,
+    },
+
+    Cast {
+        expr: Box<Self>,
+        ty: HirType,
     },
 }
 
@@ -103,6 +173,9 @@ pub enum HirType {
     Void,
     Bool,
     Num,
+    Int { ty: IntType },
+    Float,
+    Str,
 }
 
 impl Display for HirType {
@@ -111,6 +184,9 @@ impl Display for HirType {
             Self::Void => write!(f, "void"),
             Self::Bool => write!(f, "bool"),
             Self::Num => write!(f, "num"),
+            Self::Int { ty } => write!(f, "{ty}"),
+            Self::Float => write!(f, "float"),
+            Self::Str => write!(f, "str"),
         }
     }
 }
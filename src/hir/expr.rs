@@ -1,5 +1,6 @@
 use crate::{
-    hir::Expr,
+    hir::{Expr, HirType},
+    int::IntType,
     ops::{BinOp, UnOp},
 };
 
@@ -12,6 +13,20 @@ impl Expr {
         Self::Num { value }
     }
 
+    pub fn int(value: u64, ty: Option<IntType>) -> Self {
+        Self::Int { value, ty }
+    }
+
+    pub fn float(value: f64) -> Self {
+        Self::Float {
+            value: value.into(),
+        }
+    }
+
+    pub fn str(value: String) -> Self {
+        Self::Str { value }
+    }
+
     pub fn var(name: String) -> Self {
         Self::Var { name }
     }
@@ -34,4 +49,11 @@ impl Expr {
             rhs: Box::new(rhs),
         }
     }
+
+    pub fn cast(expr: Self, ty: HirType) -> Self {
+        Self::Cast {
+            expr: Box::new(expr),
+            ty,
+        }
+    }
 }
@@ -1,12 +1,22 @@
-use crate::hir::{Expr, Stmt};
+use crate::{
+    hir::{Expr, Stmt},
+    ops::BinOp,
+};
 
 impl Stmt {
     pub fn if_(cond: Expr, body: Vec<Self>, else_: Option<Vec<Self>>) -> Self {
         Self::If { cond, body, else_ }
     }
 
-    pub fn assign(name: String, expr: Expr) -> Self {
-        Self::Assign { name, expr }
+    pub fn loop_(body: Vec<Self>, label: Option<&str>) -> Self {
+        Self::Loop {
+            body,
+            label: label.map(str::to_owned),
+        }
+    }
+
+    pub fn assign(name: String, op: Option<BinOp>, expr: Expr) -> Self {
+        Self::Assign { name, op, expr }
     }
 
     pub fn call(name: String, args: Vec<Expr>) -> Self {
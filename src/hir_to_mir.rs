@@ -1,14 +1,26 @@
+use itertools::Itertools as _;
+
 use crate::{
-    hir::{Expr, HirFun, HirModule, HirType, Stmt},
-    mir::{BlockID, MirFun, MirModule, MirType},
+    hir::{Expr, HirFun, HirModule, HirType, Range, Stmt},
+    int::Int,
+    mir::{BlockID, MirFun, MirModule, MirType, StrID},
     mir_builder::{MirBuilder, VarID, value::Value},
+    ops::BinOp,
     scope::Scope,
 };
 
+/// The blocks `continue` and `break` jump to for an enclosing loop.
+struct LoopTargets {
+    label: Option<String>,
+    next: BlockID,
+    exit: BlockID,
+}
+
 #[derive(Default)]
 pub struct HirToMir {
-    loop_stack: Vec<BlockID>,
+    loop_stack: Vec<LoopTargets>,
     scope: Scope<VarID>,
+    strings: Vec<String>,
 }
 
 impl HirToMir {
@@ -19,11 +31,18 @@ impl HirToMir {
     pub fn lower_module(&mut self, module: HirModule) -> MirModule {
         let mut funs = Vec::new();
 
-        for (name, fun) in module.funs {
+        for (name, fun) in module
+            .funs
+            .into_iter()
+            .sorted_unstable_by(|(a, _), (b, _)| a.cmp(b))
+        {
             funs.push(self.lower_fun(name, fun));
         }
 
-        MirModule { funs }
+        MirModule {
+            funs,
+            strings: std::mem::take(&mut self.strings),
+        }
     }
 
     fn lower_fun(&mut self, name: String, fun: HirFun) -> MirFun {
@@ -31,9 +50,23 @@ impl HirToMir {
 
         let mut builder = MirBuilder::new(name);
 
+        self.scope.create();
+
+        for (name, ty) in &fun.ty.params {
+            let reg = builder.add_param(self.lower_type(ty).unwrap());
+            let var_id = builder.declare_var();
+
+            self.scope.set(name, &var_id);
+            builder.assign_var(var_id, Value::reg(reg));
+        }
+
         self.lower_block(&mut builder, fun.body);
+        self.scope.pop();
+
+        let mut mir = builder.finish(self.lower_type(&fun.ty.returns));
+        mir.inline = fun.inline;
 
-        builder.finish(self.lower_type(&fun.ty.returns))
+        mir
     }
 
     fn lower_block(&mut self, builder: &mut MirBuilder, stmts: Vec<Stmt>) {
@@ -50,13 +83,14 @@ impl HirToMir {
         match stmt {
             Stmt::Block { body } => self.lower_block(builder, body),
 
-            Stmt::Break => {
-                let target = self.loop_stack.last().unwrap();
-                builder.build_jump(*target);
+            Stmt::Break { label } => {
+                let target = self.loop_targets(label.as_deref()).exit;
+                Self::lower_jump(builder, target);
+            }
 
-                let unreachable = builder.create_block();
-                builder.set_active_block(unreachable);
-                builder.seal_block(unreachable);
+            Stmt::Continue { label } => {
+                let target = self.loop_targets(label.as_deref()).next;
+                Self::lower_jump(builder, target);
             }
 
             Stmt::Return { expr } => {
@@ -68,14 +102,19 @@ impl HirToMir {
                 builder.seal_block(unreachable);
             }
 
-            Stmt::Loop { body } => {
+            Stmt::Loop { body, label } => {
                 let body_block = builder.create_block();
                 let exit_block = builder.create_block();
 
                 builder.build_jump(body_block);
                 builder.set_active_block(body_block);
 
-                self.loop_stack.push(exit_block);
+                self.loop_stack.push(LoopTargets {
+                    label,
+                    next: body_block,
+                    exit: exit_block,
+                });
+
                 self.lower_block(builder, body);
                 self.loop_stack.pop();
 
@@ -88,6 +127,14 @@ impl HirToMir {
                 builder.set_active_block(exit_block);
             }
 
+            Stmt::For {
+                var,
+                ty,
+                range,
+                body,
+                label,
+            } => self.lower_stmt_for(builder, &var, &ty.unwrap(), range, body, label),
+
             Stmt::If { cond, body, else_ } => {
                 let then_block = builder.create_block();
                 let else_block = builder.create_block();
@@ -123,13 +170,10 @@ impl HirToMir {
             }
 
             Stmt::Let { name, ty, expr } => {
-                let value = if let Some(expr) = expr {
-                    self.lower_expr(builder, expr)
-                } else {
-                    self.lower_type(&ty.unwrap())
-                        .unwrap()
-                        .default_value()
-                        .into()
+                let value = match (expr, self.lower_type(&ty.unwrap()).unwrap()) {
+                    (Some(expr), _) => self.lower_expr(builder, expr),
+                    (None, MirType::Str) => Value::str(self.intern(String::new())),
+                    (None, ty) => ty.default_value().into(),
                 };
 
                 let var_id = builder.declare_var();
@@ -138,11 +182,15 @@ impl HirToMir {
                 builder.assign_var(var_id, value);
             }
 
-            Stmt::Assign { name, expr } => {
-                let value = self.lower_expr(builder, expr);
-                let reg = self.scope.get(name).unwrap();
+            Stmt::Assign { name, op, expr } => {
+                let mut value = self.lower_expr(builder, expr);
+                let reg = *self.scope.get(name).unwrap();
+
+                if let Some(op) = op {
+                    value = builder.build_binary(op, reg.into(), value);
+                }
 
-                builder.assign_var(*reg, value);
+                builder.assign_var(reg, value);
             }
 
             Stmt::Call { name, args } => {
@@ -151,10 +199,168 @@ impl HirToMir {
         }
     }
 
+    /// Compares the counter at the head of the loop and steps it in the block `continue` jumps to.
+    /// The step exits first if it would pass `end`, comparing the distance left as unsigned, so
+    /// that the counter never overflows near the type's bounds.
+    fn lower_stmt_for(
+        &mut self,
+        builder: &mut MirBuilder,
+        var: &str,
+        ty: &HirType,
+        range: Range,
+        body: Vec<Stmt>,
+        label: Option<String>,
+    ) {
+        let op = |op| typed_op(op, ty);
+
+        let start = self.lower_expr(builder, range.start);
+        let end = self.lower_expr(builder, range.end);
+        let literal_step = matches!(range.step, None | Some(Expr::Num { .. } | Expr::Int { .. }));
+        let step = match (range.step, ty) {
+            (Some(step), _) => self.lower_expr(builder, step),
+            (None, HirType::Int { ty }) => Value::int(Int::new(1, *ty)),
+            (None, _) => Value::num(1),
+        };
+
+        // `end` and `step` are held in their own variables so that the body cannot change them by
+        // assigning to variables they read.
+        let [counter, end, step] = [start, end, step].map(|value| {
+            let var_id = builder.declare_var();
+            builder.assign_var(var_id, value);
+            var_id
+        });
+
+        let header_block = builder.create_block();
+        let body_block = builder.create_block();
+        let next_block = builder.create_block();
+        let exit_block = builder.create_block();
+
+        builder.build_jump(header_block);
+        builder.set_active_block(header_block);
+
+        let cmp = if range.inclusive {
+            BinOp::LesserEq
+        } else {
+            BinOp::Lesser
+        };
+
+        let cond = builder.build_binary(op(cmp), counter.into(), end.into());
+        builder.build_branch(cond, body_block, exit_block);
+
+        builder.seal_block(body_block);
+        builder.set_active_block(body_block);
+
+        self.scope.create();
+        self.scope.set(var, &counter);
+        self.loop_stack.push(LoopTargets {
+            label,
+            next: next_block,
+            exit: exit_block,
+        });
+
+        self.lower_block(builder, body);
+
+        self.loop_stack.pop();
+        self.scope.pop();
+
+        if !builder.has_terminator() {
+            builder.build_jump(next_block);
+        }
+
+        builder.seal_block(next_block);
+        builder.set_active_block(next_block);
+
+        // The counter is undefined in the step if the body never falls through nor continues.
+        if builder.is_reachable(next_block) {
+            let step_block = builder.create_block();
+            let mut last = Self::lower_last_step(builder, ty, range.inclusive, counter, end, step);
+
+            // Literal steps are checked to be positive, but a step computed at runtime can be zero
+            // and would never leave the range.
+            if !literal_step {
+                let zero = match ty {
+                    HirType::Int { ty } => Value::int(Int::new(0, *ty)),
+                    _ => Value::num(0),
+                };
+
+                let stuck = builder.build_binary(op(BinOp::Eq), step.into(), zero);
+                last = builder.build_binary(BinOp::Or, last, stuck);
+            }
+
+            builder.build_branch(last, exit_block, step_block);
+            builder.seal_block(step_block);
+            builder.set_active_block(step_block);
+
+            let next = builder.build_binary(op(BinOp::Add), counter.into(), step.into());
+            builder.assign_var(counter, next);
+            builder.build_jump(header_block);
+        }
+
+        builder.seal_block(header_block);
+        builder.seal_block(exit_block);
+        builder.set_active_block(exit_block);
+    }
+
+    /// Whether stepping would leave the range, i.e. whether `end - counter` is below `step`, or
+    /// not above it for an exclusive range. Signed values are ordered as unsigned by flipping
+    /// their sign bit.
+    fn lower_last_step(
+        builder: &mut MirBuilder,
+        ty: &HirType,
+        inclusive: bool,
+        counter: VarID,
+        end: VarID,
+        step: VarID,
+    ) -> Value {
+        let cmp = if inclusive {
+            BinOp::Lesser
+        } else {
+            BinOp::LesserEq
+        };
+
+        let mut distance =
+            builder.build_binary(typed_op(BinOp::Sub, ty), end.into(), counter.into());
+        let mut step = step.into();
+
+        let sign = match ty {
+            HirType::Int { ty } if ty.is_signed() => {
+                Some(Value::int(Int::new(ty.min_value(), *ty)))
+            }
+            HirType::Int { .. } => None,
+            _ => Some(Value::num(i32::MIN)),
+        };
+
+        if let Some(sign) = sign {
+            distance = builder.build_binary(typed_op(BinOp::BitXor, ty), distance, sign);
+            step = builder.build_binary(typed_op(BinOp::BitXor, ty), step, sign);
+        }
+
+        builder.build_binary(typed_op(cmp, ty), distance, step)
+    }
+
+    fn lower_jump(builder: &mut MirBuilder, target: BlockID) {
+        builder.build_jump(target);
+
+        let unreachable = builder.create_block();
+        builder.set_active_block(unreachable);
+        builder.seal_block(unreachable);
+    }
+
+    fn loop_targets(&self, label: Option<&str>) -> &LoopTargets {
+        self.loop_stack
+            .iter()
+            .rev()
+            .find(|targets| label.is_none_or(|label| targets.label.as_deref() == Some(label)))
+            .unwrap()
+    }
+
     fn lower_expr(&mut self, builder: &mut MirBuilder, expr: Expr) -> Value {
         match expr {
             Expr::Bool { value } => Value::bool(value),
             Expr::Num { value } => Value::num(value),
+            Expr::Int { value, ty } => Value::int(Int::new(value.into(), ty.unwrap())),
+            Expr::Float { value } => Value::float(value),
+            Expr::Str { value } => Value::str(self.intern(value)),
             Expr::Var { name } => self.scope.get(name).unwrap().to_owned().into(),
             Expr::Call { name, args } => self.lower_expr_call(builder, name, args),
 
@@ -170,6 +376,12 @@ impl HirToMir {
 
                 builder.build_binary(op, lhs, rhs)
             }
+
+            Expr::Cast { expr, ty } => {
+                let arg = self.lower_expr(builder, *expr);
+
+                builder.build_cast(arg, self.lower_type(&ty).unwrap())
+            }
         }
     }
 
@@ -192,6 +404,30 @@ impl HirToMir {
             HirType::Void => None,
             HirType::Bool => Some(MirType::Bool),
             HirType::Num => Some(MirType::Num),
+            HirType::Int { ty } => Some(MirType::Int(*ty)),
+            HirType::Float => Some(MirType::Float),
+            HirType::Str => Some(MirType::Str),
         }
     }
+
+    fn intern(&mut self, value: String) -> StrID {
+        let idx = self
+            .strings
+            .iter()
+            .position(|string| *string == value)
+            .unwrap_or_else(|| {
+                self.strings.push(value);
+                self.strings.len() - 1
+            });
+
+        StrID(idx)
+    }
+}
+
+/// `op` specialized to the sized integer type `ty`, if it is one.
+fn typed_op(op: BinOp, ty: &HirType) -> BinOp {
+    match ty {
+        HirType::Int { ty } => op.to_int(*ty).unwrap(),
+        _ => op,
+    }
 }
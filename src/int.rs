@@ -0,0 +1,129 @@
+use serde::Serialize;
+use std::{
+    fmt::{self, Display, Formatter},
+    num::ParseIntError,
+};
+
+/// The sized integer types. `num` is the default `i32` and is not part of this family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum IntType {
+    I8,
+    I16,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl IntType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i64" => Self::I64,
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "u32" => Self::U32,
+            "u64" => Self::U64,
+
+            _ => return None,
+        })
+    }
+
+    pub fn bits(self) -> u32 {
+        match self {
+            Self::I8 | Self::U8 => 8,
+            Self::I16 | Self::U16 => 16,
+            Self::U32 => 32,
+            Self::I64 | Self::U64 => 64,
+        }
+    }
+
+    pub fn is_signed(self) -> bool {
+        matches!(self, Self::I8 | Self::I16 | Self::I64)
+    }
+
+    pub fn min_value(self) -> i128 {
+        if self.is_signed() {
+            -(1 << (self.bits() - 1))
+        } else {
+            0
+        }
+    }
+
+    pub fn max_value(self) -> i128 {
+        if self.is_signed() {
+            (1 << (self.bits() - 1)) - 1
+        } else {
+            (1 << self.bits()) - 1
+        }
+    }
+
+    pub fn contains(self, value: u64) -> bool {
+        self.wrap(i128::from(value)) == i128::from(value)
+    }
+
+    /// Truncates `value` to the width of the type, reinterpreting the sign bit if signed.
+    pub fn wrap(self, value: i128) -> i128 {
+        let shift = 128 - self.bits();
+        let value = value << shift;
+
+        if self.is_signed() {
+            value >> shift
+        } else {
+            i128::try_from(value.cast_unsigned() >> shift).unwrap()
+        }
+    }
+
+    /// Parses the digits of a literal in `radix`, failing with the standard error if they don't
+    /// fit. Digits of a negated literal carry a leading `-` and yield the magnitude.
+    pub fn parse(self, digits: &str, radix: u32) -> Result<u64, ParseIntError> {
+        Ok(match self {
+            Self::I8 => i8::from_str_radix(digits, radix)?.unsigned_abs().into(),
+            Self::I16 => i16::from_str_radix(digits, radix)?.unsigned_abs().into(),
+            Self::I64 => i64::from_str_radix(digits, radix)?.unsigned_abs(),
+            Self::U8 => u8::from_str_radix(digits, radix)?.into(),
+            Self::U16 => u16::from_str_radix(digits, radix)?.into(),
+            Self::U32 => u32::from_str_radix(digits, radix)?.into(),
+            Self::U64 => u64::from_str_radix(digits, radix)?,
+        })
+    }
+}
+
+impl Display for IntType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let sign = if self.is_signed() { 'i' } else { 'u' };
+        write!(f, "{sign}{}", self.bits())
+    }
+}
+
+/// A constant of a sized integer type, always wrapped to the width of its type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Int {
+    value: i128,
+    ty: IntType,
+}
+
+impl Int {
+    pub fn new(value: i128, ty: IntType) -> Self {
+        Self {
+            value: ty.wrap(value),
+            ty,
+        }
+    }
+
+    pub fn value(self) -> i128 {
+        self.value
+    }
+
+    pub fn ty(self) -> IntType {
+        self.ty
+    }
+}
+
+impl Display for Int {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.ty)
+    }
+}
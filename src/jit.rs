@@ -0,0 +1,269 @@
+use std::{cell::UnsafeCell, collections::BTreeMap, mem};
+
+use crate::{
+    bytecode::{self, Program, error::VmError, vm::Vm},
+    codegen::{
+        self,
+        x86_64::encode::{self, Checks},
+    },
+    int::IntType,
+    jit::{error::JitError, memory::ExecBuffer},
+    mir::{InstrKind, MirFun, MirModule, MirType, Operand},
+    mir_passes,
+    ops::{BinOp, UnOp},
+};
+
+pub mod error;
+mod memory;
+
+const MAX_PARAMS: usize = 6;
+const STACK_BUDGET: usize = 512 * 1024;
+
+#[repr(C)]
+#[derive(Debug, Default)]
+struct JitState {
+    bail: u8,
+    limit: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    offset: usize,
+    params: usize,
+    returns: bool,
+    /// Native code returns a `u32` in 32 bits, the VM zero-extends it.
+    unsigned: bool,
+}
+
+/// Runs functions as native code, falling back to the bytecode VM.
+///
+/// Only functions the encoder can lower, whose params fit in registers and whose callees are all
+/// native get compiled.
+/// Division by zero and deep recursion bail out of native code and the whole call is re-run in
+/// the VM, which reports the error.
+pub struct Jit {
+    buffer: ExecBuffer,
+    entries: BTreeMap<String, Entry>,
+    program: Program,
+    state: Box<UnsafeCell<JitState>>,
+}
+
+impl Jit {
+    /// Fails if the VM can't run the module either, since calls that native code doesn't take
+    /// or bails out of have nowhere else to go.
+    pub fn new(module: &MirModule) -> Result<Self, JitError> {
+        let mut native = module.clone();
+        mir_passes::out_of_ssa(&mut native);
+
+        let supported = supported(&native);
+        let program = bytecode::compile(module).map_err(|err| JitError::Unsupported { err })?;
+
+        let state = Box::new(UnsafeCell::new(JitState::default()));
+
+        let funs = native
+            .funs
+            .iter()
+            .filter(|fun| supported.contains(&fun.name))
+            .collect::<Vec<_>>();
+
+        let checks = Checks::Bail {
+            flag: state.get() as u64,
+            limit: state.get() as u64 + mem::offset_of!(JitState, limit) as u64,
+        };
+
+        let mut machine = encode::encode(&native, &funs, checks);
+        let offsets = machine
+            .symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.offset))
+            .collect::<BTreeMap<_, _>>();
+
+        for reloc in &machine.relocs {
+            let rel = i32::try_from(offsets[reloc.symbol.as_str()]).unwrap()
+                - i32::try_from(reloc.offset + 4).unwrap();
+
+            machine.code[reloc.offset..reloc.offset + 4].copy_from_slice(&rel.to_le_bytes());
+        }
+
+        let entries = funs
+            .iter()
+            .map(|fun| {
+                let entry = Entry {
+                    offset: offsets[fun.name.as_str()],
+                    params: fun.params.len(),
+                    returns: fun.return_ty.is_some(),
+                    unsigned: fun.return_ty == Some(MirType::Int(IntType::U32)),
+                };
+
+                (fun.name.clone(), entry)
+            })
+            .collect();
+
+        let buffer = ExecBuffer::new(&machine.code).map_err(|err| JitError::Memory { err })?;
+
+        Ok(Self {
+            buffer,
+            entries,
+            program,
+            state,
+        })
+    }
+
+    pub fn is_compiled(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    /// Takes and returns values the way [`Vm::call`] does.
+    pub fn call(&self, name: &str, args: &[i64]) -> Result<Option<i64>, VmError> {
+        if let Some(entry) = self.entries.get(name)
+            && entry.params == args.len()
+            && let Some(value) = self.run(
+                *entry,
+                &args.iter().map(|arg| low_bits(*arg)).collect::<Vec<_>>(),
+            )
+        {
+            let value = if entry.unsigned {
+                value.cast_unsigned().into()
+            } else {
+                value.into()
+            };
+
+            return Ok(entry.returns.then_some(value));
+        }
+
+        Vm::new(&self.program).call(name, args)
+    }
+
+    fn run(&self, entry: Entry, args: &[i32]) -> Option<i32> {
+        let ptr = self.buffer.at(entry.offset);
+        let sp = (&raw const entry) as usize;
+
+        // SAFETY: native code only touches the state between calls, and `&self` is not `Sync`.
+        let state = unsafe { &mut *self.state.get() };
+        state.bail = 0;
+        state.limit = sp.saturating_sub(STACK_BUDGET);
+
+        // SAFETY: `ptr` is the entry of a function compiled for exactly `args.len()` params.
+        let value = unsafe {
+            match *args {
+                [] => mem::transmute::<*const u8, extern "sysv64" fn() -> i32>(ptr)(),
+                [a] => mem::transmute::<*const u8, extern "sysv64" fn(i32) -> i32>(ptr)(a),
+                [a, b] => {
+                    mem::transmute::<*const u8, extern "sysv64" fn(i32, i32) -> i32>(ptr)(a, b)
+                }
+                [a, b, c] => mem::transmute::<*const u8, extern "sysv64" fn(i32, i32, i32) -> i32>(
+                    ptr,
+                )(a, b, c),
+                [a, b, c, d] => mem::transmute::<
+                    *const u8,
+                    extern "sysv64" fn(i32, i32, i32, i32) -> i32,
+                >(ptr)(a, b, c, d),
+                [a, b, c, d, e5] => mem::transmute::<
+                    *const u8,
+                    extern "sysv64" fn(i32, i32, i32, i32, i32) -> i32,
+                >(ptr)(a, b, c, d, e5),
+                [a, b, c, d, e5, f6] => mem::transmute::<
+                    *const u8,
+                    extern "sysv64" fn(i32, i32, i32, i32, i32, i32) -> i32,
+                >(ptr)(a, b, c, d, e5, f6),
+                _ => unreachable!(),
+            }
+        };
+
+        // SAFETY: native code has returned.
+        let bailed = unsafe { (*self.state.get()).bail != 0 };
+
+        (!bailed).then_some(value)
+    }
+}
+
+/// Native code holds every value in 32 bits, and the VM extends them from there.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "values of native functions fit in 32 bits"
+)]
+fn low_bits(value: i64) -> i32 {
+    value as i32
+}
+
+fn supported(module: &MirModule) -> Vec<String> {
+    let mut supported = module
+        .funs
+        .iter()
+        .filter(|fun| fun.params.len() <= MAX_PARAMS && is_encodable(module, fun))
+        .map(|fun| fun.name.as_str())
+        .collect::<Vec<_>>();
+
+    loop {
+        let next = supported
+            .iter()
+            .copied()
+            .filter(|name| {
+                let fun = module.funs.iter().find(|fun| fun.name == *name).unwrap();
+
+                fun.blocks
+                    .iter()
+                    .flat_map(|block| &block.instrs)
+                    .all(|instr| match &instr.kind {
+                        InstrKind::Call { name, .. } => supported.contains(&name.as_str()),
+                        _ => true,
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        if next.len() == supported.len() {
+            return next.into_iter().map(String::from).collect();
+        }
+
+        supported = next;
+    }
+}
+
+/// Whether the encoder can lower every instruction of `fun`. It keeps every value in 32 bits, so
+/// wide integers, floats and strings are left to the VM.
+fn is_encodable(module: &MirModule, fun: &MirFun) -> bool {
+    let instrs = fun
+        .blocks
+        .iter()
+        .flat_map(|block| &block.instrs)
+        .all(|instr| {
+            let kind = match &instr.kind {
+                InstrKind::Cast {
+                    ty: MirType::Float | MirType::Str,
+                    ..
+                }
+                | InstrKind::Unary {
+                    op: UnOp::FNegate, ..
+                }
+                | InstrKind::Binary {
+                    op:
+                        BinOp::FAdd
+                        | BinOp::FSub
+                        | BinOp::FMul
+                        | BinOp::FDiv
+                        | BinOp::FEq
+                        | BinOp::FNotEq
+                        | BinOp::FLesser
+                        | BinOp::FLesserEq
+                        | BinOp::FGreater
+                        | BinOp::FGreaterEq
+                        | BinOp::Concat,
+                    ..
+                } => false,
+
+                InstrKind::Copy { .. }
+                | InstrKind::Cast { .. }
+                | InstrKind::Unary { .. }
+                | InstrKind::Binary { .. }
+                | InstrKind::Call { .. } => true,
+            };
+
+            kind && instr
+                .kind
+                .operands()
+                .iter()
+                .all(|operand| !matches!(operand, Operand::Float(..) | Operand::Str(..)))
+        });
+
+    instrs && codegen::check_fun_types(module, fun, "JIT", codegen::fits_32_bits).is_ok()
+}
@@ -0,0 +1,19 @@
+use std::io;
+use thiserror::Error;
+
+use crate::codegen::error::CodegenError;
+
+#[derive(Error, Debug)]
+pub enum JitError {
+    #[error("Cannot map executable memory: {err}.")]
+    Memory {
+        #[source]
+        err: io::Error,
+    },
+
+    #[error("Cannot compile the module: {err}")]
+    Unsupported {
+        #[source]
+        err: CodegenError,
+    },
+}
@@ -0,0 +1,57 @@
+use std::{io, ptr};
+
+pub struct ExecBuffer {
+    ptr: *mut u8,
+    len: usize,
+}
+
+impl ExecBuffer {
+    pub fn new(code: &[u8]) -> io::Result<Self> {
+        let len = code.len().max(1);
+
+        // SAFETY: anonymous private mapping, no existing memory is touched.
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        let buffer = Self {
+            ptr: ptr.cast(),
+            len,
+        };
+
+        // SAFETY: the mapping is at least `code.len()` bytes and writable.
+        unsafe { ptr::copy_nonoverlapping(code.as_ptr(), buffer.ptr, code.len()) };
+
+        // SAFETY: `ptr` and `len` describe the mapping created above.
+        if unsafe { libc::mprotect(ptr, len, libc::PROT_READ | libc::PROT_EXEC) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(buffer)
+    }
+
+    pub fn at(&self, offset: usize) -> *const u8 {
+        assert!(offset < self.len);
+
+        // SAFETY: `offset` is within the mapping.
+        unsafe { self.ptr.add(offset) }
+    }
+}
+
+impl Drop for ExecBuffer {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` and `len` describe a mapping owned by this buffer.
+        unsafe { libc::munmap(self.ptr.cast(), self.len) };
+    }
+}
@@ -1,8 +1,11 @@
 use crate::{
+    lexer::error::LexError,
     position::Position,
     token::{Token, TokenKind},
 };
 
+pub mod error;
+
 pub struct Lexer<'src> {
     source: &'src str,
     pos: Position,
@@ -15,6 +18,9 @@ impl<'src> Iterator for Lexer<'src> {
         self.consume_whitespace();
 
         Some(match self.source.chars().next()? {
+            '/' if is_doc_comment(self.source) => self.consume_doc_comment(),
+            '/' if self.source.starts_with("/*") => self.consume_unterminated_comment(),
+
             '{' => self.consume_char(TokenKind::LeftBrace),
             '}' => self.consume_char(TokenKind::RightBrace),
             '(' => self.consume_char(TokenKind::LeftParen),
@@ -22,16 +28,44 @@ impl<'src> Iterator for Lexer<'src> {
             ';' => self.consume_char(TokenKind::Semicolon),
             ':' => self.consume_char(TokenKind::Colon),
             ',' => self.consume_char(TokenKind::Comma),
-            '+' => self.consume_char(TokenKind::Plus),
-            '-' => self.consume_char(TokenKind::Minus),
-            '*' => self.consume_char(TokenKind::Star),
-            '/' => self.consume_char(TokenKind::Slash),
+            '~' => self.consume_char(TokenKind::Tilde),
+
+            '+' => self.consume_eq(TokenKind::Plus, TokenKind::PlusEqual),
+            '-' => self.consume_eq(TokenKind::Minus, TokenKind::MinusEqual),
+            '*' => self.consume_eq(TokenKind::Star, TokenKind::StarEqual),
+            '/' => self.consume_eq(TokenKind::Slash, TokenKind::SlashEqual),
+            '%' => self.consume_eq(TokenKind::Percent, TokenKind::PercentEqual),
+            '&' => self.consume_eq(TokenKind::Ampersand, TokenKind::AmpersandEqual),
+            '|' => self.consume_eq(TokenKind::Pipe, TokenKind::PipeEqual),
+            '^' => self.consume_eq(TokenKind::Caret, TokenKind::CaretEqual),
+
+            '<' if self.source.starts_with("<<=") => {
+                self.consume_n(TokenKind::LesserLesserEqual, 3)
+            }
+            '>' if self.source.starts_with(">>=") => {
+                self.consume_n(TokenKind::GreaterGreaterEqual, 3)
+            }
+            '<' if self.source.starts_with("<<") => self.consume_n(TokenKind::LesserLesser, 2),
+            '>' if self.source.starts_with(">>") => self.consume_n(TokenKind::GreaterGreater, 2),
 
             '=' => self.consume_eq(TokenKind::Equal, TokenKind::EqualEqual),
             '<' => self.consume_eq(TokenKind::Lesser, TokenKind::LesserEqual),
             '>' => self.consume_eq(TokenKind::Greater, TokenKind::GreaterEqual),
             '!' => self.consume_eq(TokenKind::Not, TokenKind::NotEqual),
 
+            '"' => self.consume_str(),
+
+            '\'' if self.source[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') => {
+                self.consume_label()
+            }
+
+            '.' if self.source.starts_with("..=") => self.consume_n(TokenKind::DotDotEqual, 3),
+            '.' if self.source.starts_with("..") => self.consume_n(TokenKind::DotDot, 2),
+
+            '.' if self.source[1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                self.consume_numeric()
+            }
+
             c if c.is_ascii_digit() => self.consume_numeric(),
             c if is_valid_in_identifier(c) => self.consume_identifier(),
 
@@ -57,15 +91,20 @@ impl<'src> Lexer<'src> {
                 self.pos.take_char(c);
             }
 
-            if !self.source.starts_with("//") {
-                break;
-            }
+            if self.source.starts_with("//") && !is_doc_comment(self.source) {
+                self.until(|i| i == '\n');
 
-            self.until(|i| i == '\n');
+                if let Some(source) = self.source.strip_prefix('\n') {
+                    self.source = source;
+                    self.pos.newline();
+                }
+            } else if let Some(len) = block_comment_len(self.source) {
+                let (comment, source) = self.source.split_at(len);
 
-            if let Some(source) = self.source.strip_prefix('\n') {
+                comment.chars().for_each(|c| self.pos.take_char(c));
                 self.source = source;
-                self.pos.newline();
+            } else {
+                break;
             }
         }
     }
@@ -82,9 +121,90 @@ impl<'src> Lexer<'src> {
 
     fn consume_numeric(&mut self) -> Token<'src> {
         let pos = self.pos;
-        let slice = self.until(|i| !i.is_ascii_digit());
+        let bytes = self.source.as_bytes();
+
+        let radix = match bytes {
+            [b'0', b'x', ..] => 16,
+            [b'0', b'o', ..] => 8,
+            [b'0', b'b', ..] => 2,
+            _ => 10,
+        };
+
+        let result = if radix == 10 {
+            decimal_len(bytes)
+        } else {
+            radix_len(bytes, radix).map(|len| (TokenKind::Numeric, len))
+        };
+
+        let (kind, len) = result.unwrap_or_else(|err| {
+            let len = self.source.find(|c| !is_valid_in_identifier(c));
+            (TokenKind::Error(err), len.unwrap_or(self.source.len()))
+        });
+
+        let (slice, source) = self.source.split_at(len);
+
+        self.pos.column += len;
+        self.source = source;
+
+        Token::new(kind, slice, pos)
+    }
+
+    fn consume_str(&mut self) -> Token<'src> {
+        let pos = self.pos;
+        let mut chars = self.source.char_indices().skip(1);
+
+        let (kind, len) = loop {
+            match chars.next() {
+                Some((_, '\\')) => {
+                    chars.next();
+                }
+
+                Some((idx, '"')) => break (TokenKind::Str, idx + 1),
+                Some((idx, '\n')) => break (TokenKind::Unknown, idx),
+                Some(_) => {}
+                None => break (TokenKind::Unknown, self.source.len()),
+            }
+        };
+
+        let (slice, source) = self.source.split_at(len);
+
+        slice.chars().for_each(|c| self.pos.take_char(c));
+        self.source = source;
+
+        Token::new(kind, slice, pos)
+    }
+
+    fn consume_doc_comment(&mut self) -> Token<'src> {
+        let pos = self.pos;
+        let comment = self.until(|c| c == '\n');
+
+        Token::new(TokenKind::DocComment, comment, pos)
+    }
+
+    /// Only reached when `consume_whitespace` could not find the end of the comment.
+    fn consume_unterminated_comment(&mut self) -> Token<'src> {
+        let pos = self.pos;
+        let comment = self.until(|_| false);
 
-        Token::new(TokenKind::Numeric, slice, pos)
+        Token::new(
+            TokenKind::Error(LexError::UnterminatedComment),
+            comment,
+            pos,
+        )
+    }
+
+    fn consume_label(&mut self) -> Token<'src> {
+        let pos = self.pos;
+        let len = 1 + self.source[1..]
+            .find(|c| !is_valid_in_identifier(c))
+            .unwrap_or(self.source.len() - 1);
+
+        let (slice, source) = self.source.split_at(len);
+
+        self.pos.column += len;
+        self.source = source;
+
+        Token::new(TokenKind::Label, slice, pos)
     }
 
     fn consume_identifier(&mut self) -> Token<'src> {
@@ -95,15 +215,18 @@ impl<'src> Lexer<'src> {
     }
 
     fn consume_eq(&mut self, not_eq: TokenKind, eq: TokenKind) -> Token<'src> {
-        let pos = self.pos;
-        let (idx, kind) = match self.source.chars().nth(1) {
-            Some('=') => (2, eq),
-            _ => (1, not_eq),
-        };
+        if self.source[1..].starts_with('=') {
+            self.consume_n(eq, 2)
+        } else {
+            self.consume_char(not_eq)
+        }
+    }
 
-        let (slice, source) = self.source.split_at(idx);
+    fn consume_n(&mut self, kind: TokenKind, n: usize) -> Token<'src> {
+        let pos = self.pos;
+        let (slice, source) = self.source.split_at(n);
 
-        self.pos.column += idx;
+        self.pos.column += n;
         self.source = source;
 
         Token::new(kind, slice, pos)
@@ -122,6 +245,111 @@ impl<'src> Lexer<'src> {
     }
 }
 
+fn decimal_len(bytes: &[u8]) -> Result<(TokenKind, usize), LexError> {
+    let mut kind = TokenKind::Numeric;
+    let mut len = digits(bytes, 0, 10)?;
+
+    if bytes.get(len) == Some(&b'.') && bytes.get(len + 1).is_some_and(u8::is_ascii_digit) {
+        kind = TokenKind::Float;
+        len += 1 + digits(bytes, len + 1, 10)?;
+    }
+
+    if matches!(bytes.get(len), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+        let exponent = digits(bytes, len + 1 + sign, 10)?;
+
+        if exponent > 0 {
+            kind = TokenKind::Float;
+            len += 1 + sign + exponent;
+        }
+    }
+
+    if kind == TokenKind::Numeric {
+        len += suffix_len(bytes, len);
+    }
+
+    Ok((kind, len))
+}
+
+/// Lexes a literal with a `0x`, `0o` or `0b` prefix, rejecting any trailing alphanumerics.
+fn radix_len(bytes: &[u8], radix: u32) -> Result<usize, LexError> {
+    let len = 2 + digits(bytes, 2, radix)?;
+    let len = if len == 2 {
+        len
+    } else {
+        len + suffix_len(bytes, len)
+    };
+
+    match bytes.get(len).map(|&b| char::from(b)) {
+        Some(digit) if is_valid_in_identifier(digit) => {
+            Err(LexError::InvalidDigit { digit, radix })
+        }
+        _ if len == 2 => Err(LexError::MissingDigits { radix }),
+        _ => Ok(len),
+    }
+}
+
+/// Counts the digits and `_` separators at `idx`, requiring each separator to sit between digits.
+fn digits(bytes: &[u8], idx: usize, radix: u32) -> Result<usize, LexError> {
+    let rest = bytes.get(idx..).unwrap_or_default();
+    let len = rest
+        .iter()
+        .take_while(|&&b| b == b'_' || char::from(b).is_digit(radix))
+        .count();
+
+    let run = &rest[..len];
+
+    if run.first() == Some(&b'_') || run.last() == Some(&b'_') || run.windows(2).any(|w| w == b"__")
+    {
+        return Err(LexError::MisplacedSeparator);
+    }
+
+    Ok(run.len())
+}
+
+fn suffix_len(bytes: &[u8], idx: usize) -> usize {
+    if !matches!(bytes.get(idx), Some(b'i' | b'u')) {
+        return 0;
+    }
+
+    match bytes[idx + 1..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit())
+        .count()
+    {
+        0 => 0,
+        width => 1 + width,
+    }
+}
+
+fn is_doc_comment(source: &str) -> bool {
+    source.starts_with("///") && !source.starts_with("////")
+}
+
+/// Length of the possibly nested block comment starting `source`, if there is a terminated one.
+fn block_comment_len(source: &str) -> Option<usize> {
+    let bytes = source.as_bytes();
+    let (mut idx, mut depth) = (0, 0);
+
+    loop {
+        match bytes.get(idx..idx + 2)? {
+            b"/*" => depth += 1,
+            b"*/" if depth > 0 => depth -= 1,
+            _ if depth == 0 => return None,
+            _ => {
+                idx += 1;
+                continue;
+            }
+        }
+
+        idx += 2;
+
+        if depth == 0 {
+            return Some(idx);
+        }
+    }
+}
+
 fn is_valid_in_identifier(c: char) -> bool {
     c.is_ascii_alphanumeric() || c == '_'
 }
@@ -139,7 +367,14 @@ fn match_kind(identifier: &str) -> TokenKind {
         "loop" => TokenKind::Loop,
         "while" => TokenKind::While,
         "break" => TokenKind::Break,
+        "continue" => TokenKind::Continue,
+        "for" => TokenKind::For,
+        "in" => TokenKind::In,
+        "step" => TokenKind::Step,
         "let" => TokenKind::Let,
+        "inline" => TokenKind::Inline,
+        "noinline" => TokenKind::NoInline,
+        "as" => TokenKind::As,
 
         _ => TokenKind::Identifier,
     }
@@ -0,0 +1,17 @@
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LexError {
+    #[error("missing digits in base {radix} literal")]
+    MissingDigits { radix: u32 },
+
+    #[error("invalid digit '{digit}' in base {radix} literal")]
+    InvalidDigit { digit: char, radix: u32 },
+
+    #[error("misplaced digit separator")]
+    MisplacedSeparator,
+
+    #[error("unterminated block comment")]
+    UnterminatedComment,
+}
@@ -1,13 +1,21 @@
+pub mod bytecode;
 pub mod cfg;
+pub mod codegen;
+pub mod float;
 pub mod hir;
 pub mod hir_to_mir;
+pub mod int;
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+pub mod jit;
 pub mod lexer;
 pub mod mir;
+pub mod mir_analysis;
 pub mod mir_builder;
 pub mod mir_passes;
 pub mod ops;
 pub mod parser;
 pub mod position;
+pub mod regalloc;
 pub mod scope;
 pub mod token;
 pub mod type_resolver;
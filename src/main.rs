@@ -1,34 +1,175 @@
 use std::{
     fs,
     io::{self, Write as _, stdin, stdout},
+    path::Path,
 };
 
 use lang::{
-    hir_to_mir::HirToMir, lexer::Lexer, mir_passes, parser::Parser, type_resolver::TypeResolver,
+    bytecode::{self, Program, vm::Vm},
+    codegen::{c, elf, llvm, wasm, x86_64},
+    hir_to_mir::HirToMir,
+    jit::Jit,
+    lexer::Lexer,
+    mir::MirModule,
+    mir_passes,
+    parser::Parser,
+    type_resolver::TypeResolver,
 };
 
-fn compile(source: &str) {
+#[derive(Debug, Clone, Copy)]
+enum Emit {
+    Mir,
+    Asm,
+    Obj,
+    C,
+    Llvm,
+    Wasm,
+    Wat,
+    Langc,
+}
+
+fn lower(source: &str) -> Option<MirModule> {
     let mut hir = match Parser::new(Lexer::new(source)).parse() {
         Ok(hir) => hir,
-        Err(err) => return println!("Parse Error:\n{err}"),
+        Err(err) => {
+            println!("Parse Error:\n{err}");
+            return None;
+        }
     };
 
     if let Err(err) = TypeResolver::new().resolve(&mut hir) {
         println!("Type Resolver Error:\n{err}");
+        return None;
+    }
+
+    Some(HirToMir::new().lower_module(hir))
+}
+
+fn compile(source: &str, emit: Emit) {
+    let Some(mut mir) = lower(source) else {
+        return;
+    };
+
+    match emit {
+        Emit::Mir => {
+            println!("\n== INITIAL MIR ==");
+            println!("{mir}");
+
+            mir_passes::run_passes(&mut mir);
+
+            println!("\n== FINAL MIR ==");
+            println!("{mir}");
+        }
+
+        Emit::Asm => {
+            mir_passes::run_passes(&mut mir);
+            mir_passes::remove_dead_funs(&mut mir);
+
+            match x86_64::emit_module(&mir) {
+                Ok(asm) => print!("{asm}"),
+                Err(err) => println!("Codegen Error:\n{err}"),
+            }
+        }
+
+        Emit::Obj => {
+            mir_passes::run_passes(&mut mir);
+
+            match elf::emit_object(&mir) {
+                Ok(obj) => write(&obj),
+                Err(err) => println!("Codegen Error:\n{err}"),
+            }
+        }
+
+        Emit::C => {
+            mir_passes::run_passes(&mut mir);
+            mir_passes::remove_dead_funs(&mut mir);
+
+            match c::emit_module(&mir) {
+                Ok(code) => print!("{code}"),
+                Err(err) => println!("Codegen Error:\n{err}"),
+            }
+        }
+
+        Emit::Llvm => {
+            mir_passes::run_passes(&mut mir);
+            mir_passes::remove_dead_funs(&mut mir);
+
+            match llvm::emit_module(&mir) {
+                Ok(ir) => print!("{ir}"),
+                Err(err) => println!("Codegen Error:\n{err}"),
+            }
+        }
+
+        Emit::Wasm => {
+            mir_passes::run_passes(&mut mir);
+
+            match wasm::lower_module(&mir) {
+                Ok(module) => write(&module.encode()),
+                Err(err) => println!("Codegen Error:\n{err}"),
+            }
+        }
+
+        Emit::Wat => {
+            mir_passes::run_passes(&mut mir);
+
+            match wasm::lower_module(&mir) {
+                Ok(module) => println!("{module}"),
+                Err(err) => println!("Codegen Error:\n{err}"),
+            }
+        }
+
+        Emit::Langc => {
+            mir_passes::run_passes(&mut mir);
+            mir_passes::remove_dead_funs(&mut mir);
+
+            match bytecode::compile(&mir) {
+                Ok(program) => write(&program.to_bytes()),
+                Err(err) => println!("Codegen Error:\n{err}"),
+            }
+        }
     }
+}
 
-    let mut mir = HirToMir::new().lower_module(hir);
+fn write(bytes: &[u8]) {
+    if let Err(err) = stdout().write_all(bytes) {
+        println!("Write Error:\n{err}");
+    }
+}
 
-    println!("\n== INITIAL MIR ==");
-    println!("{mir}");
+fn run(bytes: &[u8]) {
+    let program = match Program::from_bytes(bytes) {
+        Ok(program) => program,
+        Err(err) => return println!("Bytecode Error:\n{err}"),
+    };
+
+    match Vm::new(&program).call("main", &[]) {
+        Ok(Some(value)) => println!("{value}"),
+        Ok(None) => {}
+        Err(err) => println!("Runtime Error:\n{err}"),
+    }
+}
+
+fn eval(source: &str) {
+    let Some(mut mir) = lower(source) else {
+        return;
+    };
 
     mir_passes::run_passes(&mut mir);
 
-    println!("\n== FINAL MIR ==");
-    println!("{mir}");
+    let jit = match Jit::new(&mir) {
+        Ok(jit) => jit,
+        Err(err) => return println!("Codegen Error:\n{err}"),
+    };
+
+    match jit.call("main", &[]) {
+        Ok(Some(value)) => println!("{value}"),
+        Ok(None) => {}
+        Err(err) => println!("Runtime Error:\n{err}"),
+    }
 }
 
-fn repl() -> io::Result<()> {
+/// Without an `--emit` flag each line is run through the JIT and `main`'s result is printed.
+fn repl(emit: Option<Emit>) -> io::Result<()> {
     loop {
         let mut line = String::new();
 
@@ -40,16 +181,42 @@ fn repl() -> io::Result<()> {
             return Ok(());
         }
 
-        compile(&line);
+        match emit {
+            Some(emit) => compile(&line, emit),
+            None => eval(&line),
+        }
     }
 }
 
 fn main() -> io::Result<()> {
-    if let Some(path) = std::env::args().nth(1) {
+    let mut path = None;
+    let mut emit = None;
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--emit=mir" => emit = Some(Emit::Mir),
+            "--emit=asm" => emit = Some(Emit::Asm),
+            "--emit=obj" => emit = Some(Emit::Obj),
+            "--emit=c" => emit = Some(Emit::C),
+            "--emit=llvm" => emit = Some(Emit::Llvm),
+            "--emit=wasm" => emit = Some(Emit::Wasm),
+            "--emit=wat" => emit = Some(Emit::Wat),
+            "--emit=langc" => emit = Some(Emit::Langc),
+            _ => path = Some(arg),
+        }
+    }
+
+    if let Some(path) = path.as_ref().filter(|path| {
+        Path::new(path)
+            .extension()
+            .is_some_and(|ext| ext == "langc")
+    }) {
+        run(&fs::read(path)?);
+    } else if let Some(path) = path {
         let source = fs::read_to_string(&path)?;
-        compile(&source);
+        compile(&source, emit.unwrap_or(Emit::Mir));
     } else {
-        repl()?;
+        repl(emit)?;
     }
 
     Ok(())
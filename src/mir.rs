@@ -1,5 +1,8 @@
 use crate::{
     cfg::Cfg,
+    float::Float,
+    hir::InlineHint,
+    int::{Int, IntType},
     ops::{BinOp, UnOp},
 };
 
@@ -8,12 +11,15 @@ mod block_id;
 mod const_folding;
 mod display;
 mod fun;
+mod instr;
 mod operand;
+mod term;
 mod r#type;
 
 #[derive(Debug, Clone)]
 pub struct MirModule {
     pub funs: Vec<MirFun>,
+    pub strings: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -23,14 +29,18 @@ pub struct MirFun {
     pub blocks: Vec<BasicBlock>,
     pub cfg: Cfg,
     pub return_ty: Option<MirType>,
+    pub inline: Option<InlineHint>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Reg(pub usize);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BlockID(pub usize);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StrID(pub usize);
+
 #[derive(Debug, Clone)]
 pub struct BasicBlock {
     pub id: BlockID,
@@ -51,10 +61,13 @@ pub struct Instr {
     pub kind: InstrKind,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Operand {
     Bool(bool),
     Num(i32),
+    Int(Int),
+    Float(Float),
+    Str(StrID),
     Reg(Reg),
 }
 
@@ -75,6 +88,15 @@ pub enum InstrKind {
         name: String,
         args: Vec<Operand>,
     },
+
+    Copy {
+        src: Operand,
+    },
+
+    Cast {
+        arg: Operand,
+        ty: MirType,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -94,8 +116,11 @@ pub enum Term {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum MirType {
     Num,
+    Int(IntType),
     Bool,
+    Float,
+    Str,
 }
@@ -1,72 +1,241 @@
+use std::cmp::Ordering;
+
 use crate::{
-    mir::{InstrKind, Operand},
+    int::{Int, IntType},
+    mir::{InstrKind, MirType, Operand},
     ops::{BinOp, UnOp},
 };
 
 impl InstrKind {
     pub fn try_fold(&self) -> Option<Operand> {
         match self {
-            Self::Call { .. } => None,
+            Self::Call { .. } | Self::Copy { .. } => None,
 
             Self::Unary { op, arg } => arg.is_const().then(|| match op {
-                UnOp::Negate => Operand::Num(-arg.as_num()),
+                UnOp::Negate => match arg {
+                    Operand::Int(value) => Operand::Int(Int::new(-value.value(), value.ty())),
+                    _ => Operand::Num(arg.as_num().wrapping_neg()),
+                },
                 UnOp::Not => Operand::Bool(!arg.as_bool()),
+                UnOp::BitNot => match arg {
+                    Operand::Int(value) => Operand::Int(Int::new(!value.value(), value.ty())),
+                    _ => Operand::Num(!arg.as_num()),
+                },
+                UnOp::FNegate => Operand::from(-arg.as_float()),
             }),
 
-            Self::Binary { op, lhs, rhs } => (lhs.is_const() && rhs.is_const())
-                .then(|| match op {
-                    BinOp::Add => Operand::Num(lhs.as_num() + rhs.as_num()),
-                    BinOp::Sub => Operand::Num(lhs.as_num() - rhs.as_num()),
-                    BinOp::Mul => Operand::Num(lhs.as_num() * rhs.as_num()),
-                    BinOp::Div => Operand::Num(lhs.as_num() / rhs.as_num()),
-
-                    BinOp::And => Operand::Bool(lhs.as_bool() && rhs.as_bool()),
-                    BinOp::Or => Operand::Bool(lhs.as_bool() || rhs.as_bool()),
-
-                    BinOp::Eq => Operand::Bool(lhs == rhs),
-                    BinOp::NotEq => Operand::Bool(lhs != rhs),
-                    BinOp::Lesser => Operand::Bool(lhs.as_num() < rhs.as_num()),
-                    BinOp::LesserEq => Operand::Bool(lhs.as_num() <= rhs.as_num()),
-                    BinOp::Greater => Operand::Bool(lhs.as_num() > rhs.as_num()),
-                    BinOp::GreaterEq => Operand::Bool(lhs.as_num() >= rhs.as_num()),
-                })
-                .or_else(|| {
-                    (lhs.is_const() || rhs.is_const())
-                        .then(|| {
-                            let (cons, nconst) = if lhs.is_const() {
-                                (*lhs, *rhs)
-                            } else {
-                                (*rhs, *lhs)
-                            };
-
-                            match op {
-                                BinOp::Add | BinOp::Sub if cons.as_num() == 0 => Some(nconst),
-                                BinOp::Mul | BinOp::Div if cons.as_num() == 1 => Some(nconst),
-                                BinOp::Mul if cons.as_num() == 0 => Some(Operand::Num(0)),
-
-                                BinOp::And if cons.as_bool() => Some(nconst),
-                                BinOp::And if !cons.as_bool() => Some(Operand::Bool(false)),
-                                BinOp::Or if cons.as_bool() => Some(Operand::Bool(true)),
-                                BinOp::Or if !cons.as_bool() => Some(nconst),
-
-                                _ => None,
-                            }
-                        })
-                        .flatten()
-                })
-                .or_else(|| {
-                    (lhs == rhs)
-                        .then(|| match op {
-                            BinOp::Sub => Some(Operand::Num(0)),
-                            BinOp::Div => Some(Operand::Num(1)),
-                            BinOp::And | BinOp::Or => Some(*lhs),
-                            BinOp::NotEq | BinOp::Lesser | BinOp::Greater => Some(false.into()),
-                            BinOp::Eq | BinOp::LesserEq | BinOp::GreaterEq => Some(true.into()),
-
-                            _ => None,
-                        })
-                        .flatten()
-                }),
+            Self::Binary { op, lhs, rhs } => fold_consts(*op, lhs, rhs)
+                .or_else(|| fold_identity(*op, lhs, rhs))
+                .or_else(|| fold_same_operands(*op, lhs, rhs)),
+
+            Self::Cast { arg, ty } => arg.is_const().then(|| fold_cast(*arg, ty)),
+        }
+    }
+}
+
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    reason = "casts follow the semantics of `as`"
+)]
+fn fold_cast(arg: Operand, ty: &MirType) -> Operand {
+    match (arg, ty) {
+        (Operand::Bool(..), MirType::Bool)
+        | (Operand::Float(..), MirType::Float)
+        | (_, MirType::Str) => arg,
+
+        (Operand::Bool(value), ty) => fold_cast(Operand::Num(value.into()), ty),
+        (Operand::Float(value), MirType::Num) => Operand::Num(value.to_num()),
+
+        (Operand::Float(value), MirType::Int(ty)) => {
+            let value = (value.value() as i128).clamp(ty.min_value(), ty.max_value());
+            Operand::Int(Int::new(value, *ty))
+        }
+
+        (_, MirType::Num) => Operand::Num(arg.as_int() as i32),
+        (_, MirType::Int(ty)) => Operand::Int(Int::new(arg.as_int(), *ty)),
+        (_, MirType::Float) => Operand::from(arg.as_int() as f64),
+        (_, MirType::Bool) => unreachable!(),
+    }
+}
+
+fn fold_consts(op: BinOp, lhs: &Operand, rhs: &Operand) -> Option<Operand> {
+    if !lhs.is_const() || !rhs.is_const() {
+        return None;
+    }
+
+    match op {
+        BinOp::Div | BinOp::Rem if rhs.as_num() == 0 => None,
+        BinOp::IDiv(..) | BinOp::UDiv(..) | BinOp::IRem(..) | BinOp::URem(..)
+            if rhs.as_int() == 0 =>
+        {
+            None
+        }
+
+        BinOp::Add => Some(Operand::Num(lhs.as_num().wrapping_add(rhs.as_num()))),
+        BinOp::Sub => Some(Operand::Num(lhs.as_num().wrapping_sub(rhs.as_num()))),
+        BinOp::Mul => Some(Operand::Num(lhs.as_num().wrapping_mul(rhs.as_num()))),
+        BinOp::Div => Some(Operand::Num(lhs.as_num().wrapping_div(rhs.as_num()))),
+        BinOp::Rem => Some(Operand::Num(lhs.as_num().wrapping_rem(rhs.as_num()))),
+
+        BinOp::BitAnd => Some(Operand::Num(lhs.as_num() & rhs.as_num())),
+        BinOp::BitOr => Some(Operand::Num(lhs.as_num() | rhs.as_num())),
+        BinOp::BitXor => Some(Operand::Num(lhs.as_num() ^ rhs.as_num())),
+        BinOp::Shl => Some(Operand::Num(
+            lhs.as_num().wrapping_shl(rhs.as_num().cast_unsigned()),
+        )),
+        BinOp::Shr => Some(Operand::Num(
+            lhs.as_num().wrapping_shr(rhs.as_num().cast_unsigned()),
+        )),
+
+        BinOp::And => Some(Operand::Bool(lhs.as_bool() && rhs.as_bool())),
+        BinOp::Or => Some(Operand::Bool(lhs.as_bool() || rhs.as_bool())),
+        BinOp::FAdd => Some(Operand::from(lhs.as_float() + rhs.as_float())),
+        BinOp::FSub => Some(Operand::from(lhs.as_float() - rhs.as_float())),
+        BinOp::FMul => Some(Operand::from(lhs.as_float() * rhs.as_float())),
+        BinOp::FDiv => Some(Operand::from(lhs.as_float() / rhs.as_float())),
+
+        BinOp::FEq => Some(Operand::Bool(
+            lhs.as_float().partial_cmp(&rhs.as_float()) == Some(Ordering::Equal),
+        )),
+        BinOp::FNotEq => Some(Operand::Bool(
+            lhs.as_float().partial_cmp(&rhs.as_float()) != Some(Ordering::Equal),
+        )),
+        BinOp::FLesser => Some(Operand::Bool(lhs.as_float() < rhs.as_float())),
+        BinOp::FLesserEq => Some(Operand::Bool(lhs.as_float() <= rhs.as_float())),
+        BinOp::FGreater => Some(Operand::Bool(lhs.as_float() > rhs.as_float())),
+        BinOp::FGreaterEq => Some(Operand::Bool(lhs.as_float() >= rhs.as_float())),
+
+        BinOp::IAdd(ty) => Some(Operand::Int(Int::new(lhs.as_int() + rhs.as_int(), ty))),
+        BinOp::ISub(ty) => Some(Operand::Int(Int::new(lhs.as_int() - rhs.as_int(), ty))),
+        BinOp::IMul(ty) => Some(Operand::Int(Int::new(
+            lhs.as_int().wrapping_mul(rhs.as_int()),
+            ty,
+        ))),
+        BinOp::IDiv(ty) | BinOp::UDiv(ty) => {
+            Some(Operand::Int(Int::new(lhs.as_int() / rhs.as_int(), ty)))
+        }
+        BinOp::IRem(ty) | BinOp::URem(ty) => {
+            Some(Operand::Int(Int::new(lhs.as_int() % rhs.as_int(), ty)))
+        }
+
+        BinOp::IAnd(ty) => Some(Operand::Int(Int::new(lhs.as_int() & rhs.as_int(), ty))),
+        BinOp::IOr(ty) => Some(Operand::Int(Int::new(lhs.as_int() | rhs.as_int(), ty))),
+        BinOp::IXor(ty) => Some(Operand::Int(Int::new(lhs.as_int() ^ rhs.as_int(), ty))),
+        BinOp::IShl(ty) => Some(Operand::Int(Int::new(
+            lhs.as_int() << shift_amount(rhs, ty),
+            ty,
+        ))),
+        BinOp::IShr(ty) | BinOp::UShr(ty) => Some(Operand::Int(Int::new(
+            lhs.as_int() >> shift_amount(rhs, ty),
+            ty,
+        ))),
+
+        BinOp::Concat => None,
+
+        BinOp::Eq => Some(Operand::Bool(lhs == rhs)),
+        BinOp::NotEq => Some(Operand::Bool(lhs != rhs)),
+        BinOp::Lesser | BinOp::ULesser => Some(Operand::Bool(lhs.as_int() < rhs.as_int())),
+        BinOp::LesserEq | BinOp::ULesserEq => Some(Operand::Bool(lhs.as_int() <= rhs.as_int())),
+        BinOp::Greater | BinOp::UGreater => Some(Operand::Bool(lhs.as_int() > rhs.as_int())),
+        BinOp::GreaterEq | BinOp::UGreaterEq => Some(Operand::Bool(lhs.as_int() >= rhs.as_int())),
+    }
+}
+
+/// Shift amounts are masked to the width of the type, like the hardware does for `num`.
+fn shift_amount(rhs: &Operand, ty: IntType) -> i128 {
+    rhs.as_int() & i128::from(ty.bits() - 1)
+}
+
+fn fold_identity(op: BinOp, lhs: &Operand, rhs: &Operand) -> Option<Operand> {
+    let (cons, nconst) = match (lhs.is_const(), rhs.is_const()) {
+        (true, _) => (*lhs, *rhs),
+        (_, true) => (*rhs, *lhs),
+        _ => return None,
+    };
+
+    if is_identity(op, cons, rhs) {
+        return Some(nconst);
+    }
+
+    if is_absorbing(op, cons) {
+        return Some(cons);
+    }
+
+    match op {
+        BinOp::Rem if *rhs == Operand::Num(1) => Some(Operand::Num(0)),
+        BinOp::IRem(ty) | BinOp::URem(ty) if *rhs == Operand::Int(Int::new(1, ty)) => {
+            Some(Operand::Int(Int::new(0, ty)))
         }
+
+        _ => None,
+    }
+}
+
+/// Whether `cons` leaves the other operand unchanged. Non-commutative ops only have a right identity.
+fn is_identity(op: BinOp, cons: Operand, rhs: &Operand) -> bool {
+    match op {
+        BinOp::Add | BinOp::BitOr | BinOp::BitXor => cons.as_num() == 0,
+        BinOp::Sub | BinOp::Shl | BinOp::Shr => *rhs == Operand::Num(0),
+        BinOp::Mul => cons.as_num() == 1,
+        BinOp::Div => *rhs == Operand::Num(1),
+        BinOp::BitAnd => cons.as_num() == -1,
+
+        BinOp::IAdd(..) | BinOp::IOr(..) | BinOp::IXor(..) => cons.as_int() == 0,
+        BinOp::ISub(ty) | BinOp::IShl(ty) | BinOp::IShr(ty) | BinOp::UShr(ty) => {
+            *rhs == Operand::Int(Int::new(0, ty))
+        }
+        BinOp::IMul(..) => cons.as_int() == 1,
+        BinOp::IDiv(ty) | BinOp::UDiv(ty) => *rhs == Operand::Int(Int::new(1, ty)),
+        BinOp::IAnd(ty) => cons.as_int() == ty.wrap(-1),
+
+        BinOp::FSub => *rhs == Operand::from(0.0),
+        BinOp::FMul => cons == Operand::from(1.0),
+        BinOp::FDiv => *rhs == Operand::from(1.0),
+
+        BinOp::And => cons.as_bool(),
+        BinOp::Or => !cons.as_bool(),
+
+        _ => false,
+    }
+}
+
+/// Whether `cons` determines the result regardless of the other operand.
+fn is_absorbing(op: BinOp, cons: Operand) -> bool {
+    match op {
+        BinOp::Mul | BinOp::BitAnd => cons.as_num() == 0,
+        BinOp::BitOr => cons.as_num() == -1,
+        BinOp::IMul(..) | BinOp::IAnd(..) => cons.as_int() == 0,
+        BinOp::IOr(ty) => cons.as_int() == ty.wrap(-1),
+        BinOp::And => !cons.as_bool(),
+        BinOp::Or => cons.as_bool(),
+
+        _ => false,
+    }
+}
+
+fn fold_same_operands(op: BinOp, lhs: &Operand, rhs: &Operand) -> Option<Operand> {
+    if lhs != rhs {
+        return None;
+    }
+
+    match op {
+        BinOp::Sub | BinOp::BitXor => Some(Operand::Num(0)),
+        BinOp::And
+        | BinOp::Or
+        | BinOp::BitAnd
+        | BinOp::BitOr
+        | BinOp::IAnd(..)
+        | BinOp::IOr(..) => Some(*lhs),
+        BinOp::ISub(ty) | BinOp::IXor(ty) => Some(Operand::Int(Int::new(0, ty))),
+
+        BinOp::NotEq | BinOp::Lesser | BinOp::Greater | BinOp::ULesser | BinOp::UGreater => {
+            Some(false.into())
+        }
+        BinOp::Eq | BinOp::LesserEq | BinOp::GreaterEq | BinOp::ULesserEq | BinOp::UGreaterEq => {
+            Some(true.into())
+        }
+
+        _ => None,
     }
 }
@@ -1,12 +1,24 @@
 use itertools::Itertools as _;
 use std::fmt::{self, Display, Formatter};
 
-use crate::mir::{
-    BasicBlock, BlockID, Instr, InstrKind, MirFun, MirModule, Operand, Phi, Reg, Term,
+use crate::{
+    hir::InlineHint,
+    mir::{
+        BasicBlock, BlockID, Instr, InstrKind, MirFun, MirModule, MirType, Operand, Phi, Reg,
+        StrID, Term,
+    },
 };
 
 impl Display for MirModule {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (idx, value) in self.strings.iter().enumerate() {
+            writeln!(f, "{} = {value:?}", StrID(idx))?;
+        }
+
+        if !self.strings.is_empty() {
+            writeln!(f)?;
+        }
+
         for fun in &self.funs {
             write!(f, "{fun}")?;
         }
@@ -17,7 +29,25 @@ impl Display for MirModule {
 
 impl Display for MirFun {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        writeln!(f, "fun {}() {{", self.name)?;
+        let params = self
+            .params
+            .iter()
+            .map(|(reg, ty)| format!("{reg}: {ty}"))
+            .join(", ");
+
+        match self.inline {
+            Some(InlineHint::Always) => write!(f, "inline ")?,
+            Some(InlineHint::Never) => write!(f, "noinline ")?,
+            None => {}
+        }
+
+        write!(f, "fun {}({params})", self.name)?;
+
+        if let Some(ty) = &self.return_ty {
+            write!(f, ": {ty}")?;
+        }
+
+        writeln!(f, " {{")?;
 
         for block in &self.blocks {
             write!(f, "{block}")?;
@@ -75,6 +105,8 @@ impl Display for InstrKind {
             Self::Unary { op, arg } => write!(f, "{op} {arg}"),
             Self::Binary { op, lhs, rhs } => write!(f, "{op} {lhs}, {rhs}"),
             Self::Call { name, args } => write!(f, "call {name}({})", args.iter().join(", ")),
+            Self::Copy { src } => write!(f, "copy {src}"),
+            Self::Cast { arg, ty } => write!(f, "cast {arg} as {ty}"),
         }
     }
 }
@@ -107,6 +139,9 @@ impl Display for Operand {
             Self::Reg(reg) => write!(f, "{reg}"),
             Self::Bool(value) => write!(f, "{value}"),
             Self::Num(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Str(id) => write!(f, "{id}"),
         }
     }
 }
@@ -117,8 +152,26 @@ impl Display for Reg {
     }
 }
 
+impl Display for StrID {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "str.{}", self.0)
+    }
+}
+
 impl Display for BlockID {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "bb{}", self.0)
     }
 }
+
+impl Display for MirType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Num => write!(f, "num"),
+            Self::Bool => write!(f, "bool"),
+            Self::Int(ty) => write!(f, "{ty}"),
+            Self::Float => write!(f, "float"),
+            Self::Str => write!(f, "str"),
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{cfg::Cfg, mir::MirFun};
+use crate::{
+    cfg::Cfg,
+    mir::{MirFun, Reg},
+};
 
 impl MirFun {
     pub fn new(name: String) -> Self {
@@ -8,6 +11,25 @@ impl MirFun {
             blocks: Vec::new(),
             cfg: Cfg::default(),
             return_ty: None,
+            inline: None,
         }
     }
+
+    pub fn next_reg(&self) -> Reg {
+        let params = self.params.iter().map(|(reg, _)| *reg);
+        let phis = self
+            .blocks
+            .iter()
+            .flat_map(|b| b.phis.iter().map(|p| p.dest));
+        let instrs = self
+            .blocks
+            .iter()
+            .flat_map(|b| b.instrs.iter().map(|i| i.dest));
+
+        params
+            .chain(phis)
+            .chain(instrs)
+            .max()
+            .map_or(Reg(0), |reg| Reg(reg.0 + 1))
+    }
 }
@@ -0,0 +1,12 @@
+use crate::mir::{InstrKind, Operand};
+
+impl InstrKind {
+    pub fn operands(&self) -> Vec<Operand> {
+        match self {
+            Self::Unary { arg, .. } | Self::Cast { arg, .. } => vec![*arg],
+            Self::Binary { lhs, rhs, .. } => vec![*lhs, *rhs],
+            Self::Call { args, .. } => args.clone(),
+            Self::Copy { src } => vec![*src],
+        }
+    }
+}
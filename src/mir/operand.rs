@@ -4,20 +4,44 @@ impl Operand {
     pub fn as_num(&self) -> i32 {
         match self {
             Self::Num(value) => *value,
-            Self::Bool(..) | Self::Reg(..) => unreachable!(),
+            Self::Bool(..) | Self::Int(..) | Self::Float(..) | Self::Str(..) | Self::Reg(..) => {
+                unreachable!()
+            }
+        }
+    }
+
+    /// The value of a `num` or sized integer constant.
+    pub fn as_int(&self) -> i128 {
+        match self {
+            Self::Num(value) => (*value).into(),
+            Self::Int(value) => value.value(),
+            Self::Bool(..) | Self::Float(..) | Self::Str(..) | Self::Reg(..) => unreachable!(),
         }
     }
 
     pub fn as_bool(&self) -> bool {
         match self {
             Self::Bool(value) => *value,
-            Self::Num(..) | Self::Reg(..) => unreachable!(),
+            Self::Num(..) | Self::Int(..) | Self::Float(..) | Self::Str(..) | Self::Reg(..) => {
+                unreachable!()
+            }
+        }
+    }
+
+    pub fn as_float(&self) -> f64 {
+        match self {
+            Self::Float(value) => value.value(),
+            Self::Bool(..) | Self::Num(..) | Self::Int(..) | Self::Str(..) | Self::Reg(..) => {
+                unreachable!()
+            }
         }
     }
 
     pub fn is_const(&self) -> bool {
         match self {
-            Self::Num(..) | Self::Bool(..) => true,
+            Self::Num(..) | Self::Int(..) | Self::Bool(..) | Self::Float(..) | Self::Str(..) => {
+                true
+            }
             Self::Reg(..) => false,
         }
     }
@@ -29,6 +53,12 @@ impl From<bool> for Operand {
     }
 }
 
+impl From<f64> for Operand {
+    fn from(value: f64) -> Self {
+        Self::Float(value.into())
+    }
+}
+
 impl From<i32> for Operand {
     fn from(value: i32) -> Self {
         Self::Num(value)
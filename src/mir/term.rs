@@ -0,0 +1,52 @@
+use crate::mir::{BlockID, Operand, Term};
+
+impl Term {
+    pub fn successors(&self) -> Vec<BlockID> {
+        match self {
+            Self::Return { .. } => Vec::new(),
+            Self::Jump { target } => vec![*target],
+
+            Self::Branch {
+                then_block,
+                else_block,
+                ..
+            } => {
+                if then_block == else_block {
+                    vec![*then_block]
+                } else {
+                    vec![*then_block, *else_block]
+                }
+            }
+        }
+    }
+
+    pub fn operands(&self) -> Vec<Operand> {
+        match self {
+            Self::Jump { .. } | Self::Return { value: None } => Vec::new(),
+            Self::Branch { cond, .. } => vec![*cond],
+            Self::Return { value: Some(value) } => vec![*value],
+        }
+    }
+
+    pub fn replace_successor(&mut self, old: BlockID, new: BlockID) {
+        let replace = |id: &mut BlockID| {
+            if *id == old {
+                *id = new;
+            }
+        };
+
+        match self {
+            Self::Return { .. } => {}
+            Self::Jump { target } => replace(target),
+
+            Self::Branch {
+                then_block,
+                else_block,
+                ..
+            } => {
+                replace(then_block);
+                replace(else_block);
+            }
+        }
+    }
+}
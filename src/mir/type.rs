@@ -1,10 +1,16 @@
-use crate::mir::{MirType, Operand};
+use crate::{
+    int::Int,
+    mir::{MirType, Operand},
+};
 
 impl MirType {
     pub fn default_value(&self) -> Operand {
         match self {
             Self::Bool => Operand::Bool(false),
             Self::Num => Operand::Num(0),
+            Self::Int(ty) => Operand::Int(Int::new(0, *ty)),
+            Self::Float => 0.0.into(),
+            Self::Str => unreachable!(),
         }
     }
 }
@@ -0,0 +1,6 @@
+pub mod call_graph;
+pub mod dominators;
+pub mod interference;
+pub mod liveness;
+pub mod loops;
+pub mod reg_types;
@@ -0,0 +1,181 @@
+use itertools::Itertools as _;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::{self, Display, Formatter},
+};
+
+use crate::mir::{InstrKind, MirModule};
+
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    callees: BTreeMap<String, Vec<String>>,
+    callers: BTreeMap<String, Vec<String>>,
+    sccs: Vec<Vec<String>>,
+}
+
+impl CallGraph {
+    pub fn new(module: &MirModule) -> Self {
+        let mut outgoing = BTreeMap::new();
+        let mut incoming = BTreeMap::<_, Vec<_>>::new();
+
+        for fun in &module.funs {
+            incoming.entry(fun.name.clone()).or_default();
+        }
+
+        for fun in &module.funs {
+            let names = fun
+                .blocks
+                .iter()
+                .flat_map(|block| &block.instrs)
+                .filter_map(|instr| match &instr.kind {
+                    InstrKind::Call { name, .. } => Some(name.clone()),
+                    InstrKind::Unary { .. }
+                    | InstrKind::Binary { .. }
+                    | InstrKind::Copy { .. }
+                    | InstrKind::Cast { .. } => None,
+                })
+                .sorted()
+                .dedup()
+                .collect_vec();
+
+            for name in &names {
+                incoming
+                    .entry(name.clone())
+                    .or_default()
+                    .push(fun.name.clone());
+            }
+
+            outgoing.insert(fun.name.clone(), names);
+        }
+
+        for names in incoming.values_mut() {
+            names.sort();
+        }
+
+        let mut graph = Self {
+            callees: outgoing,
+            callers: incoming,
+            sccs: Vec::new(),
+        };
+
+        graph.sccs = Tarjan::new(&graph.callees).run();
+        graph
+    }
+
+    pub fn callees(&self, name: &str) -> &[String] {
+        self.callees.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn callers(&self, name: &str) -> &[String] {
+        self.callers.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn sccs(&self) -> &[Vec<String>] {
+        &self.sccs
+    }
+
+    pub fn is_recursive(&self, name: &str) -> bool {
+        self.callees(name).iter().any(|callee| callee == name)
+            || self
+                .sccs
+                .iter()
+                .any(|scc| scc.len() > 1 && scc.iter().any(|n| n == name))
+    }
+
+    pub fn reachable_from(&self, root: &str) -> HashSet<String> {
+        let mut reachable = HashSet::new();
+        let mut worklist = vec![root.to_owned()];
+
+        while let Some(name) = worklist.pop() {
+            if reachable.insert(name.clone()) {
+                worklist.extend(self.callees(&name).iter().cloned());
+            }
+        }
+
+        reachable
+    }
+}
+
+struct Tarjan<'a> {
+    callees: &'a BTreeMap<String, Vec<String>>,
+    index: HashMap<&'a str, usize>,
+    lowlink: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: HashSet<&'a str>,
+    sccs: Vec<Vec<String>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn new(callees: &'a BTreeMap<String, Vec<String>>) -> Self {
+        Self {
+            callees,
+            index: HashMap::new(),
+            lowlink: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            sccs: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> Vec<Vec<String>> {
+        for name in self.callees.keys() {
+            if !self.index.contains_key(name.as_str()) {
+                self.visit(name);
+            }
+        }
+
+        self.sccs
+    }
+
+    fn visit(&mut self, name: &'a str) {
+        let index = self.index.len();
+
+        self.index.insert(name, index);
+        self.lowlink.insert(name, index);
+        self.stack.push(name);
+        self.on_stack.insert(name);
+
+        for callee in self.callees.get(name).into_iter().flatten() {
+            if !self.index.contains_key(callee.as_str()) {
+                self.visit(callee);
+                self.lowlink
+                    .insert(name, self.lowlink[name].min(self.lowlink[callee.as_str()]));
+            } else if self.on_stack.contains(callee.as_str()) {
+                self.lowlink
+                    .insert(name, self.lowlink[name].min(self.index[callee.as_str()]));
+            }
+        }
+
+        if self.lowlink[name] == index {
+            let mut scc = Vec::new();
+
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                scc.push(member.to_owned());
+
+                if member == name {
+                    break;
+                }
+            }
+
+            scc.sort();
+            self.sccs.push(scc);
+        }
+    }
+}
+
+impl Display for CallGraph {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (name, callees) in &self.callees {
+            writeln!(f, "{name} -> [{}]", callees.join(", "))?;
+        }
+
+        for scc in &self.sccs {
+            if scc.len() > 1 || self.is_recursive(&scc[0]) {
+                writeln!(f, "recursive [{}]", scc.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::mir::{BlockID, MirFun};
+
+#[derive(Debug, Clone)]
+pub struct Dominators {
+    postorder: Vec<BlockID>,
+    idoms: HashMap<BlockID, BlockID>,
+    children: HashMap<BlockID, Vec<BlockID>>,
+}
+
+impl Dominators {
+    pub fn new(fun: &MirFun) -> Self {
+        let mut postorder = Vec::new();
+
+        if let Some(entry) = fun.blocks.first() {
+            visit_postorder(fun, entry.id, &mut HashSet::new(), &mut postorder);
+        }
+
+        let order = postorder
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect::<HashMap<_, _>>();
+
+        let mut idoms = HashMap::new();
+
+        if let Some(&entry) = postorder.last() {
+            idoms.insert(entry, entry);
+        }
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for &id in postorder.iter().rev().skip(1) {
+                let new_idom = fun
+                    .cfg
+                    .predecessors(id)
+                    .into_iter()
+                    .filter(|pred| idoms.contains_key(pred))
+                    .reduce(|a, b| intersect(&idoms, &order, a, b));
+
+                if let Some(new_idom) = new_idom
+                    && idoms.insert(id, new_idom) != Some(new_idom)
+                {
+                    changed = true;
+                }
+            }
+        }
+
+        let mut children = HashMap::<_, Vec<_>>::new();
+
+        for &id in postorder.iter().rev() {
+            if let Some(&idom) = idoms.get(&id)
+                && idom != id
+            {
+                children.entry(idom).or_default().push(id);
+            }
+        }
+
+        Self {
+            postorder,
+            idoms,
+            children,
+        }
+    }
+
+    pub fn idom(&self, id: BlockID) -> Option<BlockID> {
+        self.idoms.get(&id).copied().filter(|idom| *idom != id)
+    }
+
+    pub fn dominates(&self, dominator: BlockID, mut id: BlockID) -> bool {
+        if !self.idoms.contains_key(&id) {
+            return false;
+        }
+
+        loop {
+            if id == dominator {
+                return true;
+            }
+
+            match self.idom(id) {
+                Some(idom) => id = idom,
+                None => return false,
+            }
+        }
+    }
+
+    pub fn children(&self, id: BlockID) -> &[BlockID] {
+        self.children.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn is_reachable(&self, id: BlockID) -> bool {
+        self.idoms.contains_key(&id)
+    }
+
+    pub fn reverse_postorder(&self) -> impl Iterator<Item = BlockID> {
+        self.postorder.iter().rev().copied()
+    }
+}
+
+fn visit_postorder(
+    fun: &MirFun,
+    id: BlockID,
+    visited: &mut HashSet<BlockID>,
+    postorder: &mut Vec<BlockID>,
+) {
+    if !visited.insert(id) {
+        return;
+    }
+
+    for succ in fun.cfg.successors(id) {
+        visit_postorder(fun, succ, visited, postorder);
+    }
+
+    postorder.push(id);
+}
+
+fn intersect(
+    idoms: &HashMap<BlockID, BlockID>,
+    order: &HashMap<BlockID, usize>,
+    mut a: BlockID,
+    mut b: BlockID,
+) -> BlockID {
+    while a != b {
+        while order[&a] < order[&b] {
+            a = idoms[&a];
+        }
+
+        while order[&b] < order[&a] {
+            b = idoms[&b];
+        }
+    }
+
+    a
+}
@@ -0,0 +1,112 @@
+use itertools::Itertools as _;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{
+    mir::{InstrKind, MirFun, Operand, Reg, Term},
+    mir_analysis::liveness::Liveness,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct InterferenceGraph {
+    edges: BTreeMap<Reg, BTreeSet<Reg>>,
+}
+
+impl InterferenceGraph {
+    pub fn new(fun: &MirFun, liveness: &Liveness) -> Self {
+        let mut graph = Self::default();
+
+        let params = fun.params.iter().map(|(reg, _)| *reg).collect_vec();
+
+        for &param in &params {
+            graph.add_reg(param);
+            graph.add_edges(param, params.iter().copied());
+
+            if let Some(entry) = fun.blocks.first() {
+                graph.add_edges(param, liveness.live_in(entry.id).iter().copied());
+            }
+        }
+
+        for block in &fun.blocks {
+            let mut live = liveness.live_out(block.id).clone();
+
+            for operand in block.term.iter().flat_map(Term::operands) {
+                if let Operand::Reg(reg) = operand {
+                    live.insert(reg);
+                }
+            }
+
+            for instr in block.instrs.iter().rev() {
+                graph.add_reg(instr.dest);
+
+                let copied = match instr.kind {
+                    InstrKind::Copy {
+                        src: Operand::Reg(src),
+                    } => Some(src),
+                    _ => None,
+                };
+
+                let interfering = live
+                    .iter()
+                    .copied()
+                    .filter(|reg| Some(*reg) != copied)
+                    .collect_vec();
+
+                graph.add_edges(instr.dest, interfering);
+                live.remove(&instr.dest);
+
+                for operand in instr.kind.operands() {
+                    if let Operand::Reg(reg) = operand {
+                        live.insert(reg);
+                    }
+                }
+            }
+
+            let phis = block.phis.iter().map(|phi| phi.dest).collect_vec();
+
+            for &phi in &phis {
+                graph.add_reg(phi);
+                graph.add_edges(phi, live.iter().chain(&phis).copied());
+            }
+        }
+
+        graph
+    }
+
+    pub fn regs(&self) -> impl Iterator<Item = Reg> {
+        self.edges.keys().copied()
+    }
+
+    pub fn neighbors(&self, reg: Reg) -> impl Iterator<Item = Reg> {
+        self.edges.get(&reg).into_iter().flatten().copied()
+    }
+
+    pub fn interferes(&self, a: Reg, b: Reg) -> bool {
+        self.edges.get(&a).is_some_and(|edges| edges.contains(&b))
+    }
+
+    fn add_reg(&mut self, reg: Reg) {
+        self.edges.entry(reg).or_default();
+    }
+
+    fn add_edges(&mut self, reg: Reg, others: impl IntoIterator<Item = Reg>) {
+        for other in others {
+            if other != reg {
+                self.edges.entry(reg).or_default().insert(other);
+                self.edges.entry(other).or_default().insert(reg);
+            }
+        }
+    }
+}
+
+impl Display for InterferenceGraph {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (reg, edges) in &self.edges {
+            writeln!(f, "{reg}: [{}]", edges.iter().join(", "))?;
+        }
+
+        Ok(())
+    }
+}
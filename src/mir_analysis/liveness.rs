@@ -0,0 +1,128 @@
+use itertools::Itertools as _;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::{self, Display, Formatter},
+};
+
+use crate::mir::{BlockID, MirFun, Operand, Reg, Term};
+
+#[derive(Debug, Clone, Default)]
+pub struct Liveness {
+    live_in: HashMap<BlockID, BTreeSet<Reg>>,
+    live_out: HashMap<BlockID, BTreeSet<Reg>>,
+}
+
+pub struct AnnotatedFun<'a> {
+    fun: &'a MirFun,
+    liveness: &'a Liveness,
+}
+
+impl Liveness {
+    pub fn new(fun: &MirFun) -> Self {
+        let mut liveness = Self::default();
+
+        for block in &fun.blocks {
+            liveness.live_in.insert(block.id, BTreeSet::new());
+            liveness.live_out.insert(block.id, BTreeSet::new());
+        }
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for block in fun.blocks.iter().rev() {
+                let mut live = fun
+                    .cfg
+                    .successors(block.id)
+                    .into_iter()
+                    .flat_map(|succ| liveness.edge_live(fun, block.id, succ))
+                    .collect::<BTreeSet<_>>();
+
+                if liveness.live_out[&block.id] != live {
+                    liveness.live_out.insert(block.id, live.clone());
+                    changed = true;
+                }
+
+                for operand in block.term.iter().flat_map(Term::operands) {
+                    insert_reg(&mut live, operand);
+                }
+
+                for instr in block.instrs.iter().rev() {
+                    live.remove(&instr.dest);
+
+                    for operand in instr.kind.operands() {
+                        insert_reg(&mut live, operand);
+                    }
+                }
+
+                live.extend(block.phis.iter().map(|phi| phi.dest));
+
+                if liveness.live_in[&block.id] != live {
+                    liveness.live_in.insert(block.id, live);
+                    changed = true;
+                }
+            }
+        }
+
+        liveness
+    }
+
+    pub fn live_in(&self, id: BlockID) -> &BTreeSet<Reg> {
+        &self.live_in[&id]
+    }
+
+    pub fn live_out(&self, id: BlockID) -> &BTreeSet<Reg> {
+        &self.live_out[&id]
+    }
+
+    pub fn annotate<'a>(&'a self, fun: &'a MirFun) -> AnnotatedFun<'a> {
+        AnnotatedFun {
+            fun,
+            liveness: self,
+        }
+    }
+
+    fn edge_live(&self, fun: &MirFun, from: BlockID, to: BlockID) -> BTreeSet<Reg> {
+        let succ = &fun.blocks[to];
+        let mut live = self.live_in[&to].clone();
+
+        for phi in &succ.phis {
+            live.remove(&phi.dest);
+        }
+
+        for phi in &succ.phis {
+            for (_, value) in phi.srcs.iter().filter(|(src, _)| *src == from) {
+                insert_reg(&mut live, *value);
+            }
+        }
+
+        live
+    }
+}
+
+fn insert_reg(live: &mut BTreeSet<Reg>, operand: Operand) {
+    if let Operand::Reg(reg) = operand {
+        live.insert(reg);
+    }
+}
+
+impl Display for AnnotatedFun<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for block in &self.fun.blocks {
+            writeln!(
+                f,
+                "; live-in [{}]",
+                self.liveness.live_in(block.id).iter().join(", ")
+            )?;
+            write!(f, "{block}")?;
+            writeln!(
+                f,
+                "; live-out [{}]",
+                self.liveness.live_out(block.id).iter().join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use itertools::Itertools as _;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{
+    mir::{BlockID, MirFun},
+    mir_analysis::dominators::Dominators,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LoopID(pub usize);
+
+#[derive(Debug, Clone)]
+pub struct Loop {
+    pub header: BlockID,
+    pub blocks: Vec<BlockID>,
+    pub latches: Vec<BlockID>,
+    pub exits: Vec<BlockID>,
+    pub preheader: Option<BlockID>,
+    pub parent: Option<LoopID>,
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LoopForest {
+    loops: Vec<Loop>,
+    innermost: HashMap<BlockID, LoopID>,
+}
+
+impl Loop {
+    pub fn contains(&self, id: BlockID) -> bool {
+        self.blocks.binary_search(&id).is_ok()
+    }
+}
+
+impl LoopForest {
+    pub fn new(fun: &MirFun, dominators: &Dominators) -> Self {
+        let mut latches = HashMap::<_, Vec<_>>::new();
+
+        for from in dominators.reverse_postorder() {
+            for to in fun.cfg.successors(from) {
+                if dominators.dominates(to, from) {
+                    latches.entry(to).or_default().push(from);
+                }
+            }
+        }
+
+        let mut loops = latches
+            .into_iter()
+            .map(|(header, latches)| build_loop(fun, dominators, header, latches))
+            .sorted_by_key(|l| (std::cmp::Reverse(l.blocks.len()), l.header))
+            .collect_vec();
+
+        for i in 0..loops.len() {
+            let parent = (0..i)
+                .rev()
+                .find(|&j| loops[j].contains(loops[i].header))
+                .map(LoopID);
+
+            loops[i].parent = parent;
+            loops[i].depth = parent.map_or(1, |parent| loops[parent.0].depth + 1);
+        }
+
+        let mut innermost = HashMap::new();
+
+        for (i, l) in loops.iter().enumerate() {
+            for block in &l.blocks {
+                innermost.insert(*block, LoopID(i));
+            }
+        }
+
+        Self { loops, innermost }
+    }
+
+    pub fn loops(&self) -> &[Loop] {
+        &self.loops
+    }
+
+    pub fn get(&self, id: LoopID) -> &Loop {
+        &self.loops[id.0]
+    }
+
+    pub fn innermost(&self, block: BlockID) -> Option<LoopID> {
+        self.innermost.get(&block).copied()
+    }
+
+    pub fn depth(&self, block: BlockID) -> usize {
+        self.innermost(block).map_or(0, |id| self.get(id).depth)
+    }
+
+    pub fn is_header(&self, block: BlockID) -> bool {
+        self.loops.iter().any(|l| l.header == block)
+    }
+
+    pub fn is_back_edge(&self, from: BlockID, to: BlockID) -> bool {
+        self.loops
+            .iter()
+            .any(|l| l.header == to && l.latches.contains(&from))
+    }
+}
+
+fn build_loop(
+    fun: &MirFun,
+    dominators: &Dominators,
+    header: BlockID,
+    latches: Vec<BlockID>,
+) -> Loop {
+    let mut blocks = HashSet::from([header]);
+    let mut worklist = latches.clone();
+
+    while let Some(id) = worklist.pop() {
+        if blocks.insert(id) {
+            worklist.extend(
+                fun.cfg
+                    .predecessors(id)
+                    .into_iter()
+                    .filter(|pred| dominators.is_reachable(*pred)),
+            );
+        }
+    }
+
+    let exits = blocks
+        .iter()
+        .flat_map(|id| fun.cfg.successors(*id))
+        .filter(|succ| !blocks.contains(succ))
+        .unique()
+        .sorted()
+        .collect();
+
+    let outside_preds = fun
+        .cfg
+        .predecessors(header)
+        .into_iter()
+        .filter(|pred| !blocks.contains(pred))
+        .collect_vec();
+
+    let preheader = match outside_preds.as_slice() {
+        [pred] if fun.cfg.successors(*pred) == [header] => Some(*pred),
+        _ => None,
+    };
+
+    Loop {
+        header,
+        blocks: blocks.into_iter().sorted().collect(),
+        latches: latches.into_iter().sorted().collect(),
+        exits,
+        preheader,
+        parent: None,
+        depth: 0,
+    }
+}
+
+impl Display for LoopForest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for l in &self.loops {
+            writeln!(f, "{l}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Loop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "loop {} (depth {}): blocks [{}], latches [{}], exits [{}]",
+            self.header,
+            self.depth,
+            self.blocks.iter().join(", "),
+            self.latches.iter().join(", "),
+            self.exits.iter().join(", "),
+        )?;
+
+        if let Some(preheader) = self.preheader {
+            write!(f, ", preheader {preheader}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    mir::{InstrKind, MirFun, MirModule, MirType, Operand, Reg},
+    ops::{BinOp, UnOp},
+};
+
+/// The type of every register of a function, inferred from the instructions that define it.
+#[derive(Debug, Clone, Default)]
+pub struct RegTypes {
+    tys: BTreeMap<Reg, MirType>,
+}
+
+impl RegTypes {
+    pub fn new(module: &MirModule, fun: &MirFun) -> Self {
+        let return_tys = module
+            .funs
+            .iter()
+            .map(|fun| (fun.name.as_str(), fun.return_ty.clone()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut types = Self {
+            tys: fun.params.iter().cloned().collect(),
+        };
+
+        loop {
+            let before = types.tys.len();
+
+            for block in &fun.blocks {
+                for phi in &block.phis {
+                    if let Some(ty) = phi.srcs.iter().find_map(|(_, src)| types.operand(*src)) {
+                        types.tys.insert(phi.dest, ty);
+                    }
+                }
+
+                for instr in &block.instrs {
+                    let ty = match &instr.kind {
+                        InstrKind::Copy { src } => types.operand(*src),
+                        InstrKind::Cast { ty, .. } => Some(ty.clone()),
+                        InstrKind::Unary {
+                            op: UnOp::Negate | UnOp::BitNot,
+                            arg,
+                        } => types.operand(*arg),
+                        InstrKind::Unary { op: UnOp::Not, .. } => Some(MirType::Bool),
+                        InstrKind::Unary {
+                            op: UnOp::FNegate, ..
+                        } => Some(MirType::Float),
+                        InstrKind::Binary { op, .. } => Some(binary_type(*op)),
+                        InstrKind::Call { name, .. } => {
+                            return_tys.get(name.as_str()).cloned().flatten()
+                        }
+                    };
+
+                    if let Some(ty) = ty {
+                        types.tys.insert(instr.dest, ty);
+                    }
+                }
+            }
+
+            if types.tys.len() == before {
+                return types;
+            }
+        }
+    }
+
+    pub fn get(&self, reg: Reg) -> Option<&MirType> {
+        self.tys.get(&reg)
+    }
+
+    pub fn operand(&self, operand: Operand) -> Option<MirType> {
+        match operand {
+            Operand::Num(..) => Some(MirType::Num),
+            Operand::Int(value) => Some(MirType::Int(value.ty())),
+            Operand::Bool(..) => Some(MirType::Bool),
+            Operand::Str(..) => Some(MirType::Str),
+            Operand::Float(..) => Some(MirType::Float),
+            Operand::Reg(reg) => self.get(reg).cloned(),
+        }
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &MirType> {
+        self.tys.values()
+    }
+}
+
+fn binary_type(op: BinOp) -> MirType {
+    match op {
+        BinOp::Add
+        | BinOp::Sub
+        | BinOp::Mul
+        | BinOp::Div
+        | BinOp::Rem
+        | BinOp::BitAnd
+        | BinOp::BitOr
+        | BinOp::BitXor
+        | BinOp::Shl
+        | BinOp::Shr => MirType::Num,
+        BinOp::FAdd | BinOp::FSub | BinOp::FMul | BinOp::FDiv => MirType::Float,
+        BinOp::IAdd(ty)
+        | BinOp::ISub(ty)
+        | BinOp::IMul(ty)
+        | BinOp::IDiv(ty)
+        | BinOp::UDiv(ty)
+        | BinOp::IRem(ty)
+        | BinOp::URem(ty)
+        | BinOp::IAnd(ty)
+        | BinOp::IOr(ty)
+        | BinOp::IXor(ty)
+        | BinOp::IShl(ty)
+        | BinOp::IShr(ty)
+        | BinOp::UShr(ty) => MirType::Int(ty),
+        BinOp::Concat => MirType::Str,
+        _ => MirType::Bool,
+    }
+}
@@ -1,4 +1,3 @@
-use itertools::Itertools as _;
 use std::collections::HashMap;
 
 use crate::{
@@ -67,6 +66,13 @@ impl MirBuilder {
         self.active_block = id;
     }
 
+    pub fn add_param(&mut self, ty: MirType) -> Reg {
+        let reg = self.fresh_reg();
+        self.fun.params.push((reg, ty));
+
+        reg
+    }
+
     pub fn declare_var(&mut self) -> VarID {
         let var_id = VarID(self.next_var);
         self.next_var += 1;
@@ -87,12 +93,39 @@ impl MirBuilder {
         self.fun.blocks[self.active_block.0].term.is_some()
     }
 
+    /// Whether `id` can be reached from the entry block through the edges built so far.
+    pub fn is_reachable(&self, id: BlockID) -> bool {
+        let mut visited = vec![id];
+        let mut stack = vec![id];
+
+        while let Some(block) = stack.pop() {
+            if block == BlockID(0) {
+                return true;
+            }
+
+            for pred in self.fun.cfg.predecessors(block) {
+                if !visited.contains(&pred) {
+                    visited.push(pred);
+                    stack.push(pred);
+                }
+            }
+        }
+
+        false
+    }
+
     pub fn build_unary(&mut self, op: UnOp, arg: Value) -> Value {
         let arg = self.resolve_value(arg);
 
         self.build_instr(InstrKind::Unary { op, arg })
     }
 
+    pub fn build_cast(&mut self, arg: Value, ty: MirType) -> Value {
+        let arg = self.resolve_value(arg);
+
+        self.build_instr(InstrKind::Cast { arg, ty })
+    }
+
     pub fn build_binary(&mut self, op: BinOp, lhs: Value, rhs: Value) -> Value {
         let lhs = self.resolve_value(lhs);
         let rhs = self.resolve_value(rhs);
@@ -150,7 +183,7 @@ impl MirBuilder {
     fn resolve_value(&mut self, value: Value) -> Operand {
         match value {
             Value::Operand(operand) => operand,
-            Value::Variable(var_id) => self.read_var(self.active_block, var_id).unwrap().1,
+            Value::Variable(var_id) => self.read_var(self.active_block, var_id).unwrap(),
         }
     }
 
@@ -188,57 +221,51 @@ impl MirBuilder {
 
         for pred in preds {
             if let Some(src) = self.read_var(pred, var_id) {
-                self.fun.blocks[block].get_phi_mut(dest).srcs.push(src);
+                self.fun.blocks[block]
+                    .get_phi_mut(dest)
+                    .srcs
+                    .push((pred, src));
             }
         }
     }
 
-    fn read_var(&mut self, block: BlockID, var_id: VarID) -> Option<(BlockID, Operand)> {
+    fn read_var(&mut self, block: BlockID, var_id: VarID) -> Option<Operand> {
         if let Some((_, operand)) = self.var_uses[block]
             .iter()
             .filter(|&(&(v, _), _)| v == var_id)
             .max_by_key(|&(&(_, g), _)| g)
         {
-            return Some((block, *operand));
+            return Some(*operand);
         }
 
-        if self.sealed_blocks.contains(&block) {
-            let preds = self.fun.cfg.predecessors(block);
-
-            if preds.len() == 1 {
-                self.read_var(preds[0], var_id)
-            } else {
-                let srcs = preds
-                    .iter()
-                    .filter_map(|pred| self.read_var(*pred, var_id))
-                    .collect_vec();
-
-                if srcs.is_empty() {
-                    None
-                } else if srcs.iter().all(|src| src.1 == srcs[0].1) {
-                    Some((block, srcs[0].1))
-                } else {
-                    let genn = self.fresh_var_gen(var_id);
-                    let dest = self.fresh_reg();
-
-                    self.var_uses[block].insert((var_id, genn), Operand::Reg(dest));
-                    self.fun.blocks[block].phis.push(Phi { dest, srcs });
-
-                    Some((block, Operand::Reg(dest)))
-                }
-            }
-        } else {
-            let genn = self.fresh_var_gen(var_id);
-            let dest = self.fresh_reg();
+        let preds = self.fun.cfg.predecessors(block);
+        let sealed = self.sealed_blocks.contains(&block);
 
-            self.incomplete_phis[block].push((var_id, dest));
-            self.var_uses[block].insert((var_id, genn), Operand::Reg(dest));
-            self.fun.blocks[block].phis.push(Phi {
-                dest,
-                srcs: Vec::new(),
-            });
+        if sealed && preds.is_empty() {
+            return None;
+        }
 
-            Some((block, Operand::Reg(dest)))
+        if sealed && preds.len() == 1 {
+            return self.read_var(preds[0], var_id);
         }
+
+        // The phi is recorded before its operands are read, so that cycles
+        // through loop headers terminate on it.
+        let genn = self.fresh_var_gen(var_id);
+        let dest = self.fresh_reg();
+
+        self.var_uses[block].insert((var_id, genn), Operand::Reg(dest));
+        self.fun.blocks[block].phis.push(Phi {
+            dest,
+            srcs: Vec::new(),
+        });
+
+        if sealed {
+            self.add_phi_operands(block, var_id, dest);
+        } else {
+            self.incomplete_phis[block].push((var_id, dest));
+        }
+
+        Some(Operand::Reg(dest))
     }
 }
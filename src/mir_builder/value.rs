@@ -1,5 +1,7 @@
 use crate::{
-    mir::{Operand, Reg},
+    float::Float,
+    int::Int,
+    mir::{Operand, Reg, StrID},
     mir_builder::VarID,
 };
 
@@ -18,6 +20,18 @@ impl Value {
         Self::Operand(Operand::Num(value))
     }
 
+    pub fn int(value: Int) -> Self {
+        Self::Operand(Operand::Int(value))
+    }
+
+    pub fn float(value: Float) -> Self {
+        Self::Operand(Operand::Float(value))
+    }
+
+    pub fn str(value: StrID) -> Self {
+        Self::Operand(Operand::Str(value))
+    }
+
     pub fn reg(value: Reg) -> Self {
         Self::Operand(Operand::Reg(value))
     }
@@ -1,23 +1,58 @@
 use crate::{
-    mir::MirModule,
+    mir::{MirFun, MirModule},
     mir_passes::{
+        destruct_ssa::destruct_ssa, global_value_numbering::global_value_numbering,
+        inline_calls::inline_calls, loop_invariant_code_motion::loop_invariant_code_motion,
         remove_empty_blocks::remove_empty_blocks, remove_trivial_phis::remove_trivial_phis,
         remove_unreachable_blocks::remove_unreachable_blocks, sync_block_ids::sync_block_ids,
+        sync_cfg::sync_cfg,
     },
 };
 
+mod destruct_ssa;
+mod global_value_numbering;
+mod inline_calls;
+mod loop_invariant_code_motion;
+mod remove_dead_funs;
 mod remove_empty_blocks;
 mod remove_trivial_phis;
 mod remove_unreachable_blocks;
 mod rename_blocks;
 mod rename_operands;
 mod sync_block_ids;
+mod sync_cfg;
 
 pub fn run_passes(mir: &mut MirModule) {
     for fun in &mut mir.funs {
-        remove_unreachable_blocks(fun);
-        remove_empty_blocks(fun);
-        sync_block_ids(fun);
-        remove_trivial_phis(fun);
+        simplify_cfg(fun);
+    }
+
+    inline_calls(mir);
+
+    for fun in &mut mir.funs {
+        simplify_cfg(fun);
+        global_value_numbering(fun);
+        loop_invariant_code_motion(fun);
+        simplify_cfg(fun);
+    }
+}
+
+fn simplify_cfg(fun: &mut MirFun) {
+    remove_unreachable_blocks(fun);
+    remove_trivial_phis(fun);
+    remove_empty_blocks(fun);
+    sync_block_ids(fun);
+    sync_cfg(fun);
+}
+
+/// Removes functions unreachable from `main`. Only for whole programs: an object file or a wasm
+/// module exports every function, called or not.
+pub fn remove_dead_funs(mir: &mut MirModule) {
+    remove_dead_funs::remove_dead_funs(mir);
+}
+
+pub fn out_of_ssa(mir: &mut MirModule) {
+    for fun in &mut mir.funs {
+        destruct_ssa(fun);
     }
 }
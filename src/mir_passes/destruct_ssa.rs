@@ -0,0 +1,116 @@
+use itertools::Itertools as _;
+
+use crate::{
+    mir::{BasicBlock, BlockID, Instr, InstrKind, MirFun, Operand, Reg, Term},
+    mir_passes::sync_cfg::sync_cfg,
+};
+
+pub fn destruct_ssa(fun: &mut MirFun) {
+    split_phi_edges(fun);
+
+    let mut next_reg = fun.next_reg();
+
+    for id in 0..fun.blocks.len() {
+        let phis = std::mem::take(&mut fun.blocks[id].phis);
+
+        if phis.is_empty() {
+            continue;
+        }
+
+        let preds = phis
+            .iter()
+            .flat_map(|phi| phi.srcs.iter().map(|(pred, _)| *pred))
+            .unique()
+            .collect_vec();
+
+        for pred in preds {
+            let copies = phis
+                .iter()
+                .filter_map(|phi| {
+                    phi.srcs
+                        .iter()
+                        .find(|(src, _)| *src == pred)
+                        .map(|(_, value)| (phi.dest, *value))
+                })
+                .collect();
+
+            let instrs = sequentialize(copies, &mut next_reg)
+                .into_iter()
+                .map(|(dest, src)| Instr {
+                    dest,
+                    kind: InstrKind::Copy { src },
+                });
+
+            fun.blocks[pred].instrs.extend(instrs);
+        }
+    }
+}
+
+fn split_phi_edges(fun: &mut MirFun) {
+    let edges = fun
+        .blocks
+        .iter()
+        .filter(|block| fun.cfg.successors(block.id).len() > 1)
+        .flat_map(|block| {
+            fun.cfg
+                .successors(block.id)
+                .into_iter()
+                .filter(|succ| !fun.blocks[*succ].phis.is_empty())
+                .map(|succ| (block.id, succ))
+        })
+        .collect_vec();
+
+    for (pred, succ) in edges {
+        let id = BlockID(fun.blocks.len());
+        let mut block = BasicBlock::new(id);
+
+        block.term = Some(Term::Jump { target: succ });
+
+        if let Some(term) = &mut fun.blocks[pred].term {
+            term.replace_successor(succ, id);
+        }
+
+        for phi in &mut fun.blocks[succ].phis {
+            for (src, _) in &mut phi.srcs {
+                if *src == pred {
+                    *src = id;
+                }
+            }
+        }
+
+        fun.blocks.push(block);
+    }
+
+    sync_cfg(fun);
+}
+
+fn sequentialize(mut pending: Vec<(Reg, Operand)>, next_reg: &mut Reg) -> Vec<(Reg, Operand)> {
+    let mut copies = Vec::new();
+
+    pending.retain(|(dest, src)| Operand::Reg(*dest) != *src);
+
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|(dest, _)| pending.iter().all(|(_, src)| *src != Operand::Reg(*dest)));
+
+        if let Some(idx) = ready {
+            copies.push(pending.remove(idx));
+            continue;
+        }
+
+        let (dest, _) = pending[0];
+        let temp = *next_reg;
+        next_reg.0 += 1;
+
+        copies.push((temp, Operand::Reg(dest)));
+
+        for (_, src) in &mut pending {
+            if *src == Operand::Reg(dest) {
+                *src = Operand::Reg(temp);
+            }
+        }
+    }
+
+    copies
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use crate::{
+    mir::{BlockID, InstrKind, MirFun, MirType, Operand},
+    mir_analysis::dominators::Dominators,
+    mir_passes::rename_operands::{rename_instr, rename_operands},
+    ops::{BinOp, UnOp},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ValueKey {
+    Unary(UnOp, Operand),
+    Binary(BinOp, Operand, Operand),
+    Cast(Operand, MirType),
+}
+
+impl ValueKey {
+    fn new(kind: &InstrKind) -> Option<Self> {
+        match *kind {
+            InstrKind::Call { .. } | InstrKind::Copy { .. } => None,
+            InstrKind::Unary { op, arg } => Some(Self::Unary(op, arg)),
+            InstrKind::Cast { arg, ref ty } => Some(Self::Cast(arg, ty.clone())),
+
+            InstrKind::Binary { op, lhs, rhs } => Some(match op {
+                _ if op.is_commutative() => Self::Binary(op, lhs.min(rhs), lhs.max(rhs)),
+                BinOp::Greater => Self::Binary(BinOp::Lesser, rhs, lhs),
+                BinOp::GreaterEq => Self::Binary(BinOp::LesserEq, rhs, lhs),
+
+                _ => Self::Binary(op, lhs, rhs),
+            }),
+        }
+    }
+}
+
+pub fn global_value_numbering(fun: &mut MirFun) {
+    let dominators = Dominators::new(fun);
+    let mut values = HashMap::new();
+    let mut renames = HashMap::new();
+
+    if let Some(entry) = fun.blocks.first() {
+        let entry = entry.id;
+        number_block(fun, &dominators, entry, &mut values, &mut renames);
+    }
+
+    rename_operands(fun, &renames);
+}
+
+fn number_block(
+    fun: &mut MirFun,
+    dominators: &Dominators,
+    id: BlockID,
+    values: &mut HashMap<ValueKey, Operand>,
+    renames: &mut HashMap<Operand, Operand>,
+) {
+    let mut defined = Vec::new();
+
+    fun.blocks[id].instrs.retain_mut(|instr| {
+        rename_instr(&mut instr.kind, renames);
+
+        let dest = Operand::Reg(instr.dest);
+
+        if let Some(value) = instr.kind.try_fold() {
+            renames.insert(dest, value);
+            return false;
+        }
+
+        let Some(key) = ValueKey::new(&instr.kind) else {
+            return true;
+        };
+
+        if let Some(&value) = values.get(&key) {
+            renames.insert(dest, value);
+            false
+        } else {
+            values.insert(key.clone(), dest);
+            defined.push(key);
+            true
+        }
+    });
+
+    for &child in dominators.children(id) {
+        number_block(fun, dominators, child, values, renames);
+    }
+
+    for key in defined {
+        values.remove(&key);
+    }
+}
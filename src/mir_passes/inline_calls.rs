@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use crate::{
+    hir::InlineHint,
+    mir::{BasicBlock, BlockID, Instr, InstrKind, MirFun, MirModule, Operand, Phi, Reg, Term},
+    mir_analysis::call_graph::CallGraph,
+    mir_passes::{
+        rename_blocks::rename_blocks, rename_operands::rename_operands, sync_cfg::sync_cfg,
+    },
+};
+
+const INLINE_THRESHOLD: usize = 16;
+
+pub fn inline_calls(module: &mut MirModule) {
+    let graph = CallGraph::new(module);
+
+    for name in graph.sccs().iter().flatten() {
+        let Some(idx) = module.funs.iter().position(|fun| fun.name == *name) else {
+            continue;
+        };
+
+        let mut fun = module.funs[idx].clone();
+
+        while let Some((block, instr)) = find_call_site(&fun, |callee| {
+            !graph.is_recursive(callee)
+                && module
+                    .funs
+                    .iter()
+                    .find(|f| f.name == callee)
+                    .is_some_and(should_inline)
+        }) {
+            let InstrKind::Call { name, .. } = &fun.blocks[block].instrs[instr].kind else {
+                unreachable!();
+            };
+
+            let callee = module.funs.iter().find(|f| f.name == *name).unwrap();
+            inline_call(&mut fun, callee, block, instr);
+        }
+
+        module.funs[idx] = fun;
+    }
+}
+
+fn should_inline(fun: &MirFun) -> bool {
+    // The call's result becomes a phi over the callee's returns, so every exit must return a value.
+    if fun.return_ty.is_some() && !always_returns_value(fun) {
+        return false;
+    }
+
+    match fun.inline {
+        Some(InlineHint::Always) => true,
+        Some(InlineHint::Never) => false,
+
+        None => {
+            let size = fun
+                .blocks
+                .iter()
+                .map(|block| block.phis.len() + block.instrs.len())
+                .sum::<usize>();
+
+            size <= INLINE_THRESHOLD
+        }
+    }
+}
+
+fn always_returns_value(fun: &MirFun) -> bool {
+    let mut exits = fun
+        .blocks
+        .iter()
+        .filter(|block| !matches!(block.term, Some(Term::Jump { .. } | Term::Branch { .. })))
+        .peekable();
+
+    exits.peek().is_some()
+        && exits.all(|block| matches!(block.term, Some(Term::Return { value: Some(_) })))
+}
+
+fn find_call_site(fun: &MirFun, inlinable: impl Fn(&str) -> bool) -> Option<(BlockID, usize)> {
+    fun.blocks.iter().find_map(|block| {
+        block
+            .instrs
+            .iter()
+            .position(|instr| {
+                matches!(&instr.kind, InstrKind::Call { name, .. } if *name != fun.name && inlinable(name))
+            })
+            .map(|idx| (block.id, idx))
+    })
+}
+
+fn inline_call(fun: &mut MirFun, callee: &MirFun, block: BlockID, idx: usize) {
+    let cont_id = BlockID(fun.blocks.len());
+    let offset = cont_id.0 + 1;
+    let next_reg = fun.next_reg();
+
+    let caller_block = &mut fun.blocks[block];
+    let rest = caller_block.instrs.split_off(idx + 1);
+
+    let Some(Instr {
+        dest,
+        kind: InstrKind::Call { args, .. },
+    }) = caller_block.instrs.pop()
+    else {
+        unreachable!();
+    };
+
+    let term = caller_block.term.replace(Term::Jump {
+        target: BlockID(offset),
+    });
+
+    for succ in term.iter().flat_map(Term::successors) {
+        for phi in &mut fun.blocks[succ].phis {
+            for (src, _) in &mut phi.srcs {
+                if *src == block {
+                    *src = cont_id;
+                }
+            }
+        }
+    }
+
+    let mut body = clone_body(callee, offset, next_reg, &args);
+    let mut returns = Vec::new();
+
+    for block in &mut body.blocks {
+        match &block.term {
+            Some(Term::Jump { .. } | Term::Branch { .. }) => continue,
+            Some(Term::Return { value: Some(value) }) => returns.push((block.id, *value)),
+            Some(Term::Return { value: None }) | None => {}
+        }
+
+        block.term = Some(Term::Jump { target: cont_id });
+    }
+
+    let mut cont = BasicBlock::new(cont_id);
+    cont.instrs = rest;
+    cont.term = term;
+
+    if callee.return_ty.is_some() {
+        cont.phis.push(Phi {
+            dest,
+            srcs: returns,
+        });
+    }
+
+    fun.blocks.push(cont);
+    fun.blocks.extend(body.blocks);
+
+    sync_cfg(fun);
+}
+
+fn clone_body(callee: &MirFun, offset: usize, next_reg: Reg, args: &[Operand]) -> MirFun {
+    let mut body = callee.clone();
+
+    let blocks = body
+        .blocks
+        .iter()
+        .map(|block| (block.id, BlockID(offset + block.id.0)))
+        .collect();
+
+    rename_blocks(&mut body, &blocks);
+
+    let mut regs = callee
+        .params
+        .iter()
+        .zip(args)
+        .map(|((reg, _), arg)| (Operand::Reg(*reg), *arg))
+        .collect::<HashMap<_, _>>();
+
+    let fresh = |reg: &mut Reg, regs: &mut HashMap<Operand, Operand>| {
+        let new_reg = Reg(next_reg.0 + reg.0);
+
+        regs.insert(Operand::Reg(*reg), Operand::Reg(new_reg));
+        *reg = new_reg;
+    };
+
+    for block in &mut body.blocks {
+        for phi in &mut block.phis {
+            fresh(&mut phi.dest, &mut regs);
+        }
+
+        for instr in &mut block.instrs {
+            fresh(&mut instr.dest, &mut regs);
+        }
+    }
+
+    rename_operands(&mut body, &regs);
+
+    body
+}
@@ -0,0 +1,153 @@
+use itertools::Itertools as _;
+use std::collections::HashSet;
+
+use crate::{
+    mir::{BasicBlock, BlockID, InstrKind, MirFun, Operand, Phi, Reg, Term},
+    mir_analysis::{
+        dominators::Dominators,
+        loops::{Loop, LoopForest},
+    },
+    mir_passes::sync_cfg::sync_cfg,
+    ops::BinOp,
+};
+
+pub fn loop_invariant_code_motion(fun: &mut MirFun) {
+    let forest = LoopForest::new(fun, &Dominators::new(fun));
+
+    for l in forest.loops() {
+        if l.preheader.is_none() {
+            insert_preheader(fun, l);
+        }
+    }
+
+    let dominators = Dominators::new(fun);
+    let forest = LoopForest::new(fun, &dominators);
+    let order = dominators.reverse_postorder().collect_vec();
+
+    for l in forest.loops().iter().rev() {
+        hoist_invariants(fun, l, &order);
+    }
+}
+
+fn insert_preheader(fun: &mut MirFun, l: &Loop) {
+    let id = BlockID(fun.blocks.len());
+    let mut preheader = BasicBlock::new(id);
+    let mut next_reg = fun.next_reg().0;
+
+    let outside_preds = fun
+        .cfg
+        .predecessors(l.header)
+        .into_iter()
+        .filter(|pred| !l.contains(*pred))
+        .collect_vec();
+
+    for phi in &mut fun.blocks[l.header].phis {
+        let (outside, inside) = phi
+            .srcs
+            .iter()
+            .partition::<Vec<_>, _>(|(src, _)| outside_preds.contains(src));
+
+        let value = if let Some(&(_, first)) = outside.first()
+            && outside.iter().all(|(_, value)| *value == first)
+        {
+            first
+        } else {
+            let dest = Reg(next_reg);
+            next_reg += 1;
+
+            preheader.phis.push(Phi {
+                dest,
+                srcs: outside,
+            });
+
+            Operand::Reg(dest)
+        };
+
+        phi.srcs = inside;
+        phi.srcs.insert(0, (id, value));
+    }
+
+    for pred in outside_preds {
+        if let Some(term) = &mut fun.blocks[pred].term {
+            term.replace_successor(l.header, id);
+        }
+    }
+
+    preheader.term = Some(Term::Jump { target: l.header });
+    fun.blocks.push(preheader);
+
+    sync_cfg(fun);
+}
+
+fn hoist_invariants(fun: &mut MirFun, l: &Loop, order: &[BlockID]) {
+    let Some(preheader) = l.preheader else {
+        return;
+    };
+
+    let mut variant = l
+        .blocks
+        .iter()
+        .flat_map(|id| {
+            let block = &fun.blocks[*id];
+
+            block
+                .phis
+                .iter()
+                .map(|phi| phi.dest)
+                .chain(block.instrs.iter().map(|instr| instr.dest))
+        })
+        .collect::<HashSet<_>>();
+
+    let is_invariant = |variant: &HashSet<_>, operand: &Operand| match operand {
+        Operand::Reg(reg) => !variant.contains(reg),
+        Operand::Bool(..)
+        | Operand::Num(..)
+        | Operand::Int(..)
+        | Operand::Float(..)
+        | Operand::Str(..) => true,
+    };
+
+    let mut hoisted = Vec::new();
+
+    for id in order.iter().filter(|id| l.contains(**id)) {
+        fun.blocks[*id].instrs.retain(|instr| {
+            let invariant = can_speculate(&instr.kind)
+                && match &instr.kind {
+                    InstrKind::Unary { arg, .. } | InstrKind::Cast { arg, .. } => {
+                        is_invariant(&variant, arg)
+                    }
+                    InstrKind::Binary { lhs, rhs, .. } => {
+                        is_invariant(&variant, lhs) && is_invariant(&variant, rhs)
+                    }
+                    InstrKind::Call { .. } | InstrKind::Copy { .. } => false,
+                };
+
+            if invariant {
+                variant.remove(&instr.dest);
+                hoisted.push(instr.clone());
+            }
+
+            !invariant
+        });
+    }
+
+    fun.blocks[preheader].instrs.extend(hoisted);
+}
+
+fn can_speculate(kind: &InstrKind) -> bool {
+    match kind {
+        InstrKind::Call { .. } | InstrKind::Copy { .. } => false,
+        InstrKind::Unary { .. } | InstrKind::Cast { .. } => true,
+
+        InstrKind::Binary { op, rhs, .. } => match op {
+            BinOp::Div | BinOp::Rem => {
+                matches!(rhs, Operand::Num(value) if *value != 0 && *value != -1)
+            }
+            BinOp::IDiv(..) | BinOp::UDiv(..) | BinOp::IRem(..) | BinOp::URem(..) => {
+                matches!(rhs, Operand::Int(value) if value.value() != 0 && value.value() != -1)
+            }
+
+            _ => true,
+        },
+    }
+}
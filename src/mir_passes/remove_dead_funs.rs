@@ -0,0 +1,7 @@
+use crate::{mir::MirModule, mir_analysis::call_graph::CallGraph};
+
+pub fn remove_dead_funs(module: &mut MirModule) {
+    let reachable = CallGraph::new(module).reachable_from("main");
+
+    module.funs.retain(|fun| reachable.contains(&fun.name));
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     mir::{BlockID, MirFun, Term},
@@ -8,11 +8,22 @@ use crate::{
 pub fn remove_empty_blocks(fun: &mut MirFun) {
     let mut renamed_blocks = HashMap::new();
 
-    for block in &mut fun.blocks {
+    let has_phis = fun
+        .blocks
+        .iter()
+        .filter(|block| !block.phis.is_empty())
+        .map(|block| block.id)
+        .collect::<HashSet<_>>();
+
+    for block in &fun.blocks {
+        // Jumps into a block with phis are kept, since the phi sources name
+        // the predecessor they come from.
         if block.instrs.is_empty()
             && block.phis.is_empty()
             && block.id != BlockID(0)
             && let Some(Term::Jump { target }) = block.term
+            && target != block.id
+            && !has_phis.contains(&target)
         {
             let renamed_to = renamed_blocks.get(&target).copied().unwrap_or(target);
 
@@ -14,38 +14,34 @@ pub fn remove_trivial_phis(fun: &mut MirFun) {
 
         for block in &mut fun.blocks {
             block.phis.retain(|phi| {
-                let trivial = phi
+                let dest = Operand::Reg(phi.dest);
+                let srcs = phi
                     .srcs
                     .iter()
-                    .all(|src| src.1 == phi.srcs[0].1 || src.1 == Operand::Reg(phi.dest));
+                    .map(|src| renames.get(&src.1).copied().unwrap_or(src.1))
+                    .filter(|src| *src != dest)
+                    .collect::<Vec<_>>();
 
-                if trivial {
-                    let value = phi
-                        .srcs
-                        .iter()
-                        .find(|src| src.1 != Operand::Reg(phi.dest))
-                        .unwrap()
-                        .1;
+                let trivial = srcs
+                    .first()
+                    .copied()
+                    .filter(|first| srcs.iter().all(|src| src == first));
 
+                if let Some(value) = trivial {
                     changed = true;
-                    renames.insert(Operand::Reg(phi.dest), value);
+
+                    renames
+                        .values_mut()
+                        .filter(|renamed| **renamed == dest)
+                        .for_each(|renamed| *renamed = value);
+                    renames.insert(dest, value);
                 }
 
-                !trivial
+                trivial.is_none()
             });
         }
 
         if changed {
-            for block in &mut fun.blocks {
-                for phi in &mut block.phis {
-                    for (_, value) in &mut phi.srcs {
-                        if let Some(new_value) = renames.get(value) {
-                            *value = *new_value;
-                        }
-                    }
-                }
-            }
-
             rename_operands(fun, &renames);
             renames.clear();
         }
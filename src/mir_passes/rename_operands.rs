@@ -10,23 +10,16 @@ pub fn rename_operands(fun: &mut MirFun, mapping: &HashMap<Operand, Operand>) {
     };
 
     for block in &mut fun.blocks {
-        for instr in &mut block.instrs {
-            match &mut instr.kind {
-                InstrKind::Unary { arg, .. } => rename(arg),
-
-                InstrKind::Binary { lhs, rhs, .. } => {
-                    rename(lhs);
-                    rename(rhs);
-                }
-
-                InstrKind::Call { args, .. } => {
-                    for arg in args {
-                        rename(arg);
-                    }
-                }
+        for phi in &mut block.phis {
+            for (_, src) in &mut phi.srcs {
+                rename(src);
             }
         }
 
+        for instr in &mut block.instrs {
+            rename_instr(&mut instr.kind, mapping);
+        }
+
         if let Some(term) = &mut block.term {
             match term {
                 Term::Jump { .. } => {}
@@ -41,3 +34,27 @@ pub fn rename_operands(fun: &mut MirFun, mapping: &HashMap<Operand, Operand>) {
         }
     }
 }
+
+pub fn rename_instr(kind: &mut InstrKind, mapping: &HashMap<Operand, Operand>) {
+    let rename = |operand: &mut Operand| {
+        if let Some(new_operand) = mapping.get(operand) {
+            *operand = *new_operand;
+        }
+    };
+
+    match kind {
+        InstrKind::Unary { arg, .. } | InstrKind::Cast { arg, .. } => rename(arg),
+        InstrKind::Copy { src } => rename(src),
+
+        InstrKind::Binary { lhs, rhs, .. } => {
+            rename(lhs);
+            rename(rhs);
+        }
+
+        InstrKind::Call { args, .. } => {
+            for arg in args {
+                rename(arg);
+            }
+        }
+    }
+}
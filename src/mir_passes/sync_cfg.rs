@@ -0,0 +1,13 @@
+use crate::{cfg::Cfg, mir::MirFun};
+
+pub fn sync_cfg(fun: &mut MirFun) {
+    fun.cfg = Cfg::default();
+
+    for block in &fun.blocks {
+        if let Some(term) = &block.term {
+            for succ in term.successors() {
+                fun.cfg.add_edge(block.id, succ);
+            }
+        }
+    }
+}
@@ -2,18 +2,30 @@ use std::fmt::{self, Display, Formatter};
 
 use serde::Serialize;
 
-#[derive(Debug, PartialEq, Eq, Serialize, Clone, Copy)]
+use crate::int::IntType;
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Clone, Copy)]
 pub enum UnOp {
     Negate,
     Not,
+    BitNot,
+
+    FNegate,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Clone, Copy)]
 pub enum BinOp {
     Add,
     Sub,
     Mul,
     Div,
+    Rem,
+
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
 
     Eq,
     NotEq,
@@ -24,6 +36,141 @@ pub enum BinOp {
 
     And,
     Or,
+
+    FAdd,
+    FSub,
+    FMul,
+    FDiv,
+
+    FEq,
+    FNotEq,
+    FLesser,
+    FLesserEq,
+    FGreater,
+    FGreaterEq,
+
+    IAdd(IntType),
+    ISub(IntType),
+    IMul(IntType),
+    IDiv(IntType),
+    UDiv(IntType),
+    IRem(IntType),
+    URem(IntType),
+
+    IAnd(IntType),
+    IOr(IntType),
+    IXor(IntType),
+    IShl(IntType),
+    IShr(IntType),
+    UShr(IntType),
+
+    ULesser,
+    ULesserEq,
+    UGreater,
+    UGreaterEq,
+
+    Concat,
+}
+
+impl BinOp {
+    pub fn is_commutative(self) -> bool {
+        matches!(
+            self,
+            Self::Add
+                | Self::Mul
+                | Self::BitAnd
+                | Self::BitOr
+                | Self::BitXor
+                | Self::Eq
+                | Self::NotEq
+                | Self::And
+                | Self::Or
+                | Self::FAdd
+                | Self::FMul
+                | Self::FEq
+                | Self::FNotEq
+                | Self::IAdd(..)
+                | Self::IMul(..)
+                | Self::IAnd(..)
+                | Self::IOr(..)
+                | Self::IXor(..)
+        )
+    }
+
+    /// The operator as written in source, which sized and float variants share with their generic
+    /// form.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Add | Self::FAdd | Self::IAdd(..) | Self::Concat => "+",
+            Self::Sub | Self::FSub | Self::ISub(..) => "-",
+            Self::Mul | Self::FMul | Self::IMul(..) => "*",
+            Self::Div | Self::FDiv | Self::IDiv(..) | Self::UDiv(..) => "/",
+            Self::Rem | Self::IRem(..) | Self::URem(..) => "%",
+            Self::BitAnd | Self::IAnd(..) => "&",
+            Self::BitOr | Self::IOr(..) => "|",
+            Self::BitXor | Self::IXor(..) => "^",
+            Self::Shl | Self::IShl(..) => "<<",
+            Self::Shr | Self::IShr(..) | Self::UShr(..) => ">>",
+            Self::Eq | Self::FEq => "==",
+            Self::NotEq | Self::FNotEq => "!=",
+            Self::Lesser | Self::FLesser | Self::ULesser => "<",
+            Self::LesserEq | Self::FLesserEq | Self::ULesserEq => "<=",
+            Self::Greater | Self::FGreater | Self::UGreater => ">",
+            Self::GreaterEq | Self::FGreaterEq | Self::UGreaterEq => ">=",
+            Self::And => "and",
+            Self::Or => "or",
+        }
+    }
+
+    pub fn to_float(self) -> Option<Self> {
+        Some(match self {
+            Self::Add => Self::FAdd,
+            Self::Sub => Self::FSub,
+            Self::Mul => Self::FMul,
+            Self::Div => Self::FDiv,
+            Self::Eq => Self::FEq,
+            Self::NotEq => Self::FNotEq,
+            Self::Lesser => Self::FLesser,
+            Self::LesserEq => Self::FLesserEq,
+            Self::Greater => Self::FGreater,
+            Self::GreaterEq => Self::FGreaterEq,
+
+            _ => return None,
+        })
+    }
+
+    pub fn to_int(self, ty: IntType) -> Option<Self> {
+        let signed = ty.is_signed();
+
+        Some(match self {
+            Self::Add => Self::IAdd(ty),
+            Self::Sub => Self::ISub(ty),
+            Self::Mul => Self::IMul(ty),
+            Self::Div if signed => Self::IDiv(ty),
+            Self::Div => Self::UDiv(ty),
+            Self::Rem if signed => Self::IRem(ty),
+            Self::Rem => Self::URem(ty),
+            Self::BitAnd => Self::IAnd(ty),
+            Self::BitOr => Self::IOr(ty),
+            Self::BitXor => Self::IXor(ty),
+            Self::Shl => Self::IShl(ty),
+            Self::Shr if signed => Self::IShr(ty),
+            Self::Shr => Self::UShr(ty),
+            Self::Lesser if !signed => Self::ULesser,
+            Self::LesserEq if !signed => Self::ULesserEq,
+            Self::Greater if !signed => Self::UGreater,
+            Self::GreaterEq if !signed => Self::UGreaterEq,
+
+            Self::Eq
+            | Self::NotEq
+            | Self::Lesser
+            | Self::LesserEq
+            | Self::Greater
+            | Self::GreaterEq => self,
+
+            _ => return None,
+        })
+    }
 }
 
 impl Display for UnOp {
@@ -31,6 +178,8 @@ impl Display for UnOp {
         match self {
             Self::Negate => write!(f, "neg"),
             Self::Not => write!(f, "not"),
+            Self::BitNot => write!(f, "bnot"),
+            Self::FNegate => write!(f, "fneg"),
         }
     }
 }
@@ -42,6 +191,12 @@ impl Display for BinOp {
             Self::Sub => write!(f, "sub"),
             Self::Mul => write!(f, "mul"),
             Self::Div => write!(f, "div"),
+            Self::Rem => write!(f, "rem"),
+            Self::BitAnd => write!(f, "band"),
+            Self::BitOr => write!(f, "bor"),
+            Self::BitXor => write!(f, "bxor"),
+            Self::Shl => write!(f, "shl"),
+            Self::Shr => write!(f, "shr"),
             Self::Eq => write!(f, "eq"),
             Self::NotEq => write!(f, "neq"),
             Self::Lesser => write!(f, "lt"),
@@ -50,6 +205,34 @@ impl Display for BinOp {
             Self::GreaterEq => write!(f, "gte"),
             Self::And => write!(f, "and"),
             Self::Or => write!(f, "or"),
+            Self::FAdd => write!(f, "fadd"),
+            Self::FSub => write!(f, "fsub"),
+            Self::FMul => write!(f, "fmul"),
+            Self::FDiv => write!(f, "fdiv"),
+            Self::FEq => write!(f, "feq"),
+            Self::FNotEq => write!(f, "fneq"),
+            Self::FLesser => write!(f, "flt"),
+            Self::FLesserEq => write!(f, "flte"),
+            Self::FGreater => write!(f, "fgt"),
+            Self::FGreaterEq => write!(f, "fgte"),
+            Self::IAdd(ty) => write!(f, "add.{ty}"),
+            Self::ISub(ty) => write!(f, "sub.{ty}"),
+            Self::IMul(ty) => write!(f, "mul.{ty}"),
+            Self::IDiv(ty) => write!(f, "div.{ty}"),
+            Self::UDiv(ty) => write!(f, "udiv.{ty}"),
+            Self::IRem(ty) => write!(f, "rem.{ty}"),
+            Self::URem(ty) => write!(f, "urem.{ty}"),
+            Self::IAnd(ty) => write!(f, "band.{ty}"),
+            Self::IOr(ty) => write!(f, "bor.{ty}"),
+            Self::IXor(ty) => write!(f, "bxor.{ty}"),
+            Self::IShl(ty) => write!(f, "shl.{ty}"),
+            Self::IShr(ty) => write!(f, "shr.{ty}"),
+            Self::UShr(ty) => write!(f, "ushr.{ty}"),
+            Self::ULesser => write!(f, "ult"),
+            Self::ULesserEq => write!(f, "ulte"),
+            Self::UGreater => write!(f, "ugt"),
+            Self::UGreaterEq => write!(f, "ugte"),
+            Self::Concat => write!(f, "concat"),
         }
     }
 }
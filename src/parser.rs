@@ -1,10 +1,10 @@
 use std::{collections::HashMap, iter::Peekable};
 
 use crate::{
-    hir::{HirFun, HirFunType, HirModule, HirType},
+    hir::{HirFun, HirFunType, HirModule, HirType, InlineHint},
     lexer::Lexer,
     parser::error::ParseError,
-    token::TokenKind,
+    token::{Token, TokenKind},
 };
 
 pub mod error;
@@ -17,14 +17,15 @@ type Result<T> = std::result::Result<T, ParseError>;
 
 pub struct Parser<'src> {
     lexer: Peekable<Lexer<'src>>,
-    in_loop: bool,
+    /// The labels of the enclosing loops, innermost last.
+    loops: Vec<Option<&'src str>>,
 }
 
 impl<'src> Parser<'src> {
     pub fn new(lexer: Lexer<'src>) -> Self {
         Self {
             lexer: lexer.peekable(),
-            in_loop: false,
+            loops: Vec::new(),
         }
     }
 
@@ -54,7 +55,11 @@ impl<'src> Parser<'src> {
         Ok(params)
     }
 
-    fn parse_function(&mut self) -> Result<(String, HirFun)> {
+    fn parse_function(
+        &mut self,
+        inline: Option<InlineHint>,
+        doc: Option<String>,
+    ) -> Result<(String, HirFun)> {
         let name = self.expect(TokenKind::Identifier, "function name")?;
         let params = self.parse_params()?;
 
@@ -71,30 +76,49 @@ impl<'src> Parser<'src> {
             HirFun {
                 body,
                 ty: HirFunType { params, returns },
+                inline,
+                doc,
             },
         ))
     }
 
     pub fn parse(&mut self) -> Result<HirModule> {
         let mut funs = HashMap::new();
+        let mut doc = Vec::new();
 
         while let Some(token) = self.lexer.next() {
-            match token.kind {
-                TokenKind::Fun => {
-                    let (name, fun) = self.parse_function()?;
-
-                    if funs.contains_key(&name) {
-                        return Err(ParseError::DuplicateFunction {
-                            name,
-                            pos: token.pos,
-                        });
-                    }
-
-                    funs.insert(name, fun);
+            let inline = match token.kind {
+                TokenKind::DocComment => {
+                    doc.push(token);
+                    continue;
+                }
+
+                TokenKind::Fun => None,
+
+                TokenKind::Inline => {
+                    self.expect(TokenKind::Fun, "fun")?;
+                    Some(InlineHint::Always)
+                }
+
+                TokenKind::NoInline => {
+                    self.expect(TokenKind::Fun, "fun")?;
+                    Some(InlineHint::Never)
                 }
 
                 _ => return Err(ParseError::invalid_decl(token)),
+            };
+
+            let doc = doc_text(&std::mem::take(&mut doc));
+            let (name, fun) = self.parse_function(inline, doc)?;
+
+            if funs.contains_key(&name) {
+                return Err(ParseError::DuplicateFunction {
+                    name,
+                    pos: token.pos,
+                });
             }
+
+            funs.insert(name, fun);
         }
 
         if funs.contains_key("main") {
@@ -104,3 +128,12 @@ impl<'src> Parser<'src> {
         }
     }
 }
+
+fn doc_text(doc: &[Token]) -> Option<String> {
+    let lines = doc.iter().map(|token| {
+        let line = &token.slice[3..];
+        line.strip_prefix(' ').unwrap_or(line)
+    });
+
+    (!doc.is_empty()).then(|| lines.collect::<Vec<_>>().join("\n"))
+}
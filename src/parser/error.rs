@@ -1,8 +1,10 @@
 use serde::{Serialize, Serializer};
-use std::num::ParseIntError;
+use std::num::{ParseFloatError, ParseIntError};
 use thiserror::Error;
 
 use crate::{
+    hir::HirType,
+    lexer::error::LexError,
     position::Position,
     token::{Token, TokenKind},
 };
@@ -15,6 +17,15 @@ pub enum ParseError {
     #[error("Break statement outside of loop at {pos}.")]
     BreakOutsideLoop { pos: Position },
 
+    #[error("Continue statement outside of loop at {pos}.")]
+    ContinueOutsideLoop { pos: Position },
+
+    #[error("Undefined label '{name} at {pos}.")]
+    UndefinedLabel { name: String, pos: Position },
+
+    #[error("Label '{name} at {pos} shadows an enclosing loop's label.")]
+    DuplicateLabel { name: String, pos: Position },
+
     #[error("Unexpected end of file, expected {expected}.")]
     UnexpectedEOF { expected: String },
 
@@ -40,14 +51,33 @@ pub enum ParseError {
         pos: Position,
     },
 
-    #[error("Cannot parse number at {pos}: {err}.")]
+    #[error("Invalid token at {pos}: {err}.")]
+    InvalidToken { err: LexError, pos: Position },
+
+    #[error("Invalid escape sequence in string at {pos}.")]
+    InvalidEscape { pos: Position },
+
+    #[error("Invalid number suffix {suffix} at {pos}.")]
+    InvalidNumSuffix { suffix: String, pos: Position },
+
+    #[error("Cannot parse number at {pos} as {ty}: {err}.")]
     CannotParseNum {
         pos: Position,
+        ty: HirType,
 
         #[serde(serialize_with = "serialize_parse_int_err")]
         #[source]
         err: ParseIntError,
     },
+
+    #[error("Cannot parse float at {pos}: {err}.")]
+    CannotParseFloat {
+        pos: Position,
+
+        #[serde(serialize_with = "serialize_parse_float_err")]
+        #[source]
+        err: ParseFloatError,
+    },
 }
 
 impl ParseError {
@@ -58,38 +88,49 @@ impl ParseError {
     }
 
     pub fn invalid_expr(found: Token) -> Self {
-        Self::InvalidExpr {
+        Self::invalid_token(found).unwrap_or(Self::InvalidExpr {
             found: found.kind,
             pos: found.pos,
-        }
+        })
     }
 
     pub fn invalid_stmt(found: Token) -> Self {
-        Self::InvalidStmt {
+        Self::invalid_token(found).unwrap_or(Self::InvalidStmt {
             found: found.kind,
             pos: found.pos,
-        }
+        })
     }
 
     pub fn invalid_type(found: Token) -> Self {
-        Self::InvalidType {
+        Self::invalid_token(found).unwrap_or(Self::InvalidType {
             found: found.kind,
             pos: found.pos,
-        }
+        })
     }
 
     pub fn invalid_decl(found: Token) -> Self {
-        Self::InvalidDecl {
+        Self::invalid_token(found).unwrap_or(Self::InvalidDecl {
             found: found.kind,
             pos: found.pos,
-        }
+        })
     }
 
     pub fn unexpected_token<T: Into<String>>(expected: T, found: Token) -> Self {
-        Self::UnexpectedToken {
+        Self::invalid_token(found).unwrap_or_else(|| Self::UnexpectedToken {
             expected: expected.into(),
             found: found.kind,
             pos: found.pos,
+        })
+    }
+
+    /// Tokens the lexer rejected are reported with its error wherever they turn up.
+    fn invalid_token(found: Token) -> Option<Self> {
+        match found.kind {
+            TokenKind::Error(err) => Some(Self::InvalidToken {
+                err,
+                pos: found.pos,
+            }),
+            _ => None,
         }
     }
 }
@@ -97,3 +138,10 @@ impl ParseError {
 fn serialize_parse_int_err<S: Serializer>(err: &ParseIntError, s: S) -> Result<S::Ok, S::Error> {
     s.serialize_str(&err.to_string())
 }
+
+fn serialize_parse_float_err<S: Serializer>(
+    err: &ParseFloatError,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&err.to_string())
+}
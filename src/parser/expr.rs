@@ -1,5 +1,8 @@
+use std::str::Chars;
+
 use crate::{
-    hir::Expr,
+    hir::{Expr, HirType},
+    int::IntType,
     ops::{BinOp, UnOp},
     parser::{ParseError, Parser, Result},
     token::{Token, TokenKind},
@@ -22,16 +25,49 @@ impl Parser<'_> {
     }
 
     fn parse_expr_and(&mut self) -> Result<Expr> {
-        let mut lhs = self.parse_expr_eq()?;
+        let mut lhs = self.parse_expr_bit_or()?;
 
         while self.eat(TokenKind::And).is_some() {
-            let rhs = self.parse_expr_eq()?;
+            let rhs = self.parse_expr_bit_or()?;
             lhs = Expr::binary(BinOp::And, lhs, rhs);
         }
 
         Ok(lhs)
     }
 
+    fn parse_expr_bit_or(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_expr_bit_xor()?;
+
+        while self.eat(TokenKind::Pipe).is_some() {
+            let rhs = self.parse_expr_bit_xor()?;
+            lhs = Expr::binary(BinOp::BitOr, lhs, rhs);
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_expr_bit_xor(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_expr_bit_and()?;
+
+        while self.eat(TokenKind::Caret).is_some() {
+            let rhs = self.parse_expr_bit_and()?;
+            lhs = Expr::binary(BinOp::BitXor, lhs, rhs);
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_expr_bit_and(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_expr_eq()?;
+
+        while self.eat(TokenKind::Ampersand).is_some() {
+            let rhs = self.parse_expr_eq()?;
+            lhs = Expr::binary(BinOp::BitAnd, lhs, rhs);
+        }
+
+        Ok(lhs)
+    }
+
     fn parse_expr_eq(&mut self) -> Result<Expr> {
         let mut lhs = self.parse_expr_cmp()?;
 
@@ -48,7 +84,7 @@ impl Parser<'_> {
     }
 
     fn parse_expr_cmp(&mut self) -> Result<Expr> {
-        let mut lhs = self.parse_expr_term()?;
+        let mut lhs = self.parse_expr_shift()?;
 
         while let Some(op) = self.eat_map(|kind| match kind {
             TokenKind::Lesser => Some(BinOp::Lesser),
@@ -56,6 +92,21 @@ impl Parser<'_> {
             TokenKind::Greater => Some(BinOp::Greater),
             TokenKind::GreaterEqual => Some(BinOp::GreaterEq),
             _ => None,
+        }) {
+            let rhs = self.parse_expr_shift()?;
+            lhs = Expr::binary(op, lhs, rhs);
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_expr_shift(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_expr_term()?;
+
+        while let Some(op) = self.eat_map(|kind| match kind {
+            TokenKind::LesserLesser => Some(BinOp::Shl),
+            TokenKind::GreaterGreater => Some(BinOp::Shr),
+            _ => None,
         }) {
             let rhs = self.parse_expr_term()?;
             lhs = Expr::binary(op, lhs, rhs);
@@ -80,27 +131,43 @@ impl Parser<'_> {
     }
 
     fn parse_expr_factor(&mut self) -> Result<Expr> {
-        let mut lhs = self.parse_expr_unary()?;
+        let mut lhs = self.parse_expr_cast()?;
 
         while let Some(op) = self.eat_map(|kind| match kind {
             TokenKind::Star => Some(BinOp::Mul),
             TokenKind::Slash => Some(BinOp::Div),
+            TokenKind::Percent => Some(BinOp::Rem),
             _ => None,
         }) {
-            let rhs = self.parse_expr_unary()?;
+            let rhs = self.parse_expr_cast()?;
             lhs = Expr::binary(op, lhs, rhs);
         }
 
         Ok(lhs)
     }
 
+    fn parse_expr_cast(&mut self) -> Result<Expr> {
+        let mut expr = self.parse_expr_unary()?;
+
+        while self.eat(TokenKind::As).is_some() {
+            expr = Expr::cast(expr, self.parse_type()?);
+        }
+
+        Ok(expr)
+    }
+
     fn parse_expr_unary(&mut self) -> Result<Expr> {
         if let Some(op) = self.eat_map(|kind| match kind {
             TokenKind::Minus => Some(UnOp::Negate),
             TokenKind::Not => Some(UnOp::Not),
+            TokenKind::Tilde => Some(UnOp::BitNot),
             _ => None,
         }) {
-            let expr = self.parse_expr_unary()?;
+            let expr = match self.eat(TokenKind::Numeric) {
+                Some(token) => self.parse_expr_numeric(token, op == UnOp::Negate)?,
+                None => self.parse_expr_unary()?,
+            };
+
             Ok(Expr::unary(op, expr))
         } else {
             self.parse_expr_primary()
@@ -113,7 +180,9 @@ impl Parser<'_> {
         Ok(match next.kind {
             TokenKind::True => Expr::bool(true),
             TokenKind::False => Expr::bool(false),
-            TokenKind::Numeric => self.parse_expr_numeric(next)?,
+            TokenKind::Numeric => self.parse_expr_numeric(next, false)?,
+            TokenKind::Float => self.parse_expr_float(next)?,
+            TokenKind::Str => self.parse_expr_str(next)?,
             TokenKind::Identifier => self.parse_expr_identifier(next)?,
             TokenKind::LeftParen => self.parse_expr_group()?,
 
@@ -121,16 +190,99 @@ impl Parser<'_> {
         })
     }
 
-    fn parse_expr_numeric(&self, token: Token) -> Result<Expr> {
-        let value = token
-            .slice
-            .parse()
-            .map_err(|err| ParseError::CannotParseNum {
-                pos: token.pos,
-                err,
-            })?;
+    /// Parses an integer literal. When `negated`, the literal is the operand of a unary minus,
+    /// which lets the digits reach the magnitude of the type's minimum.
+    fn parse_expr_numeric(&self, token: Token, negated: bool) -> Result<Expr> {
+        let (radix, literal) = match token.slice.get(..2) {
+            Some("0x") => (16, &token.slice[2..]),
+            Some("0o") => (8, &token.slice[2..]),
+            Some("0b") => (2, &token.slice[2..]),
+            _ => (10, token.slice),
+        };
+
+        let suffix_start = literal.find(['i', 'u']).unwrap_or(literal.len());
+        let (digits, suffix) = literal.split_at(suffix_start);
+        let digits = digits.replace('_', "");
+        let sign = if negated { "-" } else { "" };
+
+        let cannot_parse = |ty, err| ParseError::CannotParseNum {
+            pos: token.pos,
+            ty,
+            err,
+        };
+
+        match suffix {
+            "" => {
+                let ty = IntType::U64;
+                let value = ty
+                    .parse(&digits, radix)
+                    .map_err(|err| cannot_parse(HirType::Int { ty }, err))?;
+
+                Ok(match i32::try_from(value) {
+                    Ok(value) => Expr::num(value),
+                    Err(_) if negated && value == 1 << 31 => Expr::num(i32::MIN),
+                    Err(_) => Expr::int(value, None),
+                })
+            }
+
+            "i32" => i32::from_str_radix(&format!("{sign}{digits}"), radix)
+                .map(|value| Expr::num(value.unsigned_abs().cast_signed()))
+                .map_err(|err| cannot_parse(HirType::Num, err)),
+
+            _ => {
+                let ty =
+                    IntType::from_name(suffix).ok_or_else(|| ParseError::InvalidNumSuffix {
+                        suffix: suffix.to_owned(),
+                        pos: token.pos,
+                    })?;
 
-        Ok(Expr::num(value))
+                let digits = if ty.is_signed() {
+                    format!("{sign}{digits}")
+                } else {
+                    digits
+                };
+
+                ty.parse(&digits, radix)
+                    .map(|value| Expr::int(value, Some(ty)))
+                    .map_err(|err| cannot_parse(HirType::Int { ty }, err))
+            }
+        }
+    }
+
+    fn parse_expr_float(&self, token: Token) -> Result<Expr> {
+        let digits = token.slice.replace('_', "");
+        let value = digits.parse().map_err(|err| ParseError::CannotParseFloat {
+            pos: token.pos,
+            err,
+        })?;
+
+        Ok(Expr::float(value))
+    }
+
+    fn parse_expr_str(&self, token: Token) -> Result<Expr> {
+        let raw = &token.slice[1..token.slice.len() - 1];
+        let mut value = String::new();
+        let mut chars = raw.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+
+            let escaped = match chars.next() {
+                Some('n') => Some('\n'),
+                Some('t') => Some('\t'),
+                Some('"') => Some('"'),
+                Some('\\') => Some('\\'),
+                Some('u') => parse_unicode_escape(&mut chars),
+                _ => None,
+            };
+
+            value.push(escaped.ok_or(ParseError::InvalidEscape { pos: token.pos })?);
+        }
+
+        Ok(Expr::str(value))
     }
 
     fn parse_expr_group(&mut self) -> Result<Expr> {
@@ -144,9 +296,7 @@ impl Parser<'_> {
         let name = token.slice.to_owned();
 
         let expr = if self.eat(TokenKind::LeftParen).is_some() {
-            let args = self.parse_args()?;
-
-            Expr::call(name, args)
+            Expr::call(name, self.parse_args()?)
         } else {
             Expr::var(name)
         };
@@ -154,3 +304,20 @@ impl Parser<'_> {
         Ok(expr)
     }
 }
+
+fn parse_unicode_escape(chars: &mut Chars) -> Option<char> {
+    if chars.next()? != '{' {
+        return None;
+    }
+
+    let digits = chars.as_str().split_once('}')?.0;
+
+    if digits.is_empty() || digits.len() > 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let value = u32::from_str_radix(digits, 16).ok()?;
+    chars.nth(digits.len());
+
+    char::from_u32(value)
+}
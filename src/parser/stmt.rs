@@ -1,11 +1,11 @@
 use crate::{
-    hir::{Expr, Stmt},
-    ops::UnOp,
+    hir::{Expr, Range, Stmt},
+    ops::{BinOp, UnOp},
     parser::{ParseError, Parser, Result},
     token::{Token, TokenKind},
 };
 
-impl Parser<'_> {
+impl<'src> Parser<'src> {
     pub(super) fn parse_stmt(&mut self) -> Result<Stmt> {
         let next = self.next("statement")?;
 
@@ -13,9 +13,12 @@ impl Parser<'_> {
             TokenKind::Return => self.parse_stmt_return(),
             TokenKind::LeftBrace => self.parse_stmt_block(),
             TokenKind::If => self.parse_stmt_if(),
-            TokenKind::Loop => self.parse_stmt_loop(),
-            TokenKind::While => self.parse_stmt_while(),
+            TokenKind::Loop => self.parse_stmt_loop(None),
+            TokenKind::While => self.parse_stmt_while(None),
+            TokenKind::For => self.parse_stmt_for(None),
+            TokenKind::Label => self.parse_stmt_labeled(next),
             TokenKind::Break => self.parse_stmt_break(next),
+            TokenKind::Continue => self.parse_stmt_continue(next),
             TokenKind::Let => self.parse_stmt_let(),
             TokenKind::Identifier => self.parse_stmt_identifier(next),
 
@@ -52,31 +55,125 @@ impl Parser<'_> {
         Ok(Stmt::If { cond, body, else_ })
     }
 
-    fn parse_stmt_loop(&mut self) -> Result<Stmt> {
-        let body = self.parse_loop_body()?;
+    fn parse_stmt_loop(&mut self, label: Option<&'src str>) -> Result<Stmt> {
+        let body = self.parse_loop_body(label)?;
 
-        Ok(Stmt::Loop { body })
+        Ok(Stmt::loop_(body, label))
     }
 
-    fn parse_stmt_while(&mut self) -> Result<Stmt> {
+    /// The condition is checked at the head of the body, so `continue` re-checks it too.
+    fn parse_stmt_while(&mut self, label: Option<&'src str>) -> Result<Stmt> {
         let expr = self.parse_condition()?;
-        let mut body = self.parse_loop_body()?;
+        let mut body = self.parse_loop_body(label)?;
 
         body.insert(
             0,
-            Stmt::if_(Expr::unary(UnOp::Not, expr), vec![Stmt::Break], None),
+            Stmt::if_(
+                Expr::unary(UnOp::Not, expr),
+                vec![Stmt::Break { label: None }],
+                None,
+            ),
         );
 
-        Ok(Stmt::Loop { body })
+        Ok(Stmt::loop_(body, label))
+    }
+
+    fn parse_stmt_for(&mut self, label: Option<&'src str>) -> Result<Stmt> {
+        let var = self.expect(TokenKind::Identifier, "variable name")?;
+        self.expect(TokenKind::In, "in")?;
+
+        let start = self.parse_expr()?;
+        let next = self.next("..")?;
+
+        let inclusive = match next.kind {
+            TokenKind::DotDot => false,
+            TokenKind::DotDotEqual => true,
+
+            _ => return Err(ParseError::unexpected_token("..", next)),
+        };
+
+        let end = self.parse_expr()?;
+        let step = self
+            .eat(TokenKind::Step)
+            .map(|_| self.parse_expr())
+            .transpose()?;
+
+        let body = self.parse_loop_body(label)?;
+
+        Ok(Stmt::For {
+            var: var.slice.to_owned(),
+            ty: None,
+            range: Range {
+                start,
+                end,
+                inclusive,
+                step,
+            },
+            body,
+            label: label.map(str::to_owned),
+        })
+    }
+
+    fn parse_stmt_labeled(&mut self, token: Token<'src>) -> Result<Stmt> {
+        let name = &token.slice[1..];
+
+        if self.loops.contains(&Some(name)) {
+            return Err(ParseError::DuplicateLabel {
+                name: name.to_owned(),
+                pos: token.pos,
+            });
+        }
+
+        self.expect(TokenKind::Colon, ":")?;
+        let next = self.next("loop")?;
+
+        match next.kind {
+            TokenKind::Loop => self.parse_stmt_loop(Some(name)),
+            TokenKind::While => self.parse_stmt_while(Some(name)),
+            TokenKind::For => self.parse_stmt_for(Some(name)),
+
+            _ => Err(ParseError::unexpected_token("loop", next)),
+        }
     }
 
     fn parse_stmt_break(&mut self, token: Token) -> Result<Stmt> {
+        let label = self.parse_loop_label()?;
+
+        if self.loops.is_empty() {
+            return Err(ParseError::BreakOutsideLoop { pos: token.pos });
+        }
+
+        Ok(Stmt::Break { label })
+    }
+
+    fn parse_stmt_continue(&mut self, token: Token) -> Result<Stmt> {
+        let label = self.parse_loop_label()?;
+
+        if self.loops.is_empty() {
+            return Err(ParseError::ContinueOutsideLoop { pos: token.pos });
+        }
+
+        Ok(Stmt::Continue { label })
+    }
+
+    /// Parses the optional label of a `break` or `continue`, which must name an enclosing loop.
+    fn parse_loop_label(&mut self) -> Result<Option<String>> {
+        let label = self.eat(TokenKind::Label);
         self.expect(TokenKind::Semicolon, ";")?;
 
-        if self.in_loop {
-            Ok(Stmt::Break)
+        let Some(label) = label else {
+            return Ok(None);
+        };
+
+        let name = &label.slice[1..];
+
+        if self.loops.contains(&Some(name)) {
+            Ok(Some(name.to_owned()))
         } else {
-            Err(ParseError::BreakOutsideLoop { pos: token.pos })
+            Err(ParseError::UndefinedLabel {
+                name: name.to_owned(),
+                pos: label.pos,
+            })
         }
     }
 
@@ -102,23 +199,33 @@ impl Parser<'_> {
     fn parse_stmt_identifier(&mut self, token: Token) -> Result<Stmt> {
         let next = self.next("assignment or call")?;
 
-        match next.kind {
-            TokenKind::Equal => {
-                let expr = self.parse_expr()?;
-                self.expect(TokenKind::Semicolon, ";")?;
-
-                Ok(Stmt::assign(token.slice.to_owned(), expr))
-            }
+        if next.kind == TokenKind::LeftParen {
+            let args = self.parse_args()?;
+            self.expect(TokenKind::Semicolon, ";")?;
 
-            TokenKind::LeftParen => {
-                let args = self.parse_args()?;
-                self.expect(TokenKind::Semicolon, ";")?;
+            return Ok(Stmt::call(token.slice.to_owned(), args));
+        }
 
-                Ok(Stmt::call(token.slice.to_owned(), args))
-            }
+        let op = match next.kind {
+            TokenKind::Equal => None,
+            TokenKind::PlusEqual => Some(BinOp::Add),
+            TokenKind::MinusEqual => Some(BinOp::Sub),
+            TokenKind::StarEqual => Some(BinOp::Mul),
+            TokenKind::SlashEqual => Some(BinOp::Div),
+            TokenKind::PercentEqual => Some(BinOp::Rem),
+            TokenKind::AmpersandEqual => Some(BinOp::BitAnd),
+            TokenKind::PipeEqual => Some(BinOp::BitOr),
+            TokenKind::CaretEqual => Some(BinOp::BitXor),
+            TokenKind::LesserLesserEqual => Some(BinOp::Shl),
+            TokenKind::GreaterGreaterEqual => Some(BinOp::Shr),
+
+            _ => return Err(ParseError::unexpected_token("assignment or call", next)),
+        };
+
+        let expr = self.parse_expr()?;
+        self.expect(TokenKind::Semicolon, ";")?;
 
-            _ => Err(ParseError::unexpected_token("assignment or call", next)),
-        }
+        Ok(Stmt::assign(token.slice.to_owned(), op, expr))
     }
 
     fn parse_condition(&mut self) -> Result<Expr> {
@@ -129,13 +236,11 @@ impl Parser<'_> {
         Ok(cond)
     }
 
-    fn parse_loop_body(&mut self) -> Result<Vec<Stmt>> {
-        let old_in_loop = self.in_loop;
-        self.in_loop = true;
-
+    fn parse_loop_body(&mut self, label: Option<&'src str>) -> Result<Vec<Stmt>> {
+        self.loops.push(label);
         let body = self.parse_body(false)?;
+        self.loops.pop();
 
-        self.in_loop = old_in_loop;
         Ok(body)
     }
 
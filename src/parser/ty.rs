@@ -1,5 +1,6 @@
 use crate::{
     hir::HirType,
+    int::IntType,
     parser::{ParseError, Parser, Result},
     token::TokenKind,
 };
@@ -10,10 +11,15 @@ impl Parser<'_> {
 
         Ok(match token.slice {
             "bool" => HirType::Bool,
-            "num" => HirType::Num,
+            "num" | "i32" => HirType::Num,
+            "float" => HirType::Float,
+            "str" => HirType::Str,
             "void" => HirType::Void,
 
-            _ => return Err(ParseError::invalid_type(token)),
+            name => match IntType::from_name(name) {
+                Some(ty) => HirType::Int { ty },
+                None => return Err(ParseError::invalid_type(token)),
+            },
         })
     }
 }
@@ -5,10 +5,12 @@ use crate::{
 
 impl<'src> Parser<'src> {
     pub(super) fn eat(&mut self, kind: TokenKind) -> Option<Token<'src>> {
+        self.skip_doc_comments();
         self.lexer.next_if(|i| i.kind == kind)
     }
 
     pub(super) fn eat_map<T>(&mut self, f: impl Fn(TokenKind) -> Option<T>) -> Option<T> {
+        self.skip_doc_comments();
         self.lexer
             .next_if(|token| f(token.kind).is_some())
             .map(|t| t.kind)
@@ -16,10 +18,12 @@ impl<'src> Parser<'src> {
     }
 
     pub(super) fn next(&mut self, expected: impl Into<String>) -> Result<Token<'src>> {
+        self.skip_doc_comments();
         self.lexer.next().ok_or_else(|| ParseError::eof(expected))
     }
 
     pub(super) fn expect(&mut self, kind: TokenKind, expected: &str) -> Result<Token<'src>> {
+        self.skip_doc_comments();
         let next = self.lexer.next().ok_or_else(|| ParseError::eof(expected))?;
 
         if next.kind == kind {
@@ -28,4 +32,13 @@ impl<'src> Parser<'src> {
             Err(ParseError::unexpected_token(expected, next))
         }
     }
+
+    /// Doc comments only document top-level functions and read as plain comments anywhere else.
+    fn skip_doc_comments(&mut self) {
+        while self
+            .lexer
+            .next_if(|token| token.kind == TokenKind::DocComment)
+            .is_some()
+        {}
+    }
 }
@@ -0,0 +1,263 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{
+    mir::{InstrKind, MirFun, Operand, Reg, Term},
+    mir_analysis::liveness::Liveness,
+    regalloc::machine::{Machine, PhysReg},
+};
+
+pub mod machine;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Reg(PhysReg),
+    Stack(usize),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Allocation {
+    pub locations: BTreeMap<Reg, Location>,
+    pub stack_slots: usize,
+    pub used_callee_saved: Vec<PhysReg>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Interval {
+    reg: Reg,
+    start: usize,
+    end: usize,
+    crosses_call: bool,
+    hint: Option<PhysReg>,
+}
+
+pub struct LinearScan<'a> {
+    machine: &'a Machine,
+    allocation: Allocation,
+    active: Vec<(Interval, PhysReg)>,
+    free: BTreeSet<PhysReg>,
+}
+
+impl<'a> LinearScan<'a> {
+    pub fn new(machine: &'a Machine) -> Self {
+        Self {
+            machine,
+            allocation: Allocation::default(),
+            active: Vec::new(),
+            free: BTreeSet::new(),
+        }
+    }
+
+    pub fn allocate(mut self, fun: &MirFun) -> Allocation {
+        self.free = self
+            .machine
+            .caller_saved
+            .iter()
+            .chain(&self.machine.callee_saved)
+            .copied()
+            .collect();
+
+        for interval in self.build_intervals(fun) {
+            self.active.retain(|(active, reg)| {
+                let expired = active.end < interval.start;
+
+                if expired {
+                    self.free.insert(*reg);
+                }
+
+                !expired
+            });
+
+            match self.pick_free(&interval) {
+                Some(reg) => self.assign(interval, reg),
+                None => self.spill_at(interval),
+            }
+        }
+
+        self.allocation.used_callee_saved.sort();
+        self.allocation
+    }
+
+    fn build_intervals(&self, fun: &MirFun) -> Vec<Interval> {
+        let liveness = Liveness::new(fun);
+        let mut ranges = BTreeMap::<Reg, (usize, usize)>::new();
+        let mut calls = Vec::new();
+        let mut pos = 0;
+
+        let mut touch = |reg: Reg, pos: usize| {
+            let range = ranges.entry(reg).or_insert((pos, pos));
+            range.0 = range.0.min(pos);
+            range.1 = range.1.max(pos);
+        };
+
+        for (reg, _) in &fun.params {
+            touch(*reg, pos);
+        }
+
+        for block in &fun.blocks {
+            pos += 1;
+
+            for reg in liveness.live_in(block.id) {
+                touch(*reg, pos);
+            }
+
+            for phi in &block.phis {
+                touch(phi.dest, pos);
+            }
+
+            for instr in &block.instrs {
+                pos += 1;
+
+                for operand in instr.kind.operands() {
+                    if let Operand::Reg(reg) = operand {
+                        touch(reg, pos);
+                    }
+                }
+
+                if let InstrKind::Call { .. } = instr.kind {
+                    calls.push(pos);
+                }
+
+                touch(instr.dest, pos);
+            }
+
+            pos += 1;
+
+            for operand in block.term.iter().flat_map(Term::operands) {
+                if let Operand::Reg(reg) = operand {
+                    touch(reg, pos);
+                }
+            }
+
+            for reg in liveness.live_out(block.id) {
+                touch(*reg, pos);
+            }
+        }
+
+        let hints = fun
+            .params
+            .iter()
+            .zip(&self.machine.args)
+            .map(|((reg, _), arg)| (*reg, *arg))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut intervals = ranges
+            .into_iter()
+            .map(|(reg, (start, end))| Interval {
+                reg,
+                start,
+                end,
+                crosses_call: calls.iter().any(|call| start < *call && *call < end),
+                hint: hints.get(&reg).copied(),
+            })
+            .collect::<Vec<_>>();
+
+        intervals.sort_by_key(|interval| (interval.start, interval.reg));
+        intervals
+    }
+
+    fn candidates(&self, interval: &Interval) -> Vec<PhysReg> {
+        if interval.crosses_call {
+            self.machine.callee_saved.clone()
+        } else {
+            interval
+                .hint
+                .into_iter()
+                .chain(self.machine.caller_saved.iter().copied())
+                .chain(self.machine.callee_saved.iter().copied())
+                .collect()
+        }
+    }
+
+    fn pick_free(&self, interval: &Interval) -> Option<PhysReg> {
+        self.candidates(interval)
+            .into_iter()
+            .find(|reg| self.free.contains(reg))
+    }
+
+    fn spill_at(&mut self, interval: Interval) {
+        let candidates = self.candidates(&interval);
+
+        let victim = self
+            .active
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, reg))| candidates.contains(reg))
+            .max_by_key(|(_, (active, _))| active.end)
+            .map(|(idx, _)| idx);
+
+        match victim {
+            Some(idx) if self.active[idx].0.end > interval.end => {
+                let (spilled, reg) = self.active.remove(idx);
+
+                self.spill(spilled.reg);
+                self.assign(interval, reg);
+            }
+
+            _ => self.spill(interval.reg),
+        }
+    }
+
+    fn assign(&mut self, interval: Interval, reg: PhysReg) {
+        self.free.remove(&reg);
+        self.active.push((interval, reg));
+        self.allocation
+            .locations
+            .insert(interval.reg, Location::Reg(reg));
+
+        if self.machine.callee_saved.contains(&reg)
+            && !self.allocation.used_callee_saved.contains(&reg)
+        {
+            self.allocation.used_callee_saved.push(reg);
+        }
+    }
+
+    fn spill(&mut self, reg: Reg) {
+        let slot = self.allocation.stack_slots;
+
+        self.allocation.stack_slots += 1;
+        self.allocation.locations.insert(reg, Location::Stack(slot));
+    }
+}
+
+impl Allocation {
+    pub fn location(&self, reg: Reg) -> Location {
+        self.locations[&reg]
+    }
+
+    pub fn display<'a>(&'a self, machine: &'a Machine) -> AllocationDisplay<'a> {
+        AllocationDisplay {
+            allocation: self,
+            machine,
+        }
+    }
+}
+
+pub struct AllocationDisplay<'a> {
+    allocation: &'a Allocation,
+    machine: &'a Machine,
+}
+
+impl Display for AllocationDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (reg, location) in &self.allocation.locations {
+            match location {
+                Location::Reg(phys) => writeln!(f, "{reg} -> {}", self.machine.name(*phys))?,
+                Location::Stack(slot) => writeln!(f, "{reg} -> [stack {slot}]")?,
+            }
+        }
+
+        writeln!(f, "stack slots: {}", self.allocation.stack_slots)?;
+
+        let saved = self
+            .allocation
+            .used_callee_saved
+            .iter()
+            .map(|reg| self.machine.name(*reg))
+            .collect::<Vec<_>>();
+
+        writeln!(f, "callee-saved: [{}]", saved.join(", "))
+    }
+}
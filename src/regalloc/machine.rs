@@ -0,0 +1,20 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PhysReg(pub usize);
+
+#[derive(Debug, Clone)]
+pub struct Machine {
+    pub regs: Vec<String>,
+    pub caller_saved: Vec<PhysReg>,
+    pub callee_saved: Vec<PhysReg>,
+    pub args: Vec<PhysReg>,
+}
+
+impl Machine {
+    pub fn name(&self, reg: PhysReg) -> &str {
+        &self.regs[reg.0]
+    }
+
+    pub fn reg(&self, name: &str) -> Option<PhysReg> {
+        self.regs.iter().position(|reg| reg == name).map(PhysReg)
+    }
+}
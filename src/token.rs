@@ -1,7 +1,7 @@
 use serde::Serialize;
 use std::fmt::{self, Display, Formatter};
 
-use crate::position::Position;
+use crate::{lexer::error::LexError, position::Position};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize)]
 pub enum TokenKind {
@@ -17,6 +17,11 @@ pub enum TokenKind {
     Minus,
     Star,
     Slash,
+    Percent,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
 
     // One or two characters.
     Equal,
@@ -27,10 +32,32 @@ pub enum TokenKind {
     GreaterEqual,
     Not,
     NotEqual,
+    LesserLesser,
+    GreaterGreater,
+
+    // Ranges.
+    DotDot,
+    DotDotEqual,
+
+    // Compound assignments.
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    PercentEqual,
+    AmpersandEqual,
+    PipeEqual,
+    CaretEqual,
+    LesserLesserEqual,
+    GreaterGreaterEqual,
 
     // Variable length.
     Identifier,
     Numeric,
+    Float,
+    Str,
+    DocComment,
+    Label,
 
     // Keywords.
     True,
@@ -44,11 +71,19 @@ pub enum TokenKind {
     Loop,
     While,
     Break,
+    Continue,
+    For,
+    In,
+    Step,
     Let,
+    Inline,
+    NoInline,
+    As,
 
     // Error.
     #[default]
     Unknown,
+    Error(LexError),
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Serialize, Default)]
@@ -84,6 +119,11 @@ impl Display for TokenKind {
             Self::Minus => write!(f, "-"),
             Self::Star => write!(f, "*"),
             Self::Slash => write!(f, "/"),
+            Self::Percent => write!(f, "%"),
+            Self::Ampersand => write!(f, "&"),
+            Self::Pipe => write!(f, "|"),
+            Self::Caret => write!(f, "^"),
+            Self::Tilde => write!(f, "~"),
             Self::Equal => write!(f, "="),
             Self::EqualEqual => write!(f, "=="),
             Self::Lesser => write!(f, "<"),
@@ -92,8 +132,24 @@ impl Display for TokenKind {
             Self::GreaterEqual => write!(f, ">="),
             Self::Not => write!(f, "!"),
             Self::NotEqual => write!(f, "!="),
+            Self::LesserLesser => write!(f, "<<"),
+            Self::GreaterGreater => write!(f, ">>"),
+            Self::DotDot => write!(f, ".."),
+            Self::DotDotEqual => write!(f, "..="),
+            Self::PlusEqual => write!(f, "+="),
+            Self::MinusEqual => write!(f, "-="),
+            Self::StarEqual => write!(f, "*="),
+            Self::SlashEqual => write!(f, "/="),
+            Self::PercentEqual => write!(f, "%="),
+            Self::AmpersandEqual => write!(f, "&="),
+            Self::PipeEqual => write!(f, "|="),
+            Self::CaretEqual => write!(f, "^="),
+            Self::LesserLesserEqual => write!(f, "<<="),
+            Self::GreaterGreaterEqual => write!(f, ">>="),
             Self::Identifier => write!(f, "identifier"),
             Self::Numeric => write!(f, "numeric"),
+            Self::Float => write!(f, "float"),
+            Self::Str => write!(f, "string"),
             Self::True => write!(f, "true"),
             Self::False => write!(f, "false"),
             Self::And => write!(f, "and"),
@@ -105,8 +161,18 @@ impl Display for TokenKind {
             Self::Loop => write!(f, "loop"),
             Self::While => write!(f, "while"),
             Self::Break => write!(f, "break"),
+            Self::Continue => write!(f, "continue"),
+            Self::For => write!(f, "for"),
+            Self::In => write!(f, "in"),
+            Self::Step => write!(f, "step"),
             Self::Let => write!(f, "let"),
+            Self::Inline => write!(f, "inline"),
+            Self::NoInline => write!(f, "noinline"),
+            Self::As => write!(f, "as"),
+            Self::DocComment => write!(f, "doc comment"),
+            Self::Label => write!(f, "label"),
             Self::Unknown => write!(f, "unknown"),
+            Self::Error(err) => write!(f, "{err}"),
         }
     }
 }
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use crate::{
-    hir::{Expr, HirFun, HirFunType, HirModule, HirType, Stmt},
+    hir::{Expr, HirFun, HirFunType, HirModule, HirType, Range, Stmt},
     ops::{BinOp, UnOp},
     scope::Scope,
     type_resolver::error::TypeError,
@@ -53,12 +53,19 @@ impl TypeResolver {
 
     fn resolve_stmt(&mut self, stmt: &mut Stmt) -> Result<()> {
         match stmt {
-            Stmt::Break => Ok(()),
-            Stmt::Block { body } | Stmt::Loop { body } => self.resolve_block(body),
-            Stmt::Return { expr } => self.resolve_stmt_return(expr.as_ref()),
-            Stmt::Let { name, ty, expr } => self.resolve_stmt_let(name, ty, expr.as_ref()),
+            Stmt::Break { .. } | Stmt::Continue { .. } => Ok(()),
+            Stmt::Block { body } | Stmt::Loop { body, .. } => self.resolve_block(body),
+            Stmt::Return { expr } => self.resolve_stmt_return(expr.as_mut()),
+            Stmt::Let { name, ty, expr } => self.resolve_stmt_let(name, ty, expr.as_mut()),
             Stmt::If { cond, body, else_ } => self.resolve_stmt_if(cond, body, else_),
-            Stmt::Assign { name, expr } => self.resolve_stmt_assign(name, expr),
+            Stmt::For {
+                var,
+                ty,
+                range,
+                body,
+                ..
+            } => self.resolve_stmt_for(var, ty, range, body),
+            Stmt::Assign { name, op, expr } => self.resolve_stmt_assign(name, op.as_mut(), expr),
             Stmt::Call { name, args } => self.resolve_expr_call(name, args).map(|_| ()),
         }
     }
@@ -75,17 +82,22 @@ impl TypeResolver {
         Ok(())
     }
 
-    fn resolve_expr(&self, expr: &Expr) -> Result<HirType> {
+    /// Resolves the type of `expr`, giving untyped integer literals the `expected` type if it is
+    /// a sized integer.
+    fn resolve_expr(&self, expr: &mut Expr, expected: Option<&HirType>) -> Result<HirType> {
         match expr {
             Expr::Bool { .. } => Ok(HirType::Bool),
-            Expr::Num { .. } => Ok(HirType::Num),
-            Expr::Unary { op, expr } => self.resolve_expr_unary(*op, expr),
-            Expr::Binary { op, lhs, rhs } => self.resolve_expr_binary(*op, lhs, rhs),
+            Expr::Num { .. } | Expr::Int { .. } => Self::resolve_expr_int(expr, expected, false),
+            Expr::Float { .. } => Ok(HirType::Float),
+            Expr::Str { .. } => Ok(HirType::Str),
+            Expr::Unary { op, expr } => self.resolve_expr_unary(op, expr, expected),
+            Expr::Binary { op, lhs, rhs } => self.resolve_expr_binary(op, lhs, rhs, expected),
+            Expr::Cast { expr, ty } => self.resolve_expr_cast(expr, ty),
             Expr::Call { name, args } => self.resolve_expr_call(name, args),
 
             Expr::Var { name } => {
                 self.scope
-                    .get(name)
+                    .get(name.as_str())
                     .cloned()
                     .ok_or_else(|| TypeError::UndefinedVar {
                         name: name.to_owned(),
@@ -94,39 +106,125 @@ impl TypeResolver {
         }
     }
 
-    fn resolve_expr_unary(&self, op: UnOp, expr: &Expr) -> Result<HirType> {
-        let ty = self.resolve_expr(expr)?;
+    /// Resolves an integer literal. A `negated` literal may reach the magnitude of the minimum of
+    /// a signed type, which wraps to the minimum itself.
+    fn resolve_expr_int(
+        expr: &mut Expr,
+        expected: Option<&HirType>,
+        negated: bool,
+    ) -> Result<HirType> {
+        let (value, ty) = match *expr {
+            Expr::Num { value } => (value.unsigned_abs().into(), None),
+            Expr::Int { value, ty } => (value, ty),
+            _ => unreachable!(),
+        };
+
+        match (ty, expected) {
+            (Some(ty), _) => Ok(HirType::Int { ty }),
+
+            (None, Some(HirType::Int { ty }))
+                if ty.contains(value) || negated && i128::from(value) == -ty.min_value() =>
+            {
+                *expr = Expr::int(value, Some(*ty));
+                Ok(HirType::Int { ty: *ty })
+            }
+
+            (None, Some(ty @ HirType::Int { .. })) => {
+                Err(TypeError::cannot_parse_num(value, negated, ty.clone()))
+            }
+
+            (None, _) if matches!(expr, Expr::Num { .. }) => Ok(HirType::Num),
+
+            (None, _) => Err(TypeError::cannot_parse_num(value, negated, HirType::Num)),
+        }
+    }
+
+    fn resolve_expr_unary(
+        &self,
+        op: &mut UnOp,
+        expr: &mut Expr,
+        expected: Option<&HirType>,
+    ) -> Result<HirType> {
+        let expected = if matches!(op, UnOp::Negate | UnOp::BitNot) {
+            expected
+        } else {
+            None
+        };
+        let ty = match expr {
+            Expr::Num { .. } | Expr::Int { .. } if *op == UnOp::Negate => {
+                Self::resolve_expr_int(expr, expected, true)?
+            }
+            _ => self.resolve_expr(expr, expected)?,
+        };
 
-        match (op, &ty) {
-            (UnOp::Negate, HirType::Num) => Ok(HirType::Num),
+        match (*op, &ty) {
+            (UnOp::Negate | UnOp::BitNot, HirType::Num) => Ok(HirType::Num),
+            (UnOp::Negate, HirType::Int { ty: int_ty }) if int_ty.is_signed() => Ok(ty),
+            (UnOp::BitNot, HirType::Int { .. }) => Ok(ty),
             (UnOp::Not, HirType::Bool) => Ok(HirType::Bool),
 
-            _ => Err(TypeError::InvalidUnaryOp { op, ty }),
+            (UnOp::Negate, HirType::Float) => {
+                *op = UnOp::FNegate;
+                Ok(HirType::Float)
+            }
+
+            _ => Err(TypeError::InvalidUnaryOp { op: *op, ty }),
         }
     }
 
-    fn resolve_expr_binary(&self, op: BinOp, lhs: &Expr, rhs: &Expr) -> Result<HirType> {
-        let lhs = self.resolve_expr(lhs)?;
-        let rhs = self.resolve_expr(rhs)?;
+    fn resolve_expr_binary(
+        &self,
+        op: &mut BinOp,
+        lhs: &mut Expr,
+        rhs: &mut Expr,
+        expected: Option<&HirType>,
+    ) -> Result<HirType> {
+        let expected = expected.filter(|_| is_numeric_op(*op));
+        let (lhs, rhs) = self.resolve_operands(lhs, rhs, expected)?;
+
+        binary_type(op, &lhs, &rhs).ok_or(TypeError::InvalidBinaryOp { op: *op, lhs, rhs })
+    }
 
-        if lhs != rhs {
-            return Err(TypeError::InvalidBinaryOp { op, lhs, rhs });
+    /// Resolves two operands that must share a type, letting an untyped literal on either side
+    /// take the other's.
+    fn resolve_operands(
+        &self,
+        lhs: &mut Expr,
+        rhs: &mut Expr,
+        expected: Option<&HirType>,
+    ) -> Result<(HirType, HirType)> {
+        if is_untyped_literal(lhs) && !is_untyped_literal(rhs) {
+            let rhs = self.resolve_expr(rhs, expected)?;
+            Ok((self.resolve_expr(lhs, Some(&rhs))?, rhs))
+        } else {
+            let lhs = self.resolve_expr(lhs, expected)?;
+            Ok((lhs.clone(), self.resolve_expr(rhs, Some(&lhs))?))
         }
+    }
 
-        match (op, &lhs) {
-            (BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div, HirType::Num) => Ok(HirType::Num),
+    fn resolve_expr_cast(&self, expr: &mut Expr, ty: &HirType) -> Result<HirType> {
+        let from = self.resolve_expr(expr, None)?;
 
-            (BinOp::Eq | BinOp::NotEq, _)
-            | (BinOp::And | BinOp::Or, HirType::Bool)
-            | (BinOp::Lesser | BinOp::LesserEq | BinOp::Greater | BinOp::GreaterEq, HirType::Num) => {
-                Ok(HirType::Bool)
-            }
+        let is_valid = from == *ty
+            || matches!(
+                (&from, ty),
+                (
+                    HirType::Bool | HirType::Num | HirType::Int { .. } | HirType::Float,
+                    HirType::Num | HirType::Int { .. },
+                ) | (HirType::Num | HirType::Int { .. }, HirType::Float)
+            );
 
-            _ => Err(TypeError::InvalidBinaryOp { op, lhs, rhs }),
+        if is_valid {
+            Ok(ty.clone())
+        } else {
+            Err(TypeError::InvalidCast {
+                from,
+                to: ty.clone(),
+            })
         }
     }
 
-    fn resolve_expr_call(&self, name: &str, args: &[Expr]) -> Result<HirType> {
+    fn resolve_expr_call(&self, name: &str, args: &mut [Expr]) -> Result<HirType> {
         let ty = self
             .functions
             .get(name)
@@ -143,8 +241,8 @@ impl TypeResolver {
             });
         }
 
-        for (arg, param) in args.iter().zip(ty.params.iter()) {
-            let arg_ty = self.resolve_expr(arg)?;
+        for (arg, param) in args.iter_mut().zip(ty.params.iter()) {
+            let arg_ty = self.resolve_expr(arg, Some(&param.1))?;
 
             if arg_ty != param.1 {
                 return Err(TypeError::TypeMismatch {
@@ -161,14 +259,16 @@ impl TypeResolver {
         &mut self,
         name: &str,
         ty: &mut Option<HirType>,
-        expr: Option<&Expr>,
+        expr: Option<&mut Expr>,
     ) -> Result<()> {
-        let expr_ty = expr.as_ref().map(|e| self.resolve_expr(e)).transpose()?;
+        let expr_ty = expr
+            .map(|e| self.resolve_expr(e, ty.as_ref()))
+            .transpose()?;
 
-        let resolved_ty = match (ty, expr_ty) {
+        let resolved_ty = match (ty.as_ref(), expr_ty) {
             (Some(annotated_ty), None) => annotated_ty.clone(),
 
-            (ty @ None, Some(inferred_ty)) => {
+            (None, Some(inferred_ty)) => {
                 *ty = Some(inferred_ty.clone());
                 inferred_ty
             }
@@ -197,11 +297,10 @@ impl TypeResolver {
         Ok(())
     }
 
-    fn resolve_stmt_return(&self, expr: Option<&Expr>) -> Result<()> {
+    fn resolve_stmt_return(&self, expr: Option<&mut Expr>) -> Result<()> {
         let fun_ty = self.expected_return_type.as_ref().unwrap();
         let expr_ty = expr
-            .as_ref()
-            .map(|e| self.resolve_expr(e))
+            .map(|e| self.resolve_expr(e, Some(fun_ty)))
             .transpose()?
             .unwrap_or(HirType::Void);
 
@@ -217,11 +316,11 @@ impl TypeResolver {
 
     fn resolve_stmt_if(
         &mut self,
-        cond: &Expr,
+        cond: &mut Expr,
         body: &mut Vec<Stmt>,
         else_: &mut Option<Vec<Stmt>>,
     ) -> Result<()> {
-        let cond_ty = self.resolve_expr(cond)?;
+        let cond_ty = self.resolve_expr(cond, None)?;
 
         if cond_ty != HirType::Bool {
             return Err(TypeError::NonBooleanCondition { found: cond_ty });
@@ -235,14 +334,79 @@ impl TypeResolver {
         Ok(())
     }
 
-    fn resolve_stmt_assign(&self, name: &str, expr: &Expr) -> Result<()> {
-        let expr_ty = self.resolve_expr(expr)?;
-        let var_ty = self
-            .scope
-            .get(name)
-            .ok_or_else(|| TypeError::UndefinedVar {
-                name: name.to_owned(),
-            })?;
+    fn resolve_stmt_for(
+        &mut self,
+        var: &str,
+        ty: &mut Option<HirType>,
+        range: &mut Range,
+        body: &mut Vec<Stmt>,
+    ) -> Result<()> {
+        let (start_ty, end_ty) = self.resolve_operands(&mut range.start, &mut range.end, None)?;
+
+        if start_ty != end_ty {
+            return Err(TypeError::TypeMismatch {
+                expected: start_ty,
+                found: end_ty,
+            });
+        }
+
+        if !matches!(start_ty, HirType::Num | HirType::Int { .. }) {
+            return Err(TypeError::InvalidRange { ty: start_ty });
+        }
+
+        if let Some(step) = &mut range.step {
+            let step_ty = self.resolve_expr(step, Some(&start_ty))?;
+
+            if step_ty != start_ty {
+                return Err(TypeError::TypeMismatch {
+                    expected: start_ty,
+                    found: step_ty,
+                });
+            }
+
+            if let Some(value) = literal_value(step)
+                && value <= 0
+            {
+                return Err(TypeError::InvalidStep { value });
+            }
+        }
+
+        self.scope.create();
+        self.scope.set(var, &start_ty);
+        *ty = Some(start_ty);
+
+        self.resolve_block(body)?;
+        self.scope.pop();
+
+        Ok(())
+    }
+
+    /// Checks a compound assignment like the binary operation between the variable and `expr`.
+    fn resolve_stmt_assign(
+        &self,
+        name: &str,
+        op: Option<&mut BinOp>,
+        expr: &mut Expr,
+    ) -> Result<()> {
+        let var_ty = self.scope.get(name);
+        let expr_ty = match op {
+            Some(op) => {
+                let mut var = Expr::var(name.to_owned());
+                let (lhs, rhs) = self.resolve_operands(&mut var, expr, var_ty)?;
+
+                binary_type(op, &lhs, &rhs).ok_or_else(|| TypeError::InvalidCompoundAssign {
+                    op: op.symbol(),
+                    lhs,
+                    rhs,
+                })?
+            }
+
+            None => self.resolve_expr(expr, var_ty)?,
+        };
+
+        let var_ty = var_ty.ok_or_else(|| TypeError::UndefinedVar {
+            name: name.to_owned(),
+        })?;
 
         if expr_ty == *var_ty {
             Ok(())
@@ -254,3 +418,102 @@ impl TypeResolver {
         }
     }
 }
+
+/// Whether `op` is arithmetic or bitwise, so its operands take the type expected of its result.
+fn is_numeric_op(op: BinOp) -> bool {
+    matches!(
+        op,
+        BinOp::Add
+            | BinOp::Sub
+            | BinOp::Mul
+            | BinOp::Div
+            | BinOp::Rem
+            | BinOp::BitAnd
+            | BinOp::BitOr
+            | BinOp::BitXor
+            | BinOp::Shl
+            | BinOp::Shr
+    )
+}
+
+/// The result type of `op` over operands of types `lhs` and `rhs`, specializing `op` to the
+/// operand type. `None` if the operation is invalid for these types.
+fn binary_type(op: &mut BinOp, lhs: &HirType, rhs: &HirType) -> Option<HirType> {
+    if lhs != rhs {
+        return None;
+    }
+
+    match (*op, lhs) {
+        (_, HirType::Num) if is_numeric_op(*op) => Some(HirType::Num),
+
+        (BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div, HirType::Float) => {
+            *op = op.to_float().unwrap();
+            Some(HirType::Float)
+        }
+
+        (
+            BinOp::Eq
+            | BinOp::NotEq
+            | BinOp::Lesser
+            | BinOp::LesserEq
+            | BinOp::Greater
+            | BinOp::GreaterEq,
+            HirType::Float,
+        ) => {
+            *op = op.to_float().unwrap();
+            Some(HirType::Bool)
+        }
+
+        (_, HirType::Int { ty }) if is_numeric_op(*op) => {
+            *op = op.to_int(*ty).unwrap();
+            Some(lhs.clone())
+        }
+
+        (
+            BinOp::Lesser | BinOp::LesserEq | BinOp::Greater | BinOp::GreaterEq,
+            HirType::Int { ty },
+        ) => {
+            *op = op.to_int(*ty).unwrap();
+            Some(HirType::Bool)
+        }
+
+        (BinOp::Add, HirType::Str) => {
+            *op = BinOp::Concat;
+            Some(HirType::Str)
+        }
+
+        (BinOp::Eq | BinOp::NotEq, _)
+        | (BinOp::And | BinOp::Or, HirType::Bool)
+        | (BinOp::Lesser | BinOp::LesserEq | BinOp::Greater | BinOp::GreaterEq, HirType::Num) => {
+            Some(HirType::Bool)
+        }
+
+        _ => None,
+    }
+}
+
+/// The value of a possibly negated number literal.
+fn literal_value(expr: &Expr) -> Option<i128> {
+    match expr {
+        Expr::Num { value } => Some((*value).into()),
+        Expr::Int { value, .. } => Some((*value).into()),
+        Expr::Unary {
+            op: UnOp::Negate,
+            expr,
+        } => literal_value(expr).map(|value| -value),
+
+        _ => None,
+    }
+}
+
+fn is_untyped_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Num { .. } | Expr::Int { ty: None, .. } => true,
+        Expr::Unary {
+            op: UnOp::Negate,
+            expr,
+        } => is_untyped_literal(expr),
+
+        _ => false,
+    }
+}
@@ -23,6 +23,9 @@ pub enum TypeError {
     #[error("Type mismatch: expected {expected}, found {found}.")]
     TypeMismatch { expected: HirType, found: HirType },
 
+    #[error("Cannot parse number {value} as {ty}: out of range.")]
+    CannotParseNum { value: i128, ty: HirType },
+
     #[error("Invalid unary operation {op} for type {ty}.")]
     InvalidUnaryOp { op: UnOp, ty: HirType },
 
@@ -33,6 +36,22 @@ pub enum TypeError {
         rhs: HirType,
     },
 
+    #[error("Invalid compound assignment {op}= for types {lhs} and {rhs}.")]
+    InvalidCompoundAssign {
+        op: &'static str,
+        lhs: HirType,
+        rhs: HirType,
+    },
+
+    #[error("Invalid step {value}: expected a positive step.")]
+    InvalidStep { value: i128 },
+
+    #[error("Invalid range over type {ty}: expected num or a sized integer.")]
+    InvalidRange { ty: HirType },
+
+    #[error("Invalid cast from {from} to {to}.")]
+    InvalidCast { from: HirType, to: HirType },
+
     #[error("Invalid number of args for function {name}: expected {expected}, found {found}.")]
     InvalidCallArgs {
         name: String,
@@ -40,3 +59,14 @@ pub enum TypeError {
         found: usize,
     },
 }
+
+impl TypeError {
+    pub fn cannot_parse_num(value: u64, negated: bool, ty: HirType) -> Self {
+        let value = i128::from(value);
+
+        Self::CannotParseNum {
+            value: if negated { -value } else { value },
+            ty,
+        }
+    }
+}
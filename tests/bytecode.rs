@@ -0,0 +1,140 @@
+#![cfg(test)]
+
+mod common;
+
+use common::{compile, test_programs};
+use lang::{
+    bytecode::{
+        self, Program,
+        error::{BytecodeError, VmError},
+        vm::Vm,
+    },
+    mir::MirModule,
+};
+use std::path::Path;
+
+#[test]
+fn test_bytecode() {
+    test_programs!("bytecode", "programs/*.lang", compile_and_run);
+}
+
+#[test]
+fn test_bytecode_wide() {
+    test_programs!("bytecode", "programs/wide/*.lang", compile_and_run);
+}
+
+#[test]
+fn test_bytecode_floats() {
+    test_programs!("bytecode", "programs/floats/*.lang", compile_and_run);
+}
+
+#[test]
+fn test_bytecode_strings() {
+    test_programs!("bytecode", "programs/strings/*.lang", compile_and_run);
+}
+
+fn compile_and_run(_: &Path, mir: &MirModule) -> (i32, String) {
+    let program = bytecode::compile(mir).unwrap();
+    let loaded = Program::from_bytes(&program.to_bytes()).unwrap();
+
+    assert_eq!(loaded, program);
+
+    let result = Vm::new(&loaded).call("main", &[]).unwrap();
+    (
+        i32::try_from(result.unwrap_or(0) & 0xff).unwrap(),
+        program.to_string(),
+    )
+}
+
+#[test]
+fn test_bytecode_errors() {
+    let source = "fun main(): num { return div(1, 0); } noinline fun div(a: num, b: num): num { return a / b; }";
+    let program = bytecode::compile(&compile(source)).unwrap();
+    let bytes = program.to_bytes();
+
+    assert_eq!(
+        Vm::new(&program).call("main", &[]),
+        Err(VmError::DivisionByZero { name: "div".into() })
+    );
+
+    assert_eq!(
+        Vm::new(&program).call("div", &[1]),
+        Err(VmError::InvalidArgs {
+            name: "div".into(),
+            expected: 2,
+            found: 1
+        })
+    );
+
+    assert_eq!(Vm::new(&program).call("div", &[7, 2]), Ok(Some(3)));
+
+    assert_eq!(
+        Program::from_bytes(b"LANGX\x01\x00"),
+        Err(BytecodeError::InvalidMagic)
+    );
+
+    let mut versioned = bytes.clone();
+    versioned[5] = 2;
+
+    assert_eq!(
+        Program::from_bytes(&versioned),
+        Err(BytecodeError::UnsupportedVersion {
+            expected: 5,
+            found: 2
+        })
+    );
+
+    assert_eq!(
+        Program::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(BytecodeError::UnexpectedEnd)
+    );
+}
+
+#[test]
+fn test_bytecode_stack_overflow() {
+    let source = "fun main(): num { return forever(1); } fun forever(n: num): num { return forever(n + 1) + 1; }";
+    let program = bytecode::compile(&compile(source)).unwrap();
+
+    assert_eq!(
+        Vm::new(&program).call("main", &[]),
+        Err(VmError::StackOverflow {
+            name: "forever".into()
+        })
+    );
+}
+
+#[test]
+fn test_bytecode_string_handles() {
+    let source =
+        "fun main() { greet(\"hi\"); } noinline fun greet(name: str): str { return name + \"!\"; }";
+    let program = bytecode::compile(&compile(source)).unwrap();
+    let mut vm = Vm::new(&program);
+
+    let name = program
+        .strings
+        .iter()
+        .position(|string| string == "hi")
+        .unwrap();
+    let result = vm
+        .call("greet", &[name.try_into().unwrap()])
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(vm.str(result), Some("hi!"));
+    assert_eq!(
+        vm.call("greet", &[-1]),
+        Err(VmError::InvalidString {
+            name: "greet".into()
+        })
+    );
+}
+
+#[test]
+fn test_bytecode_unsupported_main_type() {
+    let mir = compile("fun main(): str { return \"hi\"; }");
+
+    assert_eq!(
+        bytecode::compile(&mir).unwrap_err().to_string(),
+        "The bytecode backend cannot return str from main."
+    );
+}
@@ -0,0 +1,49 @@
+#![cfg(test)]
+
+mod common;
+
+use common::test_programs;
+use lang::{codegen::c, mir::MirModule};
+use std::{fs, path::Path, process::Command};
+
+fn run(exe: &Path, code: &str) -> i32 {
+    let c_path = exe.with_extension("c");
+    fs::write(&c_path, code).unwrap();
+
+    let status = Command::new("cc")
+        .args(["-std=c99", "-fsanitize=undefined", "-fno-sanitize-recover"])
+        .arg(&c_path)
+        .arg("-o")
+        .arg(exe)
+        .status()
+        .unwrap();
+
+    assert!(status.success(), "cc failed for {}", c_path.display());
+
+    Command::new(exe).status().unwrap().code().unwrap()
+}
+
+#[test]
+fn test_c() {
+    test_programs!("c", "programs/*.lang", emit_and_run);
+}
+
+#[test]
+fn test_c_wide() {
+    test_programs!("c", "programs/wide/*.lang", emit_and_run);
+}
+
+#[test]
+fn test_c_floats() {
+    test_programs!("c", "programs/floats/*.lang", emit_and_run);
+}
+
+#[test]
+fn test_c_strings() {
+    test_programs!("c", "programs/strings/*.lang", emit_and_run);
+}
+
+fn emit_and_run(exe: &Path, mir: &MirModule) -> (i32, String) {
+    let code = c::emit_module(mir).unwrap();
+    (run(exe, &code), code)
+}
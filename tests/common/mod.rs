@@ -0,0 +1,68 @@
+use lang::{
+    hir_to_mir::HirToMir, lexer::Lexer, mir::MirModule, mir_passes, parser::Parser,
+    type_resolver::TypeResolver,
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+pub fn compile(source: &str) -> MirModule {
+    let lexer = Lexer::new(source);
+    let mut hir = Parser::new(lexer).parse().unwrap();
+
+    TypeResolver::new().resolve(&mut hir).unwrap();
+
+    let mut mir = HirToMir::new().lower_module(hir);
+    mir_passes::run_passes(&mut mir);
+
+    mir
+}
+
+/// The exit code a test program declares on its first line, as `// exit: N`.
+pub fn expected_exit(source: &str) -> i32 {
+    source
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("// exit: "))
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+/// A path for `program`'s build artifacts in a per-process temp dir, without an extension.
+pub fn temp_path(backend: &str, program: &Path) -> PathBuf {
+    let dir = env::temp_dir().join(format!("lang-{backend}-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    dir.join(program.file_stem().unwrap())
+}
+
+/// Compiles every program matching `pattern` and hands a temp path and its MIR to `run`, which
+/// returns the program's exit code and the snapshot to record.
+macro_rules! test_programs {
+    ($backend:literal, $pattern:literal, |$temp:pat_param, $mir:ident| $run:expr) => {
+        insta::glob!($pattern, |path| {
+            let source = std::fs::read_to_string(path).unwrap();
+            let mir = $crate::common::compile(&source);
+            let temp = $crate::common::temp_path($backend, path);
+            let run =
+                |$temp: &std::path::Path, $mir: &lang::mir::MirModule| -> (i32, String) { $run };
+            let (exit, snapshot) = run(&temp, &mir);
+
+            assert_eq!(
+                exit,
+                $crate::common::expected_exit(&source),
+                "{}",
+                path.display()
+            );
+            insta::assert_snapshot!(snapshot);
+        });
+    };
+    ($backend:literal, $pattern:literal, $run:path) => {
+        $crate::common::test_programs!($backend, $pattern, |temp, mir| $run(temp, mir))
+    };
+}
+
+pub(crate) use test_programs;
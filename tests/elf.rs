@@ -0,0 +1,102 @@
+#![cfg(test)]
+
+mod common;
+
+use common::{compile, temp_path, test_programs};
+use lang::{
+    codegen::{elf, error::CodegenError},
+    int::IntType,
+    mir::MirType,
+};
+use std::{fs, path::Path, process::Command};
+
+fn link(inputs: &[&Path], exe: &Path) {
+    let status = Command::new("cc")
+        .args(inputs)
+        .arg("-o")
+        .arg(exe)
+        .status()
+        .unwrap();
+
+    assert!(status.success(), "cc failed for {}", exe.display());
+}
+
+fn readelf(obj: &Path) -> String {
+    let output = Command::new("readelf")
+        .args(["-S", "-s", "-r", "-W"])
+        .arg(obj)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_elf() {
+    test_programs!("elf", "programs/*.lang", |exe, mir| {
+        let obj = exe.with_extension("o");
+
+        fs::write(&obj, elf::emit_object(mir).unwrap()).unwrap();
+        link(&[&obj], exe);
+
+        let status = Command::new(exe).status().unwrap();
+        (status.code().unwrap(), readelf(&obj))
+    });
+}
+
+#[test]
+fn test_elf_link_with_c() {
+    let source = "
+        fun main(): num { return fib(10) + sum(1, 2, 3, 4, 5, 6, 7, 8); }
+        fun fib(n: num): num { if (n < 2) { return n; } return fib(n - 1) + fib(n - 2); }
+        noinline fun sum(a: num, b: num, c: num, d: num, e: num, f: num, g: num, h: num): num {
+            return a + b + c + d + e + f + g * 10 + h * 100;
+        }
+    ";
+
+    let mut mir = compile(source);
+    let main = mir.funs.iter_mut().find(|fun| fun.name == "main").unwrap();
+    main.name = "lang_main".into();
+
+    let exe = temp_path("elf", Path::new("interop"));
+    let obj = exe.with_extension("o");
+    let c = exe.with_extension("c");
+
+    fs::write(&obj, elf::emit_object(&mir).unwrap()).unwrap();
+    fs::write(
+        &c,
+        "int lang_main(void); int fib(int); int sum(int, int, int, int, int, int, int, int);\n\
+         int main(void) { return lang_main() == 55 + 891 && fib(12) == 144 && sum(0, 0, 0, 0, 0, 0, 1, 1) == 110 ? 0 : 1; }\n",
+    )
+    .unwrap();
+
+    link(&[&c, &obj], &exe);
+
+    assert_eq!(Command::new(&exe).status().unwrap().code(), Some(0));
+}
+
+#[test]
+fn test_elf_unsupported_type() {
+    let mir = compile("fun main() { wide(1); } noinline fun wide(x: i64): i64 { return x; }");
+
+    assert_eq!(
+        elf::emit_object(&mir),
+        Err(CodegenError::UnsupportedType {
+            backend: "ELF",
+            ty: MirType::Int(IntType::I64),
+        })
+    );
+
+    let mir = compile(
+        "fun main() { greet(\"hi\"); } noinline fun greet(name: str): str { return name; }",
+    );
+
+    assert_eq!(
+        elf::emit_object(&mir),
+        Err(CodegenError::UnsupportedType {
+            backend: "ELF",
+            ty: MirType::Str,
+        })
+    );
+}
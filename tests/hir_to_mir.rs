@@ -0,0 +1,25 @@
+#![cfg(test)]
+
+use insta::{assert_snapshot, glob};
+use lang::{
+    hir_to_mir::HirToMir, lexer::Lexer, mir_passes, parser::Parser, type_resolver::TypeResolver,
+};
+use std::fs;
+
+#[test]
+fn test_hir_to_mir() {
+    glob!("hir_to_mir/*.lang", |path| {
+        let source = fs::read_to_string(path).unwrap();
+        let lexer = Lexer::new(&source);
+        let mut hir = Parser::new(lexer).parse().unwrap();
+
+        TypeResolver::new().resolve(&mut hir).unwrap();
+
+        let mut mir = HirToMir::new().lower_module(hir);
+        let lowered = mir.to_string();
+
+        mir_passes::run_passes(&mut mir);
+
+        assert_snapshot!(format!("{lowered}\n== AFTER PASSES ==\n{mir}"));
+    });
+}
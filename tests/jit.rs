@@ -0,0 +1,147 @@
+#![cfg(test)]
+#![cfg(all(target_arch = "x86_64", target_os = "linux"))]
+
+mod common;
+
+use common::{compile, test_programs};
+use lang::{
+    bytecode::{self, error::VmError, vm::Vm},
+    jit::{Jit, error::JitError},
+    mir::MirModule,
+};
+use std::{fmt::Write as _, path::Path};
+
+fn interpret(mir: &MirModule, name: &str, args: &[i64]) -> Result<Option<i64>, VmError> {
+    Vm::new(&bytecode::compile(mir).unwrap()).call(name, args)
+}
+
+#[test]
+fn test_jit() {
+    test_programs!("jit", "programs/*.lang", compile_and_run);
+}
+
+/// Functions with wide integers are left to the VM.
+#[test]
+fn test_jit_wide() {
+    test_programs!("jit", "programs/wide/*.lang", compile_and_run);
+}
+
+/// Functions with floats are left to the VM.
+#[test]
+fn test_jit_floats() {
+    test_programs!("jit", "programs/floats/*.lang", compile_and_run);
+}
+
+/// Functions with strings are left to the VM.
+#[test]
+fn test_jit_strings() {
+    test_programs!("jit", "programs/strings/*.lang", compile_and_run);
+}
+
+fn compile_and_run(_: &Path, mir: &MirModule) -> (i32, String) {
+    let jit = Jit::new(mir).unwrap();
+    let result = jit.call("main", &[]);
+
+    assert_eq!(result, interpret(mir, "main", &[]));
+
+    let mut out = String::new();
+
+    for fun in &mir.funs {
+        let mode = if jit.is_compiled(&fun.name) {
+            "native"
+        } else {
+            "interpreted"
+        };
+
+        writeln!(out, "{}: {mode}", fun.name).unwrap();
+    }
+
+    writeln!(out, "result: {result:?}").unwrap();
+    (
+        i32::try_from(result.unwrap().unwrap_or(0) & 0xff).unwrap(),
+        out,
+    )
+}
+
+#[test]
+fn test_jit_args() {
+    let source = "
+        fun main(): num { return div(collatz(27), 2); }
+        noinline fun div(a: num, b: num): num { return a / b; }
+        noinline fun collatz(n: num): num {
+            let steps = 0;
+            while (n != 1) {
+                if (n - n / 2 * 2 == 0) { n = n / 2; } else { n = 3 * n + 1; }
+                steps = steps + 1;
+            }
+            return steps;
+        }
+    ";
+
+    let mir = compile(source);
+    let jit = Jit::new(&mir).unwrap();
+
+    assert!(jit.is_compiled("div"));
+    assert!(jit.is_compiled("collatz"));
+
+    for args in [
+        [7, 2],
+        [-7, 2],
+        [i32::MIN.into(), -1],
+        [i32::MAX.into(), -1],
+        [5, 0],
+    ] {
+        assert_eq!(jit.call("div", &args), interpret(&mir, "div", &args));
+    }
+
+    for n in 1..50 {
+        assert_eq!(jit.call("collatz", &[n]), interpret(&mir, "collatz", &[n]));
+    }
+
+    assert_eq!(
+        jit.call("div", &[1]),
+        Err(VmError::InvalidArgs {
+            name: "div".into(),
+            expected: 2,
+            found: 1
+        })
+    );
+
+    assert_eq!(
+        jit.call("missing", &[]),
+        Err(VmError::UndefinedFunction {
+            name: "missing".into()
+        })
+    );
+}
+
+#[test]
+fn test_jit_fallback() {
+    let source = "fun main(): num { return div(1, 0); } noinline fun div(a: num, b: num): num { return a / b; }";
+    let jit = Jit::new(&compile(source)).unwrap();
+
+    assert!(jit.is_compiled("main"));
+    assert_eq!(
+        jit.call("main", &[]),
+        Err(VmError::DivisionByZero { name: "div".into() })
+    );
+
+    let source = "fun main(): num { return forever(1); } fun forever(n: num): num { return forever(n + 1) + 1; }";
+    let jit = Jit::new(&compile(source)).unwrap();
+
+    assert!(jit.is_compiled("forever"));
+    assert_eq!(
+        jit.call("main", &[]),
+        Err(VmError::StackOverflow {
+            name: "forever".into()
+        })
+    );
+}
+
+#[test]
+fn test_jit_unsupported() {
+    assert!(matches!(
+        Jit::new(&compile("fun main(): str { return \"hi\"; }")),
+        Err(JitError::Unsupported { .. })
+    ));
+}
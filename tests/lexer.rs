@@ -1,5 +1,7 @@
+#![cfg(test)]
+
 use insta::{assert_yaml_snapshot, glob};
-use itertools::Itertools;
+use itertools::Itertools as _;
 use lang::lexer::Lexer;
 use std::fs;
 
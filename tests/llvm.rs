@@ -0,0 +1,44 @@
+#![cfg(test)]
+
+mod common;
+
+use common::test_programs;
+use lang::{codegen::llvm, mir::MirModule};
+use std::{fs, path::Path, process::Command};
+
+fn run(temp: &Path, ir: &str) -> i32 {
+    let ll_path = temp.with_extension("ll");
+    fs::write(&ll_path, ir).unwrap();
+
+    Command::new("lli")
+        .arg(&ll_path)
+        .status()
+        .unwrap()
+        .code()
+        .unwrap()
+}
+
+#[test]
+fn test_llvm() {
+    test_programs!("llvm", "programs/*.lang", emit_and_run);
+}
+
+#[test]
+fn test_llvm_wide() {
+    test_programs!("llvm", "programs/wide/*.lang", emit_and_run);
+}
+
+#[test]
+fn test_llvm_floats() {
+    test_programs!("llvm", "programs/floats/*.lang", emit_and_run);
+}
+
+#[test]
+fn test_llvm_strings() {
+    test_programs!("llvm", "programs/strings/*.lang", emit_and_run);
+}
+
+fn emit_and_run(temp: &Path, mir: &MirModule) -> (i32, String) {
+    let ir = llvm::emit_module(mir).unwrap();
+    (run(temp, &ir), ir)
+}
@@ -0,0 +1,87 @@
+#![cfg(test)]
+
+use insta::{assert_snapshot, glob};
+use lang::{
+    hir_to_mir::HirToMir,
+    lexer::Lexer,
+    mir::MirModule,
+    mir_analysis::{
+        call_graph::CallGraph, dominators::Dominators, interference::InterferenceGraph,
+        liveness::Liveness, loops::LoopForest,
+    },
+    mir_passes,
+    parser::Parser,
+    type_resolver::TypeResolver,
+};
+use std::{fmt::Write as _, fs, path::Path};
+
+fn lower(path: &Path) -> MirModule {
+    let source = fs::read_to_string(path).unwrap();
+    let lexer = Lexer::new(&source);
+    let mut hir = Parser::new(lexer).parse().unwrap();
+
+    TypeResolver::new().resolve(&mut hir).unwrap();
+
+    HirToMir::new().lower_module(hir)
+}
+
+fn compile(path: &Path) -> MirModule {
+    let mut mir = lower(path);
+    mir_passes::run_passes(&mut mir);
+
+    mir
+}
+
+#[test]
+fn test_loops() {
+    glob!("mir_analysis/loops/*.lang", |path| {
+        let mir = compile(path);
+        let mut output = String::new();
+
+        for fun in &mir.funs {
+            let loops = LoopForest::new(fun, &Dominators::new(fun));
+
+            writeln!(output, "{fun}{loops}").unwrap();
+        }
+
+        assert_snapshot!(output);
+    });
+}
+
+#[test]
+fn test_call_graph() {
+    glob!("mir_analysis/call_graph/*.lang", |path| {
+        let mut mir = lower(path);
+        let before = CallGraph::new(&mir);
+
+        mir_passes::run_passes(&mut mir);
+        mir_passes::remove_dead_funs(&mut mir);
+
+        let after = CallGraph::new(&mir);
+
+        assert_snapshot!(format!("{before}\n== AFTER PASSES ==\n{after}"));
+    });
+}
+
+#[test]
+fn test_liveness() {
+    glob!("mir_analysis/liveness/*.lang", |path| {
+        let mir = compile(path);
+        let mut output = String::new();
+
+        for fun in &mir.funs {
+            let liveness = Liveness::new(fun);
+            let interference = InterferenceGraph::new(fun, &liveness);
+
+            writeln!(
+                output,
+                "fun {}:\n{}{interference}",
+                fun.name,
+                liveness.annotate(fun)
+            )
+            .unwrap();
+        }
+
+        assert_snapshot!(output);
+    });
+}
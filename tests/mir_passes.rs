@@ -0,0 +1,38 @@
+#![cfg(test)]
+
+use insta::{assert_snapshot, glob};
+use lang::{
+    hir_to_mir::HirToMir, lexer::Lexer, mir::MirModule, mir_passes, parser::Parser,
+    type_resolver::TypeResolver,
+};
+use std::{fs, path::Path};
+
+fn compile(path: &Path) -> MirModule {
+    let source = fs::read_to_string(path).unwrap();
+    let lexer = Lexer::new(&source);
+    let mut hir = Parser::new(lexer).parse().unwrap();
+
+    TypeResolver::new().resolve(&mut hir).unwrap();
+
+    let mut mir = HirToMir::new().lower_module(hir);
+    mir_passes::run_passes(&mut mir);
+
+    mir
+}
+
+#[test]
+fn test_mir_passes() {
+    glob!("mir_passes/*.lang", |path| {
+        assert_snapshot!(compile(path).to_string());
+    });
+}
+
+#[test]
+fn test_out_of_ssa() {
+    glob!("mir_passes/out_of_ssa/*.lang", |path| {
+        let mut mir = compile(path);
+        mir_passes::out_of_ssa(&mut mir);
+
+        assert_snapshot!(mir.to_string());
+    });
+}
@@ -1,3 +1,5 @@
+#![cfg(test)]
+
 use insta::{assert_yaml_snapshot, glob, with_settings};
 use lang::{lexer::Lexer, parser::Parser};
 use std::fs;
@@ -0,0 +1,60 @@
+#![cfg(test)]
+
+use insta::{assert_snapshot, glob};
+use lang::{
+    hir_to_mir::HirToMir,
+    lexer::Lexer,
+    mir_analysis::{interference::InterferenceGraph, liveness::Liveness},
+    mir_passes,
+    parser::Parser,
+    regalloc::{
+        LinearScan, Location,
+        machine::{Machine, PhysReg},
+    },
+    type_resolver::TypeResolver,
+};
+use std::{fmt::Write as _, fs};
+
+fn machine() -> Machine {
+    Machine {
+        regs: vec!["r0".into(), "r1".into(), "r2".into()],
+        caller_saved: vec![PhysReg(0), PhysReg(1)],
+        callee_saved: vec![PhysReg(2)],
+        args: vec![PhysReg(0), PhysReg(1)],
+    }
+}
+
+#[test]
+fn test_regalloc() {
+    glob!("regalloc/*.lang", |path| {
+        let source = fs::read_to_string(path).unwrap();
+        let lexer = Lexer::new(&source);
+        let mut hir = Parser::new(lexer).parse().unwrap();
+
+        TypeResolver::new().resolve(&mut hir).unwrap();
+
+        let mut mir = HirToMir::new().lower_module(hir);
+        mir_passes::run_passes(&mut mir);
+        mir_passes::out_of_ssa(&mut mir);
+
+        let machine = machine();
+        let mut output = String::new();
+
+        for fun in &mir.funs {
+            let allocation = LinearScan::new(&machine).allocate(fun);
+            let interference = InterferenceGraph::new(fun, &Liveness::new(fun));
+
+            for a in interference.regs() {
+                for b in interference.neighbors(a) {
+                    if let Location::Reg(reg) = allocation.location(a) {
+                        assert_ne!(allocation.location(b), Location::Reg(reg), "{a} and {b}");
+                    }
+                }
+            }
+
+            writeln!(output, "{fun}{}", allocation.display(&machine)).unwrap();
+        }
+
+        assert_snapshot!(output);
+    });
+}
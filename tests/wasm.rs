@@ -0,0 +1,85 @@
+#![cfg(test)]
+
+mod common;
+
+use common::{compile, test_programs};
+use lang::{
+    codegen::{error::CodegenError, wasm},
+    mir::{MirModule, MirType},
+};
+use std::{fs, path::Path, process::Command};
+
+const RUNNER: &str = "
+const bytes = require('fs').readFileSync(process.argv[1]);
+
+if (!WebAssembly.validate(bytes)) {
+    process.exit(2);
+}
+
+WebAssembly.instantiate(bytes).then(({ instance }) => {
+    process.stdout.write(String(instance.exports.main() ?? 0));
+});
+";
+
+fn run(temp: &Path, bytes: &[u8]) -> i32 {
+    let wasm_path = temp.with_extension("wasm");
+    fs::write(&wasm_path, bytes).unwrap();
+
+    let output = Command::new("node")
+        .args(["-e", RUNNER])
+        .arg(&wasm_path)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "node failed for {}",
+        wasm_path.display()
+    );
+
+    let result = String::from_utf8(output.stdout).unwrap();
+    result.parse::<i32>().unwrap() & 0xff
+}
+
+#[test]
+fn test_wasm() {
+    test_programs!("wasm", "programs/*.lang", lower_and_run);
+}
+
+#[test]
+fn test_wasm_wide() {
+    test_programs!("wasm", "programs/wide/*.lang", lower_and_run);
+}
+
+#[test]
+fn test_wasm_floats() {
+    test_programs!("wasm", "programs/floats/*.lang", lower_and_run);
+}
+
+fn lower_and_run(temp: &Path, mir: &MirModule) -> (i32, String) {
+    let module = wasm::lower_module(mir).unwrap();
+    (run(temp, &module.encode()), module.to_string())
+}
+
+#[test]
+fn test_wasm_keeps_uncalled_functions() {
+    let source = "fun helper(a: num): num { return a + 1; } fun main(): num { return 0; }";
+    let module = wasm::lower_module(&compile(source)).unwrap();
+
+    assert!(module.funcs.iter().any(|func| func.name == "helper"));
+}
+
+#[test]
+fn test_wasm_unsupported_type() {
+    let mir = compile(
+        "fun main() { greet(\"hi\"); } noinline fun greet(name: str): str { return name; }",
+    );
+
+    assert_eq!(
+        wasm::lower_module(&mir),
+        Err(CodegenError::UnsupportedType {
+            backend: "wasm",
+            ty: MirType::Str,
+        })
+    );
+}
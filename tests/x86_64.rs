@@ -0,0 +1,108 @@
+#![cfg(test)]
+
+mod common;
+
+use common::{compile, temp_path, test_programs};
+use lang::{
+    codegen::{error::CodegenError, x86_64},
+    mir::{MirModule, MirType},
+};
+use std::{fs, path::Path, process::Command};
+
+fn run(exe: &Path, asm: &str) -> i32 {
+    let asm_path = exe.with_extension("s");
+    fs::write(&asm_path, asm).unwrap();
+
+    let status = Command::new("cc")
+        .arg(&asm_path)
+        .arg("-o")
+        .arg(exe)
+        .status()
+        .unwrap();
+
+    assert!(status.success(), "cc failed for {}", asm_path.display());
+
+    Command::new(exe).status().unwrap().code().unwrap()
+}
+
+#[test]
+fn test_x86_64() {
+    test_programs!("x86_64", "programs/*.lang", emit_and_run);
+}
+
+#[test]
+fn test_x86_64_wide() {
+    test_programs!("x86_64", "programs/wide/*.lang", emit_and_run);
+}
+
+fn emit_and_run(exe: &Path, mir: &MirModule) -> (i32, String) {
+    let asm = x86_64::emit_module(mir).unwrap();
+    (run(exe, &asm), asm)
+}
+
+#[test]
+fn test_x86_64_unsupported_type() {
+    let mir =
+        compile("fun main() { half(1.0); } noinline fun half(x: float): float { return x / 2.0; }");
+
+    assert_eq!(
+        x86_64::emit_module(&mir),
+        Err(CodegenError::UnsupportedType {
+            backend: "x86-64",
+            ty: MirType::Float,
+        })
+    );
+
+    let mir = compile(
+        "fun main() { greet(\"hi\"); } noinline fun greet(name: str): str { return name; }",
+    );
+
+    assert_eq!(
+        x86_64::emit_module(&mir),
+        Err(CodegenError::UnsupportedType {
+            backend: "x86-64",
+            ty: MirType::Str,
+        })
+    );
+}
+
+#[test]
+fn test_x86_64_bool_abi() {
+    let source = "
+        fun main(): num { return 0; }
+        noinline fun flip(b: bool): bool { return !b; }
+        noinline fun pick(b: bool): num { if (b) { return 1; } return 2; }
+        noinline fun both(a: bool, b: bool, c: bool, d: bool, e: bool, f: bool, g: bool): bool {
+            return a and b and c and d and e and f and g;
+        }
+    ";
+
+    let exe = temp_path("x86_64", Path::new("bool_abi"));
+    let asm_path = exe.with_extension("s");
+    let c_path = exe.with_extension("c");
+
+    let mut mir = compile(source);
+    mir.funs.retain(|fun| fun.name != "main");
+
+    fs::write(&asm_path, x86_64::emit_module(&mir).unwrap()).unwrap();
+
+    // Only the low byte of a bool is defined, so 0x100 is false.
+    fs::write(
+        &c_path,
+        "_Bool flip(unsigned); int pick(unsigned); _Bool both(unsigned, _Bool, _Bool, _Bool, _Bool, _Bool, unsigned long);\n\
+         int main(void) { return flip(0x100) && !flip(0x101) && pick(0x100) == 2 && !both(0x100, 1, 1, 1, 1, 1, 1) \
+         && both(1, 1, 1, 1, 1, 1, 0x101) && !both(1, 1, 1, 1, 1, 1, 0x100) ? 0 : 1; }\n",
+    )
+    .unwrap();
+
+    let status = Command::new("cc")
+        .arg(&c_path)
+        .arg(&asm_path)
+        .arg("-o")
+        .arg(&exe)
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    assert_eq!(Command::new(&exe).status().unwrap().code(), Some(0));
+}